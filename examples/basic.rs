@@ -2,7 +2,7 @@ use anyhow::Result;
 use tokio::time;
 
 use webdriverbidi::model::browsing_context::{
-    GetTreeParameters, NavigateParameters, ReadinessState,
+    BrowsingContext, GetTreeParameters, NavigateParameters, ReadinessState,
 };
 use webdriverbidi::session::WebDriverBiDiSession;
 use webdriverbidi::webdriver::capabilities::CapabilitiesRequest;
@@ -23,8 +23,11 @@ pub async fn init_session() -> Result<WebDriverBiDiSession> {
 }
 
 /// Retrieve the browsing context at the specified index.
-pub async fn get_context(session: &mut WebDriverBiDiSession, idx: usize) -> Result<String> {
-    let get_tree_params = GetTreeParameters::new(None, None);
+pub async fn get_context(
+    session: &mut WebDriverBiDiSession,
+    idx: usize,
+) -> Result<BrowsingContext> {
+    let get_tree_params = GetTreeParameters::builder().build();
     let get_tree_rslt = session.browsing_context_get_tree(get_tree_params).await?;
     if let Some(context_entry) = get_tree_rslt.contexts.get(idx) {
         Ok(context_entry.context.clone())
@@ -38,8 +41,14 @@ pub async fn get_context(session: &mut WebDriverBiDiSession, idx: usize) -> Resu
 }
 
 /// Navigate to the specified URL and wait for the document to completely load.
-pub async fn navigate(session: &mut WebDriverBiDiSession, ctx: String, url: String) -> Result<()> {
-    let navigate_params = NavigateParameters::new(ctx, url, Some(ReadinessState::Complete));
+pub async fn navigate(
+    session: &mut WebDriverBiDiSession,
+    ctx: BrowsingContext,
+    url: String,
+) -> Result<()> {
+    let navigate_params = NavigateParameters::builder(ctx, url)
+        .wait(ReadinessState::Complete)
+        .build();
     session.browsing_context_navigate(navigate_params).await?;
     Ok(())
 }
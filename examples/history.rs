@@ -2,7 +2,8 @@ use anyhow::Result;
 use tokio::time;
 
 use webdriverbidi::model::browsing_context::{
-    GetTreeParameters, NavigateParameters, ReadinessState, TraverseHistoryParameters,
+    BrowsingContext, GetTreeParameters, NavigateParameters, ReadinessState,
+    TraverseHistoryParameters,
 };
 use webdriverbidi::session::WebDriverBiDiSession;
 use webdriverbidi::webdriver::capabilities::CapabilitiesRequest;
@@ -23,7 +24,10 @@ pub async fn init_session() -> Result<WebDriverBiDiSession> {
 }
 
 /// Retrieve the browsing context at the specified index.
-pub async fn get_context(session: &mut WebDriverBiDiSession, idx: usize) -> Result<String> {
+pub async fn get_context(
+    session: &mut WebDriverBiDiSession,
+    idx: usize,
+) -> Result<BrowsingContext> {
     let get_tree_params = GetTreeParameters::new(None, None);
     let get_tree_rslt = session.browsing_context_get_tree(get_tree_params).await?;
     if let Some(context_entry) = get_tree_rslt.contexts.get(idx) {
@@ -38,7 +42,11 @@ pub async fn get_context(session: &mut WebDriverBiDiSession, idx: usize) -> Resu
 }
 
 /// Navigate to the specified URL and wait for the document to completely load.
-pub async fn navigate(session: &mut WebDriverBiDiSession, ctx: String, url: String) -> Result<()> {
+pub async fn navigate(
+    session: &mut WebDriverBiDiSession,
+    ctx: BrowsingContext,
+    url: String,
+) -> Result<()> {
     let navigate_params = NavigateParameters::new(ctx, url, Some(ReadinessState::Complete));
     session.browsing_context_navigate(navigate_params).await?;
     Ok(())
@@ -47,7 +55,7 @@ pub async fn navigate(session: &mut WebDriverBiDiSession, ctx: String, url: Stri
 /// Navigate back or forward in the browsing history based on the provided delta value.
 async fn traverse_history(
     session: &mut WebDriverBiDiSession,
-    ctx: String,
+    ctx: BrowsingContext,
     delta: i64,
 ) -> Result<()> {
     let traverse_history_params = TraverseHistoryParameters::new(ctx, delta);
@@ -13,6 +13,7 @@ use tokio_tungstenite::tungstenite::protocol::Message;
 use tokio_tungstenite::{MaybeTlsStream, WebSocketStream};
 
 use super::error::CommandError;
+use super::validation;
 
 const COMMAND_ID_KEY: &str = "id";
 const RESULT_KEY: &str = "result";
@@ -23,10 +24,14 @@ const RECEIVER_TIMEOUT: u64 = 60;
 ///
 /// This function serializes the given command, sends it over the provided WebSocket stream,
 /// and waits for a response. Timesout if no response is received within 60 seconds.
+///
+/// When `validate` is set, debug builds check the serialized command against the
+/// protocol constraints before sending it.
 pub async fn send_command<T: Serialize, U: DeserializeOwned>(
     websocket_stream: Arc<Mutex<WebSocketStream<MaybeTlsStream<TcpStream>>>>,
     pending_commands: Arc<Mutex<HashMap<u64, oneshot::Sender<Value>>>>,
     command: T,
+    validate: bool,
 ) -> Result<U, CommandError> {
     let value = serde_json::to_value(command).map_err(|e| {
         error!("Serialization error: {:?}", e);
@@ -34,6 +39,13 @@ pub async fn send_command<T: Serialize, U: DeserializeOwned>(
     })?;
     debug!("Serialized command: {:?}", value);
 
+    if cfg!(debug_assertions) && validate {
+        validation::validate_command(&value).map_err(|e| {
+            error!("Command validation error: {}", e);
+            e
+        })?;
+    }
+
     let command_id = value
         .get(COMMAND_ID_KEY)
         .and_then(|id| id.as_u64())
@@ -58,7 +70,7 @@ pub async fn send_command<T: Serialize, U: DeserializeOwned>(
         if let Err(e) = websocket_stream.send(message).await {
             error!("Error sending message: {:?}", e);
             pending_commands.lock().await.remove(&command_id);
-            return Err(CommandError::WebSocketSendError(e));
+            return Err(CommandError::WebSocketSendError(Box::new(e)));
         }
     }
 
@@ -9,6 +9,15 @@ use crate::model::emulation::*;
 use crate::model::result::EmptyResult;
 use crate::session::WebDriverBiDiSession;
 
+// https://w3c.github.io/webdriver-bidi/#command-emulation-setForcedColorsModeThemeOverride
+define_command!(
+    SetForcedColorsModeThemeOverrideCommand,
+    SetForcedColorsModeThemeOverride,
+    SetForcedColorsModeThemeOverrideParameters,
+    set_forced_colors_mode_theme_override,
+    EmptyResult
+);
+
 // https://w3c.github.io/webdriver-bidi/#command-emulation-setGeolocationOverride
 define_command!(
     SetGeolocationOverrideCommand,
@@ -27,6 +36,15 @@ define_command!(
     EmptyResult
 );
 
+// https://w3c.github.io/webdriver-bidi/#command-emulation-setNetworkConditions
+define_command!(
+    SetNetworkConditionsCommand,
+    SetNetworkConditions,
+    SetNetworkConditionsParameters,
+    set_network_conditions,
+    EmptyResult
+);
+
 // https://w3c.github.io/webdriver-bidi/#command-emulation-setScreenOrientationOverride
 define_command!(
     SetScreenOrientationOverrideCommand,
@@ -36,6 +54,24 @@ define_command!(
     EmptyResult
 );
 
+// https://w3c.github.io/webdriver-bidi/#command-emulation-setScreenSettingsOverride
+define_command!(
+    SetScreenSettingsOverrideCommand,
+    SetScreenSettingsOverride,
+    SetScreenSettingsOverrideParameters,
+    set_screen_settings_override,
+    EmptyResult
+);
+
+// https://w3c.github.io/webdriver-bidi/#command-emulation-setScriptingEnabled
+define_command!(
+    SetScriptingEnabledCommand,
+    SetScriptingEnabled,
+    SetScriptingEnabledParameters,
+    set_scripting_enabled,
+    EmptyResult
+);
+
 // https://w3c.github.io/webdriver-bidi/#command-emulation-setTimezoneOverride
 define_command!(
     SetTimezoneOverrideCommand,
@@ -44,3 +80,21 @@ define_command!(
     set_timezone_override,
     EmptyResult
 );
+
+// https://w3c.github.io/webdriver-bidi/#command-emulation-setTouchOverride
+define_command!(
+    SetTouchOverrideCommand,
+    SetTouchOverride,
+    SetTouchOverrideParameters,
+    set_touch_override,
+    EmptyResult
+);
+
+// https://w3c.github.io/webdriver-bidi/#command-emulation-setUserAgentOverride
+define_command!(
+    SetUserAgentOverrideCommand,
+    SetUserAgentOverride,
+    SetUserAgentOverrideParameters,
+    set_user_agent_override,
+    EmptyResult
+);
@@ -15,7 +15,7 @@ pub enum CommandError {
 
     /// Error when sending data over a WebSocket.
     #[error("WebSocket send error: {0}.")]
-    WebSocketSendError(#[from] tungstenite::Error),
+    WebSocketSendError(Box<tungstenite::Error>),
 
     /// Missing result field in the response.
     #[error("Missing result field.")]
@@ -25,6 +25,10 @@ pub enum CommandError {
     #[error("Command returned error: {0}")]
     Error(serde_json::Value),
 
+    /// The command parameters violate a constraint of the protocol definition.
+    #[error("Invalid command parameters: {0}.")]
+    InvalidParameters(String),
+
     /// Timeout when waiting for a receiver response
     #[error("Timeout waiting for receiver response")]
     TimeoutError,
@@ -38,6 +42,14 @@ pub enum CommandError {
     Other(String),
 }
 
+// The WebSocket error is boxed, it would otherwise make every result carrying a
+// `CommandError` or a `HelperError` several times larger than its success value
+impl From<tungstenite::Error> for CommandError {
+    fn from(e: tungstenite::Error) -> Self {
+        CommandError::WebSocketSendError(Box::new(e))
+    }
+}
+
 /// Errors that can occur when starting a WebDriver session.
 #[derive(Error, Debug)]
 pub enum SessionError {
@@ -53,3 +65,23 @@ pub enum SessionError {
     #[error("Session error: {0}.")]
     Other(String),
 }
+
+/// Errors that can occur when using the higher-level helpers.
+#[derive(Error, Debug)]
+pub enum HelperError {
+    /// A command sent by the helper failed.
+    #[error("Command error: {0}")]
+    CommandError(#[from] CommandError),
+
+    /// An event received by the helper couldn't be deserialized.
+    #[error("Event deserialization error: {0}.")]
+    SerdeError(#[from] serde_json::Error),
+
+    /// The awaited condition wasn't met before the timeout elapsed.
+    #[error("Timed out waiting for {0}.")]
+    Timeout(String),
+
+    /// Other helper errors.
+    #[error("Helper error: {0}.")]
+    Other(String),
+}
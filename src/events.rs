@@ -3,7 +3,7 @@ use std::fmt;
 use std::str::FromStr;
 
 /// Represents the standard WebDriver BiDi events.
-#[derive(Hash, Eq, PartialEq, Debug)]
+#[derive(Hash, Eq, PartialEq, Debug, Clone, Copy)]
 pub enum EventType {
     BrowsingContextContextCreated,
     BrowsingContextContextDestroyed,
@@ -30,6 +30,37 @@ pub enum EventType {
     LogEntryAdded,
 }
 
+impl EventType {
+    /// Returns the name of the event, as used in `session.subscribe`.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            EventType::BrowsingContextContextCreated => "browsingContext.contextCreated",
+            EventType::BrowsingContextContextDestroyed => "browsingContext.contextDestroyed",
+            EventType::BrowsingContextNavigationStarted => "browsingContext.navigationStarted",
+            EventType::BrowsingContextFragmentNavigated => "browsingContext.fragmentNavigated",
+            EventType::BrowsingContextHistoryUpdated => "browsingContext.historyUpdated",
+            EventType::BrowsingContextDomContentLoaded => "browsingContext.domContentLoaded",
+            EventType::BrowsingContextLoad => "browsingContext.load",
+            EventType::BrowsingContextDownloadWillBegin => "browsingContext.downloadWillBegin",
+            EventType::BrowsingContextDownloadEnd => "browsingContext.downloadEnd",
+            EventType::BrowsingContextNavigationAborted => "browsingContext.navigationAborted",
+            EventType::BrowsingContextNavigationCommitted => "browsingContext.navigationCommitted",
+            EventType::BrowsingContextNavigationFailed => "browsingContext.navigationFailed",
+            EventType::BrowsingContextUserPromptClosed => "browsingContext.userPromptClosed",
+            EventType::BrowsingContextUserPromptOpened => "browsingContext.userPromptOpened",
+            EventType::NetworkAuthRequired => "network.authRequired",
+            EventType::NetworkBeforeRequestSent => "network.beforeRequestSent",
+            EventType::NetworkFetchError => "network.fetchError",
+            EventType::NetworkResponseCompleted => "network.responseCompleted",
+            EventType::NetworkResponseStarted => "network.responseStarted",
+            EventType::ScriptMessage => "script.message",
+            EventType::ScriptRealmCreated => "script.realmCreated",
+            EventType::ScriptRealmDestroyed => "script.realmDestroyed",
+            EventType::LogEntryAdded => "log.entryAdded",
+        }
+    }
+}
+
 /// Simple error type for parsing EventType.
 #[derive(Debug)]
 pub struct ParseEventTypeError;
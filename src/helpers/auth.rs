@@ -0,0 +1,348 @@
+use std::collections::HashSet;
+use std::sync::Arc;
+
+use log::debug;
+use reqwest::Url;
+use serde_json::Value;
+use tokio::sync::{Mutex, MutexGuard, mpsc};
+use tokio::task::JoinHandle;
+
+use crate::error::HelperError;
+use crate::events::EventType;
+use crate::helpers::url_pattern;
+use crate::model::browsing_context::BrowsingContext;
+use crate::model::network::{
+    AddInterceptParameters, AuthChallenge, AuthCredentials, AuthRequiredParameters,
+    ContinueWithAuthCredentials, ContinueWithAuthNoCredentials, ContinueWithAuthOption,
+    ContinueWithAuthParameters, Intercept, InterceptPhase, NoCredentialsAction,
+    RemoveInterceptParameters, Request, UrlPattern,
+};
+use crate::model::session::{SubscriptionRequest, UnsubscribeByIDRequest, UnsubscribeParameters};
+use crate::session::WebDriverBiDiSession;
+
+// --------------------------------------------------
+
+/// The URLs a credential applies to.
+#[derive(Debug, Clone)]
+pub enum CredentialScope {
+    /// The URLs of an origin, such as `https://example.com:8443`.
+    Origin(String),
+    /// The URLs matching a pattern.
+    Pattern(UrlPattern),
+}
+
+impl CredentialScope {
+    /// Return whether the scope contains the URL.
+    pub fn matches(&self, url: &Url) -> bool {
+        match self {
+            CredentialScope::Origin(origin) => {
+                Url::parse(origin).is_ok_and(|origin| origin.origin() == url.origin())
+            }
+            CredentialScope::Pattern(pattern) => url_pattern::matches(pattern, url),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+struct StoredCredential {
+    scope: CredentialScope,
+    realm: Option<String>,
+    username: String,
+    password: String,
+}
+
+/// Credentials keyed by origin or URL pattern and realm.
+///
+/// When several credentials match a challenge, the first one added is used.
+#[derive(Debug, Clone, Default)]
+pub struct CredentialStore {
+    credentials: Vec<StoredCredential>,
+}
+
+impl CredentialStore {
+    /// Create an empty store.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add a credential.
+    ///
+    /// # Arguments
+    ///
+    /// * `scope` - The URLs the credential applies to.
+    /// * `realm` - The realm of the challenges it answers, any realm if `None`.
+    /// * `username` - The user name.
+    /// * `password` - The password.
+    pub fn add(
+        &mut self,
+        scope: CredentialScope,
+        realm: Option<&str>,
+        username: impl Into<String>,
+        password: impl Into<String>,
+    ) {
+        self.credentials.push(StoredCredential {
+            scope,
+            realm: realm.map(str::to_string),
+            username: username.into(),
+            password: password.into(),
+        });
+    }
+
+    /// Remove all the credentials.
+    pub fn clear(&mut self) {
+        self.credentials.clear();
+    }
+
+    /// Return the credentials answering the challenges of a request to the URL.
+    pub fn find(&self, url: &str, challenges: &[AuthChallenge]) -> Option<AuthCredentials> {
+        let url = Url::parse(url).ok()?;
+        self.credentials
+            .iter()
+            .find(|credential| {
+                credential.scope.matches(&url)
+                    && credential.realm.as_ref().is_none_or(|realm| {
+                        challenges.iter().any(|challenge| &challenge.realm == realm)
+                    })
+            })
+            .map(|credential| {
+                AuthCredentials::new(credential.username.clone(), credential.password.clone())
+            })
+    }
+}
+
+/// How an authentication challenge was answered.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum AuthAnswer {
+    /// The credentials of the user were provided.
+    Credentials { username: String },
+    /// No credentials matched, the browser's default behavior was used.
+    Default,
+    /// No credentials matched, the authentication was canceled.
+    Cancel,
+}
+
+/// An authentication challenge answered by an `AuthResponder`.
+#[derive(Debug, Clone)]
+pub struct AnsweredChallenge {
+    pub request: Request,
+    pub context: Option<BrowsingContext>,
+    pub url: String,
+    pub challenges: Vec<AuthChallenge>,
+    pub answer: AuthAnswer,
+}
+
+#[derive(Debug)]
+struct ResponderState {
+    store: CredentialStore,
+    fallback: AuthAnswer,
+    answered: Vec<AnsweredChallenge>,
+    // The requests already given credentials, answered with the fallback when the
+    // credentials are rejected instead of being retried forever
+    attempted: HashSet<Request>,
+}
+
+/// Answers the HTTP authentication challenges of a session from a `CredentialStore`.
+///
+/// The responder adds an `authRequired` intercept, so that the requests challenged
+/// for Basic or Digest authentication wait for an answer. A challenge matching no
+/// credentials, or repeated because the credentials were rejected, is answered with
+/// the fallback action.
+pub struct AuthResponder {
+    session: WebDriverBiDiSession,
+    subscription: Option<String>,
+    intercept: Option<Intercept>,
+    state: Arc<Mutex<ResponderState>>,
+    task: JoinHandle<()>,
+}
+
+impl AuthResponder {
+    /// Start answering the authentication challenges.
+    ///
+    /// # Arguments
+    ///
+    /// * `session` - The session to answer the challenges of.
+    /// * `store` - The credentials to answer the challenges with.
+    /// * `fallback` - The action used when no credentials match.
+    pub async fn new(
+        session: &mut WebDriverBiDiSession,
+        store: CredentialStore,
+        fallback: NoCredentialsAction,
+    ) -> Result<Self, HelperError> {
+        let fallback = match fallback {
+            NoCredentialsAction::Default => AuthAnswer::Default,
+            NoCredentialsAction::Cancel => AuthAnswer::Cancel,
+            NoCredentialsAction::Unknown(action) => {
+                return Err(HelperError::Other(format!(
+                    "unsupported fallback action {action}"
+                )));
+            }
+        };
+
+        let event = EventType::NetworkAuthRequired;
+        let receiver = session.add_event_listener(vec![event]).await;
+        let subscription = session
+            .session_subscribe(SubscriptionRequest::new(
+                vec![event.as_str().to_string()],
+                None,
+                None,
+            ))
+            .await?
+            .subscription;
+        let intercept = session
+            .network_add_intercept(AddInterceptParameters::new(
+                vec![InterceptPhase::AuthRequired],
+                None,
+                None,
+            ))
+            .await?
+            .intercept;
+
+        let state = Arc::new(Mutex::new(ResponderState {
+            store,
+            fallback,
+            answered: Vec::new(),
+            attempted: HashSet::new(),
+        }));
+        let task = tokio::spawn(respond(
+            receiver,
+            session.clone(),
+            intercept.clone(),
+            state.clone(),
+        ));
+
+        Ok(Self {
+            session: session.clone(),
+            subscription,
+            intercept: Some(intercept),
+            state,
+            task,
+        })
+    }
+
+    /// Return the credential store, to add or remove credentials.
+    pub async fn store(&self) -> CredentialStoreGuard<'_> {
+        CredentialStoreGuard(self.state.lock().await)
+    }
+
+    /// Return the challenges answered so far.
+    pub async fn answered(&self) -> Vec<AnsweredChallenge> {
+        self.state.lock().await.answered.clone()
+    }
+
+    /// Stop answering the challenges, removing the intercept.
+    pub async fn close(mut self) -> Result<(), HelperError> {
+        self.task.abort();
+        if let Some(intercept) = self.intercept.take() {
+            self.session
+                .network_remove_intercept(RemoveInterceptParameters::new(intercept))
+                .await?;
+        }
+        if let Some(subscription) = self.subscription.take() {
+            self.session
+                .session_unsubscribe(UnsubscribeParameters::UnsubscribeByIDRequest(
+                    UnsubscribeByIDRequest::new(vec![subscription]),
+                ))
+                .await?;
+        }
+        Ok(())
+    }
+}
+
+impl Drop for AuthResponder {
+    fn drop(&mut self) {
+        self.task.abort();
+    }
+}
+
+/// A lock on the credential store of an `AuthResponder`.
+pub struct CredentialStoreGuard<'a>(MutexGuard<'a, ResponderState>);
+
+impl std::ops::Deref for CredentialStoreGuard<'_> {
+    type Target = CredentialStore;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0.store
+    }
+}
+
+impl std::ops::DerefMut for CredentialStoreGuard<'_> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.0.store
+    }
+}
+
+async fn respond(
+    mut receiver: mpsc::UnboundedReceiver<Value>,
+    mut session: WebDriverBiDiSession,
+    intercept: Intercept,
+    state: Arc<Mutex<ResponderState>>,
+) {
+    while let Some(mut event) = receiver.recv().await {
+        let method = event["method"].as_str().unwrap_or_default().to_string();
+        let params = match serde_json::from_value::<AuthRequiredParameters>(event["params"].take())
+        {
+            Ok(params) => params,
+            Err(e) => {
+                debug!("Ignoring malformed {method} event: {e}");
+                continue;
+            }
+        };
+        let blocked_by_responder = params.base.is_blocked
+            && params
+                .base
+                .intercepts
+                .as_ref()
+                .is_some_and(|intercepts| intercepts.contains(&intercept));
+        if !blocked_by_responder {
+            continue;
+        }
+
+        let context = params.base.context;
+        let request = params.base.request.request;
+        let url = params.base.request.url;
+        let challenges = params.response.auth_challenges.unwrap_or_default();
+        let (answer, option) = {
+            let mut state = state.lock().await;
+            let credentials = match state.attempted.insert(request.clone()) {
+                true => state.store.find(&url, &challenges),
+                false => None,
+            };
+            match credentials {
+                Some(credentials) => (
+                    AuthAnswer::Credentials {
+                        username: credentials.username.clone(),
+                    },
+                    ContinueWithAuthOption::Credentials(ContinueWithAuthCredentials::new(
+                        "provideCredentials".to_string(),
+                        credentials,
+                    )),
+                ),
+                None => {
+                    let action = match state.fallback {
+                        AuthAnswer::Cancel => NoCredentialsAction::Cancel,
+                        _ => NoCredentialsAction::Default,
+                    };
+                    (
+                        state.fallback.clone(),
+                        ContinueWithAuthOption::NoCredentials(ContinueWithAuthNoCredentials::new(
+                            action,
+                        )),
+                    )
+                }
+            }
+        };
+
+        let params = ContinueWithAuthParameters::new(request.clone(), Some(option));
+        if let Err(e) = session.network_continue_with_auth(params).await {
+            debug!("Failed to answer the challenge of request {request}: {e}");
+            continue;
+        }
+        state.lock().await.answered.push(AnsweredChallenge {
+            request,
+            context,
+            url,
+            challenges,
+            answer,
+        });
+    }
+}
@@ -0,0 +1,228 @@
+use std::collections::{HashMap, HashSet};
+use std::sync::Arc;
+
+use log::debug;
+use reqwest::Url;
+use tokio::sync::Mutex;
+
+use crate::error::HelperError;
+use crate::helpers::context_tree::{ContextTree, TreeReader};
+use crate::helpers::routes::{RouteAction, RouteGuard};
+use crate::model::browser::UserContext;
+use crate::model::browsing_context::{BrowsingContext, GetTreeParameters};
+use crate::model::network::BaseParameters;
+use crate::session::WebDriverBiDiSession;
+
+// --------------------------------------------------
+
+/// Why a request was blocked.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum BlockReason {
+    /// The destination of the request, such as `image` or `font`, is blocked.
+    Destination(String),
+    /// The host of the request is, or is a subdomain of, a blocked host.
+    Host(String),
+}
+
+/// The categories of subresources and the hosts blocked by a `ResourceBlocker`.
+#[derive(Debug, Clone, Default)]
+pub struct BlockList {
+    pub destinations: HashSet<String>,
+    pub hosts: Vec<String>,
+}
+
+impl BlockList {
+    /// Create an empty list, blocking nothing.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Block the requests with the given `RequestData.destination`.
+    pub fn destination(mut self, destination: impl Into<String>) -> Self {
+        self.destinations.insert(destination.into());
+        self
+    }
+
+    /// Block the images.
+    pub fn images(self) -> Self {
+        self.destination("image")
+    }
+
+    /// Block the fonts.
+    pub fn fonts(self) -> Self {
+        self.destination("font")
+    }
+
+    /// Block the audio, video and text tracks.
+    pub fn media(self) -> Self {
+        self.destination("audio")
+            .destination("video")
+            .destination("track")
+    }
+
+    /// Block the stylesheets.
+    pub fn stylesheets(self) -> Self {
+        self.destination("style")
+    }
+
+    /// Block the requests to a host and its subdomains, such as an analytics domain.
+    pub fn host(mut self, host: impl Into<String>) -> Self {
+        self.hosts.push(host.into().to_lowercase());
+        self
+    }
+
+    /// Return why a request is blocked, `None` if it isn't.
+    ///
+    /// The hosts are checked before the destinations.
+    pub fn reason(&self, url: &str, destination: &str) -> Option<BlockReason> {
+        let host = Url::parse(url)
+            .ok()
+            .and_then(|url| url.host_str().map(str::to_string));
+        if let Some(host) = host {
+            let blocked = self.hosts.iter().find(|blocked| {
+                host == **blocked
+                    || host
+                        .strip_suffix(blocked.as_str())
+                        .is_some_and(|prefix| prefix.ends_with('.'))
+            });
+            if let Some(blocked) = blocked {
+                return Some(BlockReason::Host(blocked.clone()));
+            }
+        }
+        self.destinations
+            .contains(destination)
+            .then(|| BlockReason::Destination(destination.to_string()))
+    }
+}
+
+/// Fails the requests of a session matching a `BlockList`.
+///
+/// The blocker is a route of `WebDriverBiDiSession::route` for every URL. The
+/// requests it doesn't block fall back to the routes added before it, such as
+/// another blocker for other user contexts, and are continued if there's none. The
+/// blocked requests are counted by reason.
+pub struct ResourceBlocker {
+    blocked: Arc<Mutex<HashMap<BlockReason, usize>>>,
+    guard: Option<RouteGuard>,
+    tree: Option<ContextTree>,
+}
+
+impl ResourceBlocker {
+    /// Start blocking the requests.
+    ///
+    /// # Arguments
+    ///
+    /// * `session` - The session to block the requests of.
+    /// * `block_list` - The requests to block.
+    /// * `user_contexts` - The user contexts to block the requests of, all of them if
+    ///   `None`.
+    pub async fn new(
+        session: &mut WebDriverBiDiSession,
+        block_list: BlockList,
+        user_contexts: Option<Vec<UserContext>>,
+    ) -> Result<Self, HelperError> {
+        // The user context of a request is the one of its browsing context, looked
+        // up in a mirror of the context tree, or asked for when the mirror doesn't
+        // know the context yet
+        let (tree, scope) = match user_contexts {
+            Some(user_contexts) => {
+                let tree = ContextTree::new(session).await?;
+                let reader = tree.reader();
+                (Some(tree), Some((user_contexts, reader)))
+            }
+            None => (None, None),
+        };
+
+        let blocked = Arc::new(Mutex::new(HashMap::new()));
+        let counts = blocked.clone();
+        let block_list = Arc::new(block_list);
+        let route_session = session.clone();
+        let guard = session
+            .route_patterns(vec![], move |params| {
+                let mut session = route_session.clone();
+                let blocked = counts.clone();
+                let block_list = block_list.clone();
+                let scope = scope.clone();
+                async move { block(&mut session, &block_list, scope, &blocked, params).await }
+            })
+            .await?;
+
+        Ok(Self {
+            blocked,
+            guard: Some(guard),
+            tree,
+        })
+    }
+
+    /// Return the number of blocked requests by reason.
+    pub async fn blocked(&self) -> HashMap<BlockReason, usize> {
+        self.blocked.lock().await.clone()
+    }
+
+    /// Return the total number of blocked requests.
+    pub async fn blocked_total(&self) -> usize {
+        self.blocked.lock().await.values().sum()
+    }
+
+    /// Stop blocking the requests, removing the route.
+    pub async fn close(mut self) -> Result<(), HelperError> {
+        if let Some(guard) = self.guard.take() {
+            guard.remove().await?;
+        }
+        if let Some(tree) = self.tree.take() {
+            tree.close().await?;
+        }
+        Ok(())
+    }
+}
+
+async fn block(
+    session: &mut WebDriverBiDiSession,
+    block_list: &BlockList,
+    scope: Option<(Vec<UserContext>, TreeReader)>,
+    blocked: &Mutex<HashMap<BlockReason, usize>>,
+    params: BaseParameters,
+) -> RouteAction {
+    if let Some((user_contexts, reader)) = scope {
+        let user_context = match &params.context {
+            Some(context) => user_context_of(session, &reader, context).await,
+            None => None,
+        };
+        if !user_context.is_some_and(|user_context| user_contexts.contains(&user_context)) {
+            return RouteAction::Fallback;
+        }
+    }
+
+    let request = params.request;
+    match block_list.reason(&request.url, &request.destination) {
+        Some(reason) => {
+            debug!("Blocking request {} for {reason:?}", request.request);
+            *blocked.lock().await.entry(reason).or_default() += 1;
+            RouteAction::Abort
+        }
+        None => RouteAction::Fallback,
+    }
+}
+
+// The first request of a new tab may arrive before the tree has seen the tab created
+async fn user_context_of(
+    session: &mut WebDriverBiDiSession,
+    reader: &TreeReader,
+    context: &BrowsingContext,
+) -> Option<UserContext> {
+    if let Some(node) = reader.get(context).await {
+        return Some(node.user_context);
+    }
+    let params = GetTreeParameters::new(Some(0), Some(context.clone()));
+    match session.browsing_context_get_tree(params).await {
+        Ok(result) => result
+            .contexts
+            .into_iter()
+            .next()
+            .map(|info| info.user_context),
+        Err(e) => {
+            debug!("Failed to get the user context of {context}: {e}");
+            None
+        }
+    }
+}
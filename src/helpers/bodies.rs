@@ -0,0 +1,236 @@
+use std::collections::HashMap;
+use std::str::Utf8Error;
+use std::sync::Arc;
+
+use base64::Engine;
+use base64::engine::general_purpose::STANDARD;
+use log::debug;
+use tokio::sync::Mutex;
+
+use crate::error::HelperError;
+use crate::model::browser::UserContext;
+use crate::model::browsing_context::BrowsingContext;
+use crate::model::common::JsUint;
+use crate::model::network::{
+    AddDataCollectorParameters, BytesValue, Collector, DataType, GetDataParameters,
+    RemoveDataCollectorParameters, Request,
+};
+use crate::session::WebDriverBiDiSession;
+
+// --------------------------------------------------
+
+const REQUEST: &str = "request";
+const RESPONSE: &str = "response";
+
+/// A request or response body.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Body {
+    /// A body sent by the remote end as a string.
+    Text(String),
+    /// A body sent by the remote end as base64, decoded.
+    Bytes(Vec<u8>),
+}
+
+impl Body {
+    /// Return the bytes of the body.
+    pub fn bytes(&self) -> &[u8] {
+        match self {
+            Body::Text(text) => text.as_bytes(),
+            Body::Bytes(bytes) => bytes,
+        }
+    }
+
+    /// Return the body as a string, decoding the bytes as UTF-8.
+    pub fn text(&self) -> Result<&str, Utf8Error> {
+        match self {
+            Body::Text(text) => Ok(text),
+            Body::Bytes(bytes) => std::str::from_utf8(bytes),
+        }
+    }
+
+    /// Return the body, consuming it, as bytes.
+    pub fn into_bytes(self) -> Vec<u8> {
+        match self {
+            Body::Text(text) => text.into_bytes(),
+            Body::Bytes(bytes) => bytes,
+        }
+    }
+}
+
+impl TryFrom<BytesValue> for Body {
+    type Error = HelperError;
+
+    fn try_from(value: BytesValue) -> Result<Self, Self::Error> {
+        match value {
+            BytesValue::StringValue(value) => Ok(Body::Text(value.value)),
+            BytesValue::Base64Value(value) => STANDARD
+                .decode(value.value)
+                .map(Body::Bytes)
+                .map_err(|e| HelperError::Other(format!("invalid base64 body: {e}"))),
+        }
+    }
+}
+
+/// Return a bytes value as text.
+///
+/// The base64 values are decoded, lossily if they aren't UTF-8, and a value that
+/// isn't valid base64 is returned as is rather than dropped.
+pub fn bytes_to_text(value: &BytesValue) -> String {
+    match value {
+        BytesValue::StringValue(value) => value.value.clone(),
+        BytesValue::Base64Value(value) => STANDARD
+            .decode(&value.value)
+            .map(|bytes| String::from_utf8_lossy(&bytes).into_owned())
+            .unwrap_or_else(|_| value.value.clone()),
+    }
+}
+
+/// The requests whose bodies are captured by a `BodyCapture`.
+#[derive(Debug, Clone)]
+pub struct BodyFilter {
+    /// The maximum encoded size of a body, larger bodies aren't captured.
+    pub max_encoded_data_size: JsUint,
+    /// The top-level browsing contexts to capture the bodies of, all of them if `None`.
+    pub contexts: Option<Vec<BrowsingContext>>,
+    /// The user contexts to capture the bodies of, all of them if `None`.
+    pub user_contexts: Option<Vec<UserContext>>,
+    /// Whether the request bodies are captured along with the response bodies.
+    pub request_bodies: bool,
+}
+
+impl BodyFilter {
+    /// Capture the response bodies of every context up to the given encoded size.
+    pub fn new(max_encoded_data_size: JsUint) -> Self {
+        Self {
+            max_encoded_data_size,
+            contexts: None,
+            user_contexts: None,
+            request_bodies: false,
+        }
+    }
+
+    /// Only capture the bodies of the given top-level browsing contexts.
+    pub fn contexts(mut self, contexts: Vec<BrowsingContext>) -> Self {
+        self.contexts = Some(contexts);
+        self
+    }
+
+    /// Only capture the bodies of the given user contexts.
+    pub fn user_contexts(mut self, user_contexts: Vec<UserContext>) -> Self {
+        self.user_contexts = Some(user_contexts);
+        self
+    }
+
+    /// Also capture the request bodies.
+    pub fn request_bodies(mut self) -> Self {
+        self.request_bodies = true;
+        self
+    }
+}
+
+/// Captures the bodies of the requests through a network data collector.
+///
+/// A body is disowned once it has been read, so that the remote end can free it,
+/// and is kept by the handle to be read again. The collector is removed when the
+/// handle is dropped.
+pub struct BodyCapture {
+    session: WebDriverBiDiSession,
+    collector: Option<Collector>,
+    bodies: Arc<Mutex<HashMap<(DataType, Request), Body>>>,
+}
+
+impl BodyCapture {
+    /// Add a data collector capturing the bodies of the requests matching the filter.
+    pub async fn new(
+        session: &mut WebDriverBiDiSession,
+        filter: BodyFilter,
+    ) -> Result<Self, HelperError> {
+        let mut data_types = vec![RESPONSE.to_string()];
+        if filter.request_bodies {
+            data_types.push(REQUEST.to_string());
+        }
+        let params = AddDataCollectorParameters {
+            data_types,
+            max_encoded_data_size: filter.max_encoded_data_size,
+            collector_type: None,
+            contexts: filter.contexts,
+            user_contexts: filter.user_contexts,
+        };
+        let collector = session.network_add_data_collector(params).await?.collector;
+        debug!("Added data collector {collector}");
+
+        Ok(Self {
+            session: session.clone(),
+            collector: Some(collector),
+            bodies: Arc::new(Mutex::new(HashMap::new())),
+        })
+    }
+
+    /// Return the response body of a request.
+    ///
+    /// The request must have completed, the body isn't available before the
+    /// `network.responseCompleted` event.
+    pub async fn body(&self, request: &Request) -> Result<Body, HelperError> {
+        self.data(RESPONSE, request).await
+    }
+
+    /// Return the body sent with a request, when the filter captures them.
+    pub async fn request_body(&self, request: &Request) -> Result<Body, HelperError> {
+        self.data(REQUEST, request).await
+    }
+
+    /// Remove the data collector and wait for the removal.
+    pub async fn close(mut self) -> Result<(), HelperError> {
+        match self.collector.take() {
+            Some(collector) => {
+                self.session
+                    .network_remove_data_collector(RemoveDataCollectorParameters::new(collector))
+                    .await?;
+                Ok(())
+            }
+            None => Ok(()),
+        }
+    }
+
+    async fn data(&self, data_type: &str, request: &Request) -> Result<Body, HelperError> {
+        let key = (data_type.to_string(), request.clone());
+        // Hold the lock while fetching, a body can only be disowned once
+        let mut bodies = self.bodies.lock().await;
+        if let Some(body) = bodies.get(&key) {
+            return Ok(body.clone());
+        }
+
+        let collector = self
+            .collector
+            .clone()
+            .expect("the collector is only taken on close");
+        let params = GetDataParameters::builder(data_type, request.clone())
+            .collector(collector)
+            .disown(true)
+            .build();
+        let bytes = self.session.clone().network_get_data(params).await?.bytes;
+        let body = Body::try_from(bytes)?;
+        bodies.insert(key, body.clone());
+        Ok(body)
+    }
+}
+
+impl Drop for BodyCapture {
+    fn drop(&mut self) {
+        let Some(collector) = self.collector.take() else {
+            return;
+        };
+        let mut session = self.session.clone();
+        match tokio::runtime::Handle::try_current() {
+            Ok(handle) => {
+                handle.spawn(async move {
+                    let params = RemoveDataCollectorParameters::new(collector.clone());
+                    if let Err(e) = session.network_remove_data_collector(params).await {
+                        debug!("Failed to remove data collector {collector}: {e}");
+                    }
+                });
+            }
+            Err(_) => debug!("No runtime to remove data collector {collector}"),
+        }
+    }
+}
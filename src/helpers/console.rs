@@ -0,0 +1,417 @@
+use std::sync::Arc;
+
+use log::debug;
+use serde_json::Value;
+use tokio::sync::{Mutex, mpsc};
+use tokio::task::JoinHandle;
+
+use crate::error::HelperError;
+use crate::events::EventType;
+use crate::model::browsing_context::BrowsingContext;
+use crate::model::common::JsUint;
+use crate::model::log::{BaseLogEntry, Entry, Level};
+use crate::model::script::{
+    MappingRemoteValue, NumberOrSpecialNumber, PrimitiveProtocolValue, Realm, RemoteValue,
+    RemoteValueOrText, SpecialNumber, StackTrace,
+};
+use crate::model::session::{SubscriptionRequest, UnsubscribeByIDRequest, UnsubscribeParameters};
+use crate::session::WebDriverBiDiSession;
+
+// --------------------------------------------------
+
+/// The kind of a collected log entry.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ConsoleMessageKind {
+    /// An entry logged with the given method of the `console` object.
+    Console(String),
+    /// An uncaught JavaScript error.
+    Javascript,
+}
+
+/// A log entry collected by a `ConsoleCollector`.
+#[derive(Debug, Clone)]
+pub struct ConsoleMessage {
+    pub kind: ConsoleMessageKind,
+    pub level: Level,
+    /// The text of the entry, or its arguments joined by spaces when it has none.
+    pub text: String,
+    /// The arguments of a console entry, rendered as text.
+    pub args: Vec<String>,
+    pub context: Option<BrowsingContext>,
+    pub realm: Realm,
+    pub timestamp: JsUint,
+    /// The stack trace, formatted one frame per line.
+    pub stack_trace: Option<String>,
+}
+
+impl std::fmt::Display for ConsoleMessage {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "[{}] {}", level_name(&self.level), self.text)?;
+        if let Some(stack_trace) = &self.stack_trace {
+            write!(f, "\n{stack_trace}")?;
+        }
+        Ok(())
+    }
+}
+
+/// The criteria selecting collected log entries.
+///
+/// An unset criterion matches every entry.
+#[derive(Debug, Clone, Default)]
+pub struct ConsoleFilter {
+    pub context: Option<BrowsingContext>,
+    pub levels: Option<Vec<Level>>,
+    pub javascript: Option<bool>,
+}
+
+impl ConsoleFilter {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Only match the entries of the browsing context.
+    pub fn context(mut self, context: impl Into<BrowsingContext>) -> Self {
+        self.context = Some(context.into());
+        self
+    }
+
+    /// Only match the entries of the level, can be called for several levels.
+    pub fn level(mut self, level: Level) -> Self {
+        self.levels.get_or_insert_with(Vec::new).push(level);
+        self
+    }
+
+    /// Only match the `console` entries.
+    pub fn console(mut self) -> Self {
+        self.javascript = Some(false);
+        self
+    }
+
+    /// Only match the JavaScript errors.
+    pub fn javascript(mut self) -> Self {
+        self.javascript = Some(true);
+        self
+    }
+
+    /// Whether the message meets the criteria.
+    pub fn matches(&self, message: &ConsoleMessage) -> bool {
+        self.context
+            .as_ref()
+            .is_none_or(|context| message.context.as_ref() == Some(context))
+            && self
+                .levels
+                .as_ref()
+                .is_none_or(|levels| levels.contains(&message.level))
+            && self.javascript.is_none_or(|javascript| {
+                javascript == (message.kind == ConsoleMessageKind::Javascript)
+            })
+    }
+}
+
+/// Collects the console messages and JavaScript errors of a session.
+///
+/// The entries of the `log.entryAdded` event are buffered in the order they were
+/// received, with their arguments rendered as text, so that tests can inspect them
+/// or assert that a page didn't throw.
+pub struct ConsoleCollector {
+    session: WebDriverBiDiSession,
+    subscription: Option<String>,
+    messages: Arc<Mutex<Vec<ConsoleMessage>>>,
+    task: JoinHandle<()>,
+}
+
+impl ConsoleCollector {
+    /// Subscribe to the log entries and start collecting them.
+    ///
+    /// # Arguments
+    ///
+    /// * `contexts` - The top-level browsing contexts to collect the entries of, all
+    ///   of them if `None`.
+    pub async fn new(
+        session: &mut WebDriverBiDiSession,
+        contexts: Option<Vec<BrowsingContext>>,
+    ) -> Result<Self, HelperError> {
+        let receiver = session
+            .add_event_listener(vec![EventType::LogEntryAdded])
+            .await;
+        let subscription = session
+            .session_subscribe(SubscriptionRequest::new(
+                vec![EventType::LogEntryAdded.as_str().to_string()],
+                contexts,
+                None,
+            ))
+            .await?
+            .subscription;
+
+        let messages = Arc::new(Mutex::new(Vec::new()));
+        let task = tokio::spawn(collect(receiver, messages.clone()));
+
+        Ok(Self {
+            session: session.clone(),
+            subscription,
+            messages,
+            task,
+        })
+    }
+
+    /// Return all the collected messages.
+    pub async fn messages(&self) -> Vec<ConsoleMessage> {
+        self.messages.lock().await.clone()
+    }
+
+    /// Return the collected messages meeting the filter's criteria.
+    pub async fn filter(&self, filter: &ConsoleFilter) -> Vec<ConsoleMessage> {
+        self.messages
+            .lock()
+            .await
+            .iter()
+            .filter(|message| filter.matches(message))
+            .cloned()
+            .collect()
+    }
+
+    /// Return the collected JavaScript errors.
+    pub async fn js_errors(&self) -> Vec<ConsoleMessage> {
+        self.filter(&ConsoleFilter::new().javascript()).await
+    }
+
+    /// Assert that no JavaScript error was collected.
+    ///
+    /// # Panics
+    ///
+    /// Panics with the collected errors and their stack traces if there are any.
+    pub async fn assert_no_js_errors(&self) {
+        let errors = self.js_errors().await;
+        if !errors.is_empty() {
+            let errors: Vec<String> = errors.iter().map(ToString::to_string).collect();
+            panic!(
+                "expected no JavaScript errors, found {}:\n{}",
+                errors.len(),
+                errors.join("\n")
+            );
+        }
+    }
+
+    /// Discard the collected messages.
+    pub async fn clear(&self) {
+        self.messages.lock().await.clear();
+    }
+
+    /// Stop collecting and unsubscribe from the log entries.
+    pub async fn close(mut self) -> Result<(), HelperError> {
+        self.task.abort();
+        if let Some(subscription) = self.subscription.take() {
+            self.session
+                .session_unsubscribe(UnsubscribeParameters::UnsubscribeByIDRequest(
+                    UnsubscribeByIDRequest::new(vec![subscription]),
+                ))
+                .await?;
+        }
+        Ok(())
+    }
+}
+
+impl Drop for ConsoleCollector {
+    fn drop(&mut self) {
+        self.task.abort();
+    }
+}
+
+async fn collect(
+    mut receiver: mpsc::UnboundedReceiver<Value>,
+    messages: Arc<Mutex<Vec<ConsoleMessage>>>,
+) {
+    while let Some(mut event) = receiver.recv().await {
+        let (kind, base, args) = match serde_json::from_value::<Entry>(event["params"].take()) {
+            Ok(Entry::ConsoleLogEntry(entry)) => (
+                ConsoleMessageKind::Console(entry.method),
+                entry.base,
+                entry.args.iter().map(render_remote_value).collect(),
+            ),
+            Ok(Entry::JavascriptLogEntry(entry)) => {
+                (ConsoleMessageKind::Javascript, entry.base, Vec::new())
+            }
+            Ok(Entry::GenericLogEntry(_)) => continue,
+            Err(e) => {
+                debug!("Ignoring malformed log.entryAdded event: {e}");
+                continue;
+            }
+        };
+        let BaseLogEntry {
+            level,
+            source,
+            text,
+            timestamp,
+            stack_trace,
+        } = base;
+
+        messages.lock().await.push(ConsoleMessage {
+            kind,
+            level,
+            text: text.unwrap_or_else(|| args.join(" ")),
+            args,
+            context: source.context,
+            realm: source.realm,
+            timestamp,
+            stack_trace: stack_trace.as_ref().map(format_stack_trace),
+        });
+    }
+}
+
+fn level_name(level: &Level) -> &str {
+    match level {
+        Level::Debug => "debug",
+        Level::Info => "info",
+        Level::Warn => "warn",
+        Level::Error => "error",
+        Level::Unknown(level) => level,
+    }
+}
+
+/// Render a remote value as text, the way a browser console would.
+///
+/// Strings are rendered as is at the top level and quoted inside containers.
+/// Values without a textual representation, such as functions, are rendered as
+/// their type.
+pub fn render_remote_value(value: &RemoteValue) -> String {
+    render(value, false)
+}
+
+/// Format a stack trace one frame per line, as `at function (url:line:column)`.
+pub fn format_stack_trace(stack_trace: &StackTrace) -> String {
+    stack_trace
+        .call_frames
+        .iter()
+        .map(|frame| {
+            let function_name = if frame.function_name.is_empty() {
+                "<anonymous>"
+            } else {
+                &frame.function_name
+            };
+            format!(
+                "    at {} ({}:{}:{})",
+                function_name, frame.url, frame.line_number, frame.column_number
+            )
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+fn render(value: &RemoteValue, nested: bool) -> String {
+    match value {
+        RemoteValue::PrimitiveProtocolValue(primitive) => render_primitive(primitive, nested),
+        RemoteValue::ArrayRemoteValue(array) => match &array.value {
+            Some(items) => format!("[{}]", render_list(items)),
+            None => "[array]".to_string(),
+        },
+        RemoteValue::SetRemoteValue(set) => match &set.value {
+            Some(items) => format!("Set({}) {{{}}}", items.len(), render_list(items)),
+            None => "[set]".to_string(),
+        },
+        RemoteValue::ObjectRemoteValue(object) => match &object.value {
+            Some(entries) => format!("{{{}}}", render_mapping(entries, ": ")),
+            None => "[object]".to_string(),
+        },
+        RemoteValue::MapRemoteValue(map) => match &map.value {
+            Some(entries) => format!(
+                "Map({}) {{{}}}",
+                entries.len(),
+                render_mapping(entries, " => ")
+            ),
+            None => "[map]".to_string(),
+        },
+        RemoteValue::NodeListRemoteValue(list) => match &list.value {
+            Some(items) => format!("NodeList({}) [{}]", items.len(), render_list(items)),
+            None => "[nodelist]".to_string(),
+        },
+        RemoteValue::HTMLCollectionRemoteValue(collection) => match &collection.value {
+            Some(items) => format!("HTMLCollection({}) [{}]", items.len(), render_list(items)),
+            None => "[htmlcollection]".to_string(),
+        },
+        RemoteValue::RegExpRemoteValue(regexp) => format!(
+            "/{}/{}",
+            regexp.local_value.value.pattern,
+            regexp
+                .local_value
+                .value
+                .flags
+                .as_deref()
+                .unwrap_or_default()
+        ),
+        RemoteValue::DateRemoteValue(date) => date.local_value.value.clone(),
+        RemoteValue::NodeRemoteValue(node) => match node
+            .value
+            .as_ref()
+            .and_then(|properties| properties.local_name.as_ref())
+        {
+            Some(local_name) => format!("<{local_name}>"),
+            None => "[node]".to_string(),
+        },
+        // The other values have no textual representation
+        RemoteValue::WindowProxyRemoteValue(value) => format!("[{}]", value.value_type),
+        RemoteValue::SymbolRemoteValue(value) => format!("[{}]", value.value_type),
+        RemoteValue::FunctionRemoteValue(value) => format!("[{}]", value.value_type),
+        RemoteValue::WeakMapRemoteValue(value) => format!("[{}]", value.value_type),
+        RemoteValue::WeakSetRemoteValue(value) => format!("[{}]", value.value_type),
+        RemoteValue::GeneratorRemoteValue(value) => format!("[{}]", value.value_type),
+        RemoteValue::ErrorRemoteValue(value) => format!("[{}]", value.value_type),
+        RemoteValue::ProxyRemoteValue(value) => format!("[{}]", value.value_type),
+        RemoteValue::PromiseRemoteValue(value) => format!("[{}]", value.value_type),
+        RemoteValue::TypedArrayRemoteValue(value) => format!("[{}]", value.value_type),
+        RemoteValue::ArrayBufferRemoteValue(value) => format!("[{}]", value.value_type),
+        RemoteValue::Unknown(value) => format!("[{}]", value.value_type),
+    }
+}
+
+fn render_primitive(value: &PrimitiveProtocolValue, nested: bool) -> String {
+    match value {
+        PrimitiveProtocolValue::StringValue(string) if nested => format!("{:?}", string.value),
+        PrimitiveProtocolValue::StringValue(string) => string.value.clone(),
+        PrimitiveProtocolValue::NumberValue(number) => match &number.value {
+            NumberOrSpecialNumber::Number(number) => render_number(*number),
+            NumberOrSpecialNumber::SpecialNumber(special) => match special {
+                SpecialNumber::NaN => "NaN".to_string(),
+                SpecialNumber::NegativeZero => "-0".to_string(),
+                SpecialNumber::Infinity => "Infinity".to_string(),
+                SpecialNumber::NegativeInfinity => "-Infinity".to_string(),
+                SpecialNumber::Unknown(special) => special.clone(),
+            },
+        },
+        PrimitiveProtocolValue::BooleanValue(boolean) => boolean.value.to_string(),
+        PrimitiveProtocolValue::BigIntValue(bigint) => format!("{}n", bigint.value),
+        PrimitiveProtocolValue::NullValue(_) => "null".to_string(),
+        PrimitiveProtocolValue::UndefinedValue(_) => "undefined".to_string(),
+        PrimitiveProtocolValue::Unknown(value) => format!("[{}]", value.value_type),
+    }
+}
+
+fn render_list(items: &[RemoteValue]) -> String {
+    items
+        .iter()
+        .map(|item| render(item, true))
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+fn render_mapping(entries: &MappingRemoteValue, separator: &str) -> String {
+    entries
+        .iter()
+        .map(|(key, value)| {
+            let key = match key {
+                RemoteValueOrText::Text(key) => key.clone(),
+                RemoteValueOrText::RemoteValue(key) => render(key, true),
+            };
+            format!("{key}{separator}{}", render(value, true))
+        })
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+fn render_number(number: f64) -> String {
+    // Integers are rendered without a fractional part, as in JavaScript
+    if number.fract() == 0.0 && number.abs() < 1e21 {
+        format!("{number:.0}")
+    } else {
+        number.to_string()
+    }
+}
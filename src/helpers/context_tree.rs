@@ -0,0 +1,329 @@
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use log::debug;
+use serde_json::Value;
+use tokio::sync::{Mutex, mpsc};
+use tokio::task::JoinHandle;
+
+use crate::error::HelperError;
+use crate::events::EventType;
+use crate::model::browser::{ClientWindow, UserContext};
+use crate::model::browsing_context::{
+    BrowsingContext, GetTreeParameters, HistoryUpdatedParameters, Info, NavigationInfo,
+};
+use crate::model::session::{SubscriptionRequest, UnsubscribeByIDRequest, UnsubscribeParameters};
+use crate::session::WebDriverBiDiSession;
+
+// --------------------------------------------------
+
+const EVENTS: [EventType; 5] = [
+    EventType::BrowsingContextContextCreated,
+    EventType::BrowsingContextContextDestroyed,
+    EventType::BrowsingContextNavigationCommitted,
+    EventType::BrowsingContextFragmentNavigated,
+    EventType::BrowsingContextHistoryUpdated,
+];
+
+/// A browsing context of a `ContextTree`.
+#[derive(Debug, Clone)]
+pub struct ContextNode {
+    pub context: BrowsingContext,
+    /// The parent context, `None` for top-level contexts.
+    pub parent: Option<BrowsingContext>,
+    /// The child contexts, in the order they were created.
+    pub children: Vec<BrowsingContext>,
+    /// The URL of the last committed navigation.
+    pub url: String,
+    pub user_context: UserContext,
+    pub client_window: Option<ClientWindow>,
+    pub original_opener: Option<BrowsingContext>,
+}
+
+#[derive(Debug, Default)]
+struct Nodes {
+    nodes: HashMap<BrowsingContext, ContextNode>,
+    top_level: Vec<BrowsingContext>,
+}
+
+impl Nodes {
+    fn insert(&mut self, info: Info) {
+        let Info {
+            children,
+            client_window,
+            context,
+            original_opener,
+            url,
+            user_context,
+            parent,
+        } = info;
+
+        match &parent {
+            Some(parent) => {
+                if let Some(parent) = self.nodes.get_mut(parent)
+                    && !parent.children.contains(&context)
+                {
+                    parent.children.push(context.clone());
+                }
+            }
+            None => {
+                if !self.top_level.contains(&context) {
+                    self.top_level.push(context.clone());
+                }
+            }
+        }
+
+        // A context created event may arrive for a context of the getTree seed
+        let known_children = self
+            .nodes
+            .remove(&context)
+            .map(|node| node.children)
+            .unwrap_or_default();
+        self.nodes.insert(
+            context.clone(),
+            ContextNode {
+                context: context.clone(),
+                parent: parent.clone(),
+                children: known_children,
+                url,
+                user_context,
+                client_window,
+                original_opener,
+            },
+        );
+
+        for mut child in children.unwrap_or_default() {
+            // The children of the getTree result don't always repeat their parent
+            child.parent.get_or_insert_with(|| context.clone());
+            self.insert(child);
+        }
+    }
+
+    fn remove(&mut self, context: &BrowsingContext) {
+        let Some(node) = self.nodes.remove(context) else {
+            return;
+        };
+        match &node.parent {
+            Some(parent) => {
+                if let Some(parent) = self.nodes.get_mut(parent) {
+                    parent.children.retain(|child| child != context);
+                }
+            }
+            None => self.top_level.retain(|top| top != context),
+        }
+        for child in &node.children {
+            self.remove(child);
+        }
+    }
+
+    fn set_url(&mut self, context: &BrowsingContext, url: String) {
+        if let Some(node) = self.nodes.get_mut(context) {
+            node.url = url;
+        }
+    }
+
+    fn top_level_of(&self, context: &BrowsingContext) -> Option<&ContextNode> {
+        let mut node = self.nodes.get(context)?;
+        while let Some(parent) = &node.parent {
+            node = self.nodes.get(parent)?;
+        }
+        Some(node)
+    }
+
+    fn collect_descendants(&self, context: &BrowsingContext, descendants: &mut Vec<ContextNode>) {
+        let Some(node) = self.nodes.get(context) else {
+            return;
+        };
+        for child in &node.children {
+            if let Some(child_node) = self.nodes.get(child) {
+                descendants.push(child_node.clone());
+                self.collect_descendants(child, descendants);
+            }
+        }
+    }
+}
+
+/// A live mirror of the browsing context tree of a session.
+///
+/// The tree is seeded with `browsingContext.getTree` and kept up to date with the
+/// context creation, destruction and navigation events, so that it can be queried
+/// without a round trip to the remote end.
+pub struct ContextTree {
+    session: WebDriverBiDiSession,
+    subscription: Option<String>,
+    nodes: Arc<Mutex<Nodes>>,
+    task: JoinHandle<()>,
+}
+
+impl ContextTree {
+    /// Subscribe to the browsing context events and start mirroring the tree.
+    pub async fn new(session: &mut WebDriverBiDiSession) -> Result<Self, HelperError> {
+        // Listen before fetching the tree, so that no change is missed
+        let receiver = session.add_event_listener(EVENTS.to_vec()).await;
+        let events = EVENTS.iter().map(|e| e.as_str().to_string()).collect();
+        let subscription = session
+            .session_subscribe(SubscriptionRequest::new(events, None, None))
+            .await?
+            .subscription;
+        let seed = session
+            .browsing_context_get_tree(GetTreeParameters::new(None, None))
+            .await?
+            .contexts;
+
+        let mut nodes = Nodes::default();
+        for info in seed {
+            nodes.insert(info);
+        }
+        let nodes = Arc::new(Mutex::new(nodes));
+        let task = tokio::spawn(track(receiver, nodes.clone()));
+
+        Ok(Self {
+            session: session.clone(),
+            subscription,
+            nodes,
+            task,
+        })
+    }
+
+    /// Return the browsing context.
+    pub async fn get(&self, context: &BrowsingContext) -> Option<ContextNode> {
+        self.nodes.lock().await.nodes.get(context).cloned()
+    }
+
+    /// Return the parent of the browsing context.
+    pub async fn parent(&self, context: &BrowsingContext) -> Option<ContextNode> {
+        let nodes = self.nodes.lock().await;
+        let parent = nodes.nodes.get(context)?.parent.as_ref()?;
+        nodes.nodes.get(parent).cloned()
+    }
+
+    /// Return the direct children of the browsing context.
+    pub async fn children(&self, context: &BrowsingContext) -> Vec<ContextNode> {
+        let nodes = self.nodes.lock().await;
+        nodes
+            .nodes
+            .get(context)
+            .map(|node| {
+                node.children
+                    .iter()
+                    .filter_map(|child| nodes.nodes.get(child).cloned())
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+
+    /// Return all the descendants of the browsing context, such as its nested iframes.
+    pub async fn descendants(&self, context: &BrowsingContext) -> Vec<ContextNode> {
+        let mut descendants = Vec::new();
+        self.nodes
+            .lock()
+            .await
+            .collect_descendants(context, &mut descendants);
+        descendants
+    }
+
+    /// Return the top-level browsing contexts.
+    pub async fn top_level(&self) -> Vec<ContextNode> {
+        let nodes = self.nodes.lock().await;
+        nodes
+            .top_level
+            .iter()
+            .filter_map(|context| nodes.nodes.get(context).cloned())
+            .collect()
+    }
+
+    /// Return the top-level browsing contexts of the user context.
+    pub async fn top_level_in_user_context(&self, user_context: &UserContext) -> Vec<ContextNode> {
+        self.top_level()
+            .await
+            .into_iter()
+            .filter(|node| &node.user_context == user_context)
+            .collect()
+    }
+
+    /// Return the top-level browsing context containing the browsing context.
+    pub async fn top_level_of(&self, context: &BrowsingContext) -> Option<ContextNode> {
+        self.nodes.lock().await.top_level_of(context).cloned()
+    }
+
+    /// Return the current URL of the browsing context.
+    pub async fn url(&self, context: &BrowsingContext) -> Option<String> {
+        self.get(context).await.map(|node| node.url)
+    }
+
+    // A handle on the mirrored nodes, for the helpers reading them from their own
+    // tasks while keeping the tree itself
+    pub(crate) fn reader(&self) -> TreeReader {
+        TreeReader(self.nodes.clone())
+    }
+
+    /// Stop mirroring the tree and unsubscribe from the browsing context events.
+    pub async fn close(mut self) -> Result<(), HelperError> {
+        self.task.abort();
+        if let Some(subscription) = self.subscription.take() {
+            self.session
+                .session_unsubscribe(UnsubscribeParameters::UnsubscribeByIDRequest(
+                    UnsubscribeByIDRequest::new(vec![subscription]),
+                ))
+                .await?;
+        }
+        Ok(())
+    }
+}
+
+#[derive(Clone)]
+pub(crate) struct TreeReader(Arc<Mutex<Nodes>>);
+
+impl TreeReader {
+    pub(crate) async fn get(&self, context: &BrowsingContext) -> Option<ContextNode> {
+        self.0.lock().await.nodes.get(context).cloned()
+    }
+
+    pub(crate) async fn top_level_of(&self, context: &BrowsingContext) -> Option<ContextNode> {
+        self.0.lock().await.top_level_of(context).cloned()
+    }
+}
+
+impl Drop for ContextTree {
+    fn drop(&mut self) {
+        self.task.abort();
+    }
+}
+
+async fn track(mut receiver: mpsc::UnboundedReceiver<Value>, nodes: Arc<Mutex<Nodes>>) {
+    while let Some(mut event) = receiver.recv().await {
+        let method = event["method"].as_str().unwrap_or_default().to_string();
+        let params = event["params"].take();
+
+        let result = if method == EventType::BrowsingContextContextCreated.as_str() {
+            serde_json::from_value::<Info>(params).map(|info| Change::Created(Box::new(info)))
+        } else if method == EventType::BrowsingContextContextDestroyed.as_str() {
+            serde_json::from_value::<Info>(params).map(|info| Change::Destroyed(info.context))
+        } else if method == EventType::BrowsingContextHistoryUpdated.as_str() {
+            serde_json::from_value::<HistoryUpdatedParameters>(params)
+                .map(|params| Change::Url(params.context, params.url))
+        } else {
+            // Committed and fragment navigations
+            serde_json::from_value::<NavigationInfo>(params)
+                .map(|params| Change::Url(params.base.context, params.base.url))
+        };
+
+        match result {
+            Ok(change) => {
+                let mut nodes = nodes.lock().await;
+                match change {
+                    Change::Created(info) => nodes.insert(*info),
+                    Change::Destroyed(context) => nodes.remove(&context),
+                    Change::Url(context, url) => nodes.set_url(&context, url),
+                }
+            }
+            Err(e) => debug!("Ignoring malformed {method} event: {e}"),
+        }
+    }
+}
+
+enum Change {
+    Created(Box<Info>),
+    Destroyed(BrowsingContext),
+    Url(BrowsingContext, String),
+}
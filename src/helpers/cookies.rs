@@ -0,0 +1,181 @@
+use crate::error::HelperError;
+use crate::helpers::bodies::bytes_to_text;
+use crate::model::network::{BytesValue, Cookie, SameSite, SetCookieHeader};
+use crate::model::storage::PartialCookie;
+
+// --------------------------------------------------
+
+const NETSCAPE_HEADER: &str = "# Netscape HTTP Cookie File";
+// The prefix of the domain of the HttpOnly cookies, as written by curl
+const HTTP_ONLY_PREFIX: &str = "#HttpOnly_";
+
+impl From<&Cookie> for PartialCookie {
+    /// Convert a cookie returned by the browser to a cookie that can be set.
+    ///
+    /// A `SameSite` of `default` is left unset, so that the browser applies its
+    /// default again.
+    fn from(cookie: &Cookie) -> Self {
+        let mut builder = PartialCookie::builder(
+            cookie.name.clone(),
+            cookie.value.clone(),
+            cookie.domain.clone(),
+        )
+        .path(cookie.path.clone())
+        .http_only(cookie.http_only)
+        .secure(cookie.secure);
+        if let Some(same_site) = settable_same_site(&cookie.same_site) {
+            builder = builder.same_site(same_site);
+        }
+        if let Some(expiry) = cookie.expiry {
+            builder = builder.expiry(expiry);
+        }
+        builder.build()
+    }
+}
+
+/// Parse a raw `Set-Cookie` header value.
+///
+/// The attribute names are matched case-insensitively and the unknown attributes
+/// are ignored. A `SameSite` value other than `Strict`, `Lax` or `None` is kept as
+/// `SameSite::Unknown`.
+///
+/// # Returns
+///
+/// The `SetCookieHeader`, or `None` if the header has no `name=value` pair.
+pub fn parse_set_cookie(header: &str) -> Option<SetCookieHeader> {
+    let mut parts = header.split(';');
+    let (name, value) = parts.next()?.split_once('=')?;
+    let name = name.trim();
+    if name.is_empty() {
+        return None;
+    }
+
+    let mut cookie = SetCookieHeader::builder(name, BytesValue::string(value.trim())).build();
+    for attribute in parts {
+        let (key, value) = match attribute.split_once('=') {
+            Some((key, value)) => (key.trim(), value.trim()),
+            None => (attribute.trim(), ""),
+        };
+        match key.to_ascii_lowercase().as_str() {
+            "domain" => cookie.domain = Some(value.to_string()),
+            "path" => cookie.path = Some(value.to_string()),
+            "expires" => cookie.expiry = Some(value.to_string()),
+            "max-age" => cookie.max_age = value.parse().ok(),
+            "samesite" => cookie.same_site = Some(parse_same_site(value)),
+            "secure" => cookie.secure = Some(true),
+            "httponly" => cookie.http_only = Some(true),
+            _ => {}
+        }
+    }
+    Some(cookie)
+}
+
+/// Write cookies in the Netscape `cookies.txt` format read by curl and wget.
+///
+/// The base64 values are decoded, lossily if they aren't UTF-8. The session
+/// cookies get an expiry of 0.
+pub fn to_netscape(cookies: &[Cookie]) -> String {
+    let mut lines = vec![NETSCAPE_HEADER.to_string(), String::new()];
+    for cookie in cookies {
+        let prefix = if cookie.http_only {
+            HTTP_ONLY_PREFIX
+        } else {
+            ""
+        };
+        lines.push(
+            [
+                format!("{prefix}{}", cookie.domain),
+                netscape_bool(cookie.domain.starts_with('.')).to_string(),
+                cookie.path.clone(),
+                netscape_bool(cookie.secure).to_string(),
+                cookie.expiry.unwrap_or(0).to_string(),
+                cookie.name.clone(),
+                bytes_to_text(&cookie.value),
+            ]
+            .join("\t"),
+        );
+    }
+    lines.push(String::new());
+    lines.join("\n")
+}
+
+/// Read cookies from the Netscape `cookies.txt` format.
+///
+/// The comments and blank lines are skipped, the domains prefixed with `#HttpOnly_`
+/// are read as HttpOnly cookies. A cookie whose include-subdomains field is `TRUE`
+/// gets a domain with a leading dot, and a host-only cookie one without.
+///
+/// # Returns
+///
+/// The cookies or a `HelperError::Other` naming the first malformed line.
+pub fn from_netscape(text: &str) -> Result<Vec<PartialCookie>, HelperError> {
+    let mut cookies = Vec::new();
+    for (index, line) in text.lines().enumerate() {
+        let (line, http_only) = match line.strip_prefix(HTTP_ONLY_PREFIX) {
+            Some(line) => (line, true),
+            None => (line, false),
+        };
+        if line.trim().is_empty() || line.starts_with('#') {
+            continue;
+        }
+
+        let invalid = |reason: &str| {
+            HelperError::Other(format!("invalid cookies.txt line {}: {reason}", index + 1))
+        };
+        let fields: Vec<&str> = line.split('\t').collect();
+        let [
+            domain,
+            include_subdomains,
+            path,
+            secure,
+            expiry,
+            name,
+            value,
+        ] = fields[..]
+        else {
+            return Err(invalid("expected 7 tab-separated fields"));
+        };
+        let expiry: u64 = expiry.parse().map_err(|_| invalid("invalid expiry"))?;
+        let host = domain.trim_start_matches('.');
+        let domain = match include_subdomains.eq_ignore_ascii_case("TRUE") {
+            true => format!(".{host}"),
+            false => host.to_string(),
+        };
+
+        let mut builder = PartialCookie::builder(name, BytesValue::string(value), domain)
+            .path(path)
+            .http_only(http_only)
+            .secure(secure.eq_ignore_ascii_case("TRUE"));
+        if expiry > 0 {
+            builder = builder.expiry(expiry);
+        }
+        cookies.push(builder.build());
+    }
+    Ok(cookies)
+}
+
+// Read a SameSite attribute case-insensitively
+pub(crate) fn parse_same_site(value: &str) -> SameSite {
+    match value.to_ascii_lowercase().as_str() {
+        "strict" => SameSite::Strict,
+        "lax" => SameSite::Lax,
+        "none" => SameSite::None,
+        _ => SameSite::Unknown(value.to_string()),
+    }
+}
+
+// The SameSite a cookie is set with, `default` and the unknown values are left
+// unset so that the browser applies its default again
+pub(crate) fn settable_same_site(same_site: &SameSite) -> Option<SameSite> {
+    match same_site {
+        SameSite::Default | SameSite::Unknown(_) => None,
+        same_site => Some(same_site.clone()),
+    }
+}
+
+fn netscape_bool(value: bool) -> &'static str {
+    match value {
+        true => "TRUE",
+        false => "FALSE",
+    }
+}
@@ -0,0 +1,232 @@
+use std::collections::{HashMap, VecDeque};
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::time::Duration;
+
+use log::debug;
+use serde_json::Value;
+use tokio::sync::{Mutex, Notify, mpsc};
+use tokio::task::JoinHandle;
+use tokio::time::{Instant, timeout_at};
+
+use crate::error::HelperError;
+use crate::events::EventType;
+use crate::model::browsing_context::{
+    BaseNavigationInfo, BrowsingContext, DownloadEndParams, DownloadWillBeginParams, Navigation,
+};
+use crate::model::session::{SubscriptionRequest, UnsubscribeByIDRequest, UnsubscribeParameters};
+use crate::session::WebDriverBiDiSession;
+
+// --------------------------------------------------
+
+const EVENTS: [EventType; 2] = [
+    EventType::BrowsingContextDownloadWillBegin,
+    EventType::BrowsingContextDownloadEnd,
+];
+
+/// The final status of a download.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DownloadStatus {
+    Complete,
+    Canceled,
+    /// A status this version doesn't know.
+    Unknown(String),
+}
+
+/// A finished download.
+#[derive(Debug, Clone)]
+pub struct Download {
+    pub context: BrowsingContext,
+    pub navigation: Option<Navigation>,
+    pub url: String,
+    pub suggested_filename: String,
+    /// The path of the downloaded file, only known for complete downloads.
+    pub path: Option<PathBuf>,
+    pub status: DownloadStatus,
+}
+
+// A download is identified by its navigation id, or by its context and URL when
+// the remote end doesn't report one.
+#[derive(Debug, Hash, PartialEq, Eq)]
+enum DownloadKey {
+    Navigation(Navigation),
+    ContextUrl(BrowsingContext, String),
+}
+
+impl DownloadKey {
+    fn from_info(info: &BaseNavigationInfo) -> Self {
+        match &info.navigation {
+            Some(navigation) => DownloadKey::Navigation(navigation.clone()),
+            None => DownloadKey::ContextUrl(info.context.clone(), info.url.clone()),
+        }
+    }
+}
+
+#[derive(Debug, Default)]
+struct State {
+    // Suggested filenames of the downloads that began but didn't end yet
+    pending: HashMap<DownloadKey, String>,
+    finished: VecDeque<Download>,
+}
+
+/// Tracks the downloads of a session.
+///
+/// The `browsingContext.downloadWillBegin` and `browsingContext.downloadEnd` events
+/// are correlated so that a finished download reports both the filename suggested
+/// when it began and the path it was saved to. Downloads are only saved to disk when
+/// allowed with `browser_set_download_behavior`.
+pub struct Downloads {
+    session: WebDriverBiDiSession,
+    subscription: Option<String>,
+    state: Arc<Mutex<State>>,
+    notify: Arc<Notify>,
+    task: JoinHandle<()>,
+}
+
+impl Downloads {
+    /// Subscribe to the download events and start tracking downloads.
+    pub async fn new(session: &mut WebDriverBiDiSession) -> Result<Self, HelperError> {
+        let receiver = session.add_event_listener(EVENTS.to_vec()).await;
+        let events = EVENTS.iter().map(|e| e.as_str().to_string()).collect();
+        let subscription = session
+            .session_subscribe(SubscriptionRequest::new(events, None, None))
+            .await?
+            .subscription;
+
+        let state = Arc::new(Mutex::new(State::default()));
+        let notify = Arc::new(Notify::new());
+        let task = tokio::spawn(track(receiver, state.clone(), notify.clone()));
+
+        Ok(Self {
+            session: session.clone(),
+            subscription,
+            state,
+            notify,
+            task,
+        })
+    }
+
+    /// Wait for a download started by the given browsing context to end.
+    ///
+    /// Downloads that ended before the call are returned first, in order.
+    ///
+    /// # Arguments
+    ///
+    /// * `context` - The browsing context that started the download.
+    /// * `timeout` - The maximum time to wait for.
+    ///
+    /// # Returns
+    ///
+    /// A result containing the `Download` or a `HelperError::Timeout`.
+    pub async fn wait_for_download(
+        &self,
+        context: &BrowsingContext,
+        timeout: Duration,
+    ) -> Result<Download, HelperError> {
+        let deadline = Instant::now() + timeout;
+        loop {
+            // Register for notifications before checking the state, so that a
+            // download ending in between isn't missed
+            let notified = self.notify.notified();
+            tokio::pin!(notified);
+            notified.as_mut().enable();
+
+            {
+                let mut state = self.state.lock().await;
+                if let Some(index) = state.finished.iter().position(|d| &d.context == context)
+                    && let Some(download) = state.finished.remove(index)
+                {
+                    return Ok(download);
+                }
+            }
+
+            timeout_at(deadline, notified)
+                .await
+                .map_err(|_| HelperError::Timeout(format!("a download in context {context}")))?;
+        }
+    }
+
+    /// Return the downloads that ended and weren't waited for yet.
+    pub async fn finished(&self) -> Vec<Download> {
+        self.state.lock().await.finished.iter().cloned().collect()
+    }
+
+    /// Stop tracking downloads and unsubscribe from the download events.
+    pub async fn close(mut self) -> Result<(), HelperError> {
+        self.task.abort();
+        if let Some(subscription) = self.subscription.take() {
+            self.session
+                .session_unsubscribe(UnsubscribeParameters::UnsubscribeByIDRequest(
+                    UnsubscribeByIDRequest::new(vec![subscription]),
+                ))
+                .await?;
+        }
+        Ok(())
+    }
+}
+
+impl Drop for Downloads {
+    fn drop(&mut self) {
+        self.task.abort();
+    }
+}
+
+async fn track(
+    mut receiver: mpsc::UnboundedReceiver<Value>,
+    state: Arc<Mutex<State>>,
+    notify: Arc<Notify>,
+) {
+    while let Some(mut event) = receiver.recv().await {
+        let method = event["method"].as_str().unwrap_or_default().to_string();
+        let params = event["params"].take();
+
+        if method == EventType::BrowsingContextDownloadWillBegin.as_str() {
+            match serde_json::from_value::<DownloadWillBeginParams>(params) {
+                Ok(params) => {
+                    let key = DownloadKey::from_info(&params.base);
+                    state
+                        .lock()
+                        .await
+                        .pending
+                        .insert(key, params.suggested_filename);
+                }
+                Err(e) => debug!("Ignoring malformed {method} event: {e}"),
+            }
+        } else if method == EventType::BrowsingContextDownloadEnd.as_str() {
+            let (base, path, status) = match serde_json::from_value::<DownloadEndParams>(params) {
+                Ok(DownloadEndParams::DownloadComplete(params)) => (
+                    params.base,
+                    params.filepath.map(PathBuf::from),
+                    DownloadStatus::Complete,
+                ),
+                Ok(DownloadEndParams::DownloadCanceled(params)) => {
+                    (params.base, None, DownloadStatus::Canceled)
+                }
+                Ok(DownloadEndParams::Unknown(params)) => {
+                    (params.base, None, DownloadStatus::Unknown(params.status))
+                }
+                Err(e) => {
+                    debug!("Ignoring malformed {method} event: {e}");
+                    continue;
+                }
+            };
+
+            let mut state = state.lock().await;
+            // downloadWillBegin always precedes downloadEnd, the empty filename is
+            // only a fallback for a download that began before the tracking started
+            let suggested_filename = state
+                .pending
+                .remove(&DownloadKey::from_info(&base))
+                .unwrap_or_default();
+            state.finished.push_back(Download {
+                context: base.context,
+                navigation: base.navigation,
+                url: base.url,
+                suggested_filename,
+                path,
+                status,
+            });
+            notify.notify_waiters();
+        }
+    }
+}
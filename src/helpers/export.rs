@@ -0,0 +1,132 @@
+use base64::Engine;
+use base64::engine::general_purpose::STANDARD;
+
+use crate::helpers::bodies::{Body, bytes_to_text};
+use crate::model::network::RequestData;
+
+// --------------------------------------------------
+
+// Set by the HTTP client itself, copying them would conflict with its own values
+const SKIPPED_HEADERS: [&str; 4] = ["content-length", "host", "connection", "accept-encoding"];
+
+/// Return a curl command line sending the same request.
+///
+/// The headers and cookies of the request are copied, HTTP/2 pseudo-headers and the
+/// headers curl sets itself aside. A request accepting compressed responses gets
+/// `--compressed`. A body that isn't UTF-8 is piped to curl from its base64 encoding.
+///
+/// # Arguments
+///
+/// * `request` - The request to reproduce.
+/// * `body` - The body of the request, such as one read with `BodyCapture::request_body`.
+pub fn to_curl(request: &RequestData, body: Option<&Body>) -> String {
+    let mut parts = vec![match request.method.as_str() {
+        "GET" if body.is_none() => format!("curl {}", shell_quote(&request.url)),
+        method => format!(
+            "curl -X {} {}",
+            shell_quote(method),
+            shell_quote(&request.url)
+        ),
+    }];
+    for (name, value) in headers(request) {
+        parts.push(format!("-H {}", shell_quote(&format!("{name}: {value}"))));
+    }
+    if let Some(cookies) = cookies(request) {
+        parts.push(format!("-b {}", shell_quote(&cookies)));
+    }
+    if accepts_compression(request) {
+        parts.push("--compressed".to_string());
+    }
+
+    let mut pipe = None;
+    match body.map(|body| (body, body.text())) {
+        Some((_, Ok(text))) => parts.push(format!("--data-raw {}", shell_quote(text))),
+        Some((body, Err(_))) => {
+            pipe = Some(format!(
+                "printf %s {} | base64 -d | ",
+                shell_quote(&STANDARD.encode(body.bytes()))
+            ));
+            parts.push("--data-binary @-".to_string());
+        }
+        None => {}
+    }
+
+    format!("{}{}", pipe.unwrap_or_default(), parts.join(" \\\n  "))
+}
+
+/// Return a Rust snippet sending the same request with `reqwest`.
+///
+/// The snippet is an expression to use in an `async` function returning a `Result`.
+///
+/// # Arguments
+///
+/// * `request` - The request to reproduce.
+/// * `body` - The body of the request, such as one read with `BodyCapture::request_body`.
+pub fn to_reqwest(request: &RequestData, body: Option<&Body>) -> String {
+    let mut lines = vec!["reqwest::Client::new()".to_string()];
+    let url = format!("{:?}", request.url);
+    lines.push(match request.method.as_str() {
+        "GET" | "POST" | "PUT" | "PATCH" | "DELETE" | "HEAD" => {
+            format!(".{}({url})", request.method.to_lowercase())
+        }
+        method => format!(".request(reqwest::Method::from_bytes(b{method:?})?, {url})"),
+    });
+    for (name, value) in headers(request) {
+        lines.push(format!(".header({name:?}, {value:?})"));
+    }
+    if let Some(cookies) = cookies(request) {
+        lines.push(format!(".header(\"Cookie\", {cookies:?})"));
+    }
+    match body.map(|body| (body, body.text())) {
+        Some((_, Ok(text))) => lines.push(format!(".body({text:?})")),
+        Some((body, Err(_))) => lines.push(format!(".body(vec!{:?})", body.bytes())),
+        None => {}
+    }
+    lines.push(".send()".to_string());
+    lines.push(".await?".to_string());
+
+    lines.join("\n    ")
+}
+
+fn headers(request: &RequestData) -> Vec<(String, String)> {
+    request
+        .headers
+        .iter()
+        .filter(|header| {
+            !header.name.starts_with(':')
+                && !SKIPPED_HEADERS
+                    .iter()
+                    .any(|skipped| header.name.eq_ignore_ascii_case(skipped))
+        })
+        .map(|header| (header.name.clone(), bytes_to_text(&header.value)))
+        .collect()
+}
+
+// The cookies sent by the browser, unless they're already in a Cookie header
+fn cookies(request: &RequestData) -> Option<String> {
+    let has_header = request
+        .headers
+        .iter()
+        .any(|header| header.name.eq_ignore_ascii_case("cookie"));
+    if has_header || request.cookies.is_empty() {
+        return None;
+    }
+    let cookies: Vec<String> = request
+        .cookies
+        .iter()
+        .map(|cookie| format!("{}={}", cookie.name, bytes_to_text(&cookie.value)))
+        .collect();
+    Some(cookies.join("; "))
+}
+
+fn accepts_compression(request: &RequestData) -> bool {
+    request
+        .headers
+        .iter()
+        .any(|header| header.name.eq_ignore_ascii_case("accept-encoding"))
+}
+
+// Quote a word for POSIX shells
+fn shell_quote(word: &str) -> String {
+    format!("'{}'", word.replace('\'', r"'\''"))
+}
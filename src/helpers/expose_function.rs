@@ -0,0 +1,232 @@
+use std::future::Future;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicU64, Ordering};
+
+use log::debug;
+use serde::Deserialize;
+use serde_json::{Value, json};
+use tokio::sync::mpsc;
+use tokio::task::JoinHandle;
+
+use crate::error::HelperError;
+use crate::events::EventType;
+use crate::model::browsing_context::BrowsingContext;
+use crate::model::script::{
+    AddPreloadScriptParameters, CallFunctionParameters, ChannelProperties, ChannelValue,
+    LocalValue, MessageParameters, PreloadScript, PrimitiveProtocolValue, RealmTarget, RemoteValue,
+    RemovePreloadScriptParameters, StringValue, Target,
+};
+use crate::model::session::{SubscriptionRequest, UnsubscribeByIDRequest, UnsubscribeParameters};
+use crate::session::WebDriverBiDiSession;
+
+// --------------------------------------------------
+
+static NEXT_CHANNEL: AtomicU64 = AtomicU64::new(1);
+
+// Installs `window[name]`, which sends its JSON encoded arguments through the
+// channel and returns a promise settled by the resolver registered under
+// `Symbol.for(key)`.
+const BINDING: &str = r#"(name, key) => (channel) => {
+    const callbacks = new Map();
+    let lastId = 0;
+    Object.defineProperty(window, key, {
+        value: (payload) => {
+            const { id, result, error } = JSON.parse(payload);
+            const callback = callbacks.get(id);
+            callbacks.delete(id);
+            if (error !== undefined) {
+                callback?.reject(new Error(error));
+            } else {
+                callback?.resolve(result);
+            }
+        },
+    });
+    window[name] = (...args) =>
+        new Promise((resolve, reject) => {
+            const id = ++lastId;
+            callbacks.set(id, { resolve, reject });
+            channel(JSON.stringify({ id, args }));
+        });
+}"#;
+
+const RESOLVE: &str = "(key, payload) => window[Symbol.for(key)](payload)";
+
+/// The result of an exposed function, sent back to the page.
+///
+/// An `Ok` value resolves the promise returned to the page, an `Err` message
+/// rejects it with an `Error`.
+pub type ExposedFunctionResult = Result<Value, String>;
+
+#[derive(Deserialize)]
+struct Call {
+    id: u64,
+    args: Vec<Value>,
+}
+
+/// A Rust function exposed to the pages of a session.
+///
+/// Dropping the handle stops answering the calls from the pages, use `remove` to
+/// also remove the binding from the documents created afterwards.
+pub struct ExposedFunction {
+    session: WebDriverBiDiSession,
+    name: String,
+    script: PreloadScript,
+    subscription: Option<String>,
+    task: JoinHandle<()>,
+}
+
+impl ExposedFunction {
+    /// Return the name of the function in the pages.
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Return the preload script installing the function.
+    pub fn preload_script(&self) -> &PreloadScript {
+        &self.script
+    }
+
+    /// Remove the preload script and stop answering the calls from the pages.
+    pub async fn remove(mut self) -> Result<(), HelperError> {
+        self.task.abort();
+        self.session
+            .script_remove_preload_script(RemovePreloadScriptParameters::new(self.script.clone()))
+            .await?;
+        if let Some(subscription) = self.subscription.take() {
+            self.session
+                .session_unsubscribe(UnsubscribeParameters::UnsubscribeByIDRequest(
+                    UnsubscribeByIDRequest::new(vec![subscription]),
+                ))
+                .await?;
+        }
+        Ok(())
+    }
+}
+
+impl Drop for ExposedFunction {
+    fn drop(&mut self) {
+        self.task.abort();
+    }
+}
+
+pub(crate) async fn expose_function<F, Fut>(
+    session: &mut WebDriverBiDiSession,
+    name: &str,
+    contexts: Option<Vec<BrowsingContext>>,
+    function: F,
+) -> Result<ExposedFunction, HelperError>
+where
+    F: Fn(Vec<Value>) -> Fut + Send + Sync + 'static,
+    Fut: Future<Output = ExposedFunctionResult> + Send + 'static,
+{
+    let channel = format!(
+        "webdriverbidi.exposeFunction.{}",
+        NEXT_CHANNEL.fetch_add(1, Ordering::Relaxed)
+    );
+
+    let receiver = session
+        .add_event_listener(vec![EventType::ScriptMessage])
+        .await;
+    let subscription = session
+        .session_subscribe(SubscriptionRequest::new(
+            vec![EventType::ScriptMessage.as_str().to_string()],
+            contexts.clone(),
+            None,
+        ))
+        .await?
+        .subscription;
+
+    let function_declaration = format!(
+        "({BINDING})({}, Symbol.for({}))",
+        serde_json::to_string(name)?,
+        serde_json::to_string(&channel)?
+    );
+    let mut params = AddPreloadScriptParameters::builder(function_declaration).arguments(vec![
+        ChannelValue::new(ChannelProperties::new(channel.clone(), None, None)),
+    ]);
+    if let Some(contexts) = contexts {
+        params = params.contexts(contexts);
+    }
+    let script = session
+        .script_add_preload_script(params.build())
+        .await?
+        .script;
+
+    let task = tokio::spawn(serve(
+        session.clone(),
+        receiver,
+        channel,
+        Arc::new(function),
+    ));
+
+    Ok(ExposedFunction {
+        session: session.clone(),
+        name: name.to_string(),
+        script,
+        subscription,
+        task,
+    })
+}
+
+async fn serve<F, Fut>(
+    session: WebDriverBiDiSession,
+    mut receiver: mpsc::UnboundedReceiver<Value>,
+    channel: String,
+    function: Arc<F>,
+) where
+    F: Fn(Vec<Value>) -> Fut + Send + Sync + 'static,
+    Fut: Future<Output = ExposedFunctionResult> + Send + 'static,
+{
+    while let Some(mut event) = receiver.recv().await {
+        let message = match serde_json::from_value::<MessageParameters>(event["params"].take()) {
+            Ok(message) if message.channel == channel => message,
+            Ok(_) => continue,
+            Err(e) => {
+                debug!("Ignoring malformed script.message event: {e}");
+                continue;
+            }
+        };
+        let call = match &message.data {
+            RemoteValue::PrimitiveProtocolValue(PrimitiveProtocolValue::StringValue(data)) => {
+                serde_json::from_str::<Call>(&data.value)
+            }
+            _ => continue,
+        };
+        let call = match call {
+            Ok(call) => call,
+            Err(e) => {
+                debug!("Ignoring malformed call on channel {channel}: {e}");
+                continue;
+            }
+        };
+
+        // Calls are answered concurrently, so a slow call doesn't hold back the others
+        let mut session = session.clone();
+        let channel = channel.clone();
+        let function = function.clone();
+        tokio::spawn(async move {
+            let payload = match function(call.args).await {
+                Ok(result) => json!({"id": call.id, "result": result}),
+                Err(error) => json!({"id": call.id, "error": error}),
+            };
+            let params = CallFunctionParameters::builder(
+                RESOLVE,
+                false,
+                Target::RealmTarget(RealmTarget::new(message.source.realm)),
+            )
+            .arguments(vec![
+                string_value(channel),
+                string_value(payload.to_string()),
+            ])
+            .build();
+            if let Err(e) = session.script_call_function(params).await {
+                // The page may have navigated away in the meantime
+                debug!("Failed to send the result of call {}: {e}", call.id);
+            }
+        });
+    }
+}
+
+fn string_value(value: String) -> LocalValue {
+    LocalValue::PrimitiveProtocolValue(PrimitiveProtocolValue::StringValue(StringValue::new(value)))
+}
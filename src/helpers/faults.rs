@@ -0,0 +1,241 @@
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use log::debug;
+use reqwest::Url;
+
+use crate::error::HelperError;
+use crate::helpers::routes::{Fulfillment, RouteAction, RouteGuard};
+use crate::helpers::url_pattern;
+use crate::model::common::JsUint;
+use crate::model::network::{Request, RequestData, UrlPattern};
+use crate::session::WebDriverBiDiSession;
+
+// --------------------------------------------------
+
+/// A fault injected into a request.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Fault {
+    /// Wait before continuing the request.
+    Delay(Duration),
+    /// Fail the request with a network error.
+    Fail,
+    /// Answer the request with an empty response of the given status.
+    Status(JsUint),
+}
+
+/// Selects the requests a fault is injected into.
+///
+/// A rule without conditions matches every request. The fault is injected into
+/// a matching request with the probability of the rule.
+#[derive(Debug, Clone)]
+pub struct FaultRule {
+    pub fault: Fault,
+    pub pattern: Option<UrlPattern>,
+    /// The request destinations, such as `script` or `image`, any if `None`.
+    pub destinations: Option<Vec<String>>,
+    /// The request methods, any if `None`.
+    pub methods: Option<Vec<String>>,
+    /// The probability, from 0 to 1, of injecting the fault into a matching request.
+    pub probability: f64,
+}
+
+impl FaultRule {
+    /// Create a rule injecting the fault into every request.
+    pub fn new(fault: Fault) -> Self {
+        Self {
+            fault,
+            pattern: None,
+            destinations: None,
+            methods: None,
+            probability: 1.0,
+        }
+    }
+
+    /// Only match the requests whose URL matches the pattern.
+    pub fn pattern(mut self, pattern: UrlPattern) -> Self {
+        self.pattern = Some(pattern);
+        self
+    }
+
+    /// Only match the requests with the given destination, `""` for `fetch` calls.
+    pub fn destination(mut self, destination: impl Into<String>) -> Self {
+        self.destinations
+            .get_or_insert_with(Vec::new)
+            .push(destination.into());
+        self
+    }
+
+    /// Only match the requests with the given method.
+    pub fn method(mut self, method: impl Into<String>) -> Self {
+        self.methods
+            .get_or_insert_with(Vec::new)
+            .push(method.into());
+        self
+    }
+
+    /// Set the probability of injecting the fault, clamped between 0 and 1.
+    pub fn probability(mut self, probability: f64) -> Self {
+        self.probability = probability.clamp(0.0, 1.0);
+        self
+    }
+
+    /// Return whether a request matches the conditions of the rule.
+    pub fn matches(&self, url: &str, method: &str, destination: &str) -> bool {
+        self.methods.as_ref().is_none_or(|methods| {
+            methods
+                .iter()
+                .any(|expected| expected.eq_ignore_ascii_case(method))
+        }) && self
+            .destinations
+            .as_ref()
+            .is_none_or(|destinations| destinations.iter().any(|expected| expected == destination))
+            && self.pattern.as_ref().is_none_or(|pattern| {
+                Url::parse(url).is_ok_and(|url| url_pattern::matches(pattern, &url))
+            })
+    }
+}
+
+/// A fault injected by a `FaultInjector`.
+#[derive(Debug, Clone)]
+pub struct InjectedFault {
+    pub request: Request,
+    pub url: String,
+    pub fault: Fault,
+}
+
+// A SplitMix64 generator, so that a seed replays the same faults without an extra
+// dependency
+#[derive(Debug)]
+struct Rng(u64);
+
+impl Rng {
+    fn next_f64(&mut self) -> f64 {
+        self.0 = self.0.wrapping_add(0x9E37_79B9_7F4A_7C15);
+        let mut z = self.0;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        z ^= z >> 31;
+        // The 53 high bits make a uniform float in [0, 1)
+        (z >> 11) as f64 / (1u64 << 53) as f64
+    }
+}
+
+#[derive(Debug)]
+struct InjectorState {
+    rules: Vec<FaultRule>,
+    rng: Rng,
+    injected: Vec<InjectedFault>,
+}
+
+/// Injects delays and failures into the requests of a session.
+///
+/// The requests matching the URL patterns of the rules are routed through a single
+/// route and checked against the rules in order. Each matching rule draws from a
+/// generator seeded with the given seed when the request arrives, so that a run
+/// replays the same faults as long as the requests arrive in the same order. The
+/// delays of the rules firing for a request add up, and the first firing `Fail` or
+/// `Status` rule decides its outcome; a request no such rule fires for is continued
+/// once delayed.
+pub struct FaultInjector {
+    state: Arc<Mutex<InjectorState>>,
+    guard: Option<RouteGuard>,
+}
+
+impl FaultInjector {
+    /// Start injecting the faults.
+    ///
+    /// # Arguments
+    ///
+    /// * `session` - The session to inject the faults into.
+    /// * `rules` - The rules selecting the requests and their faults.
+    /// * `seed` - The seed of the probability draws.
+    pub async fn new(
+        session: &mut WebDriverBiDiSession,
+        rules: Vec<FaultRule>,
+        seed: u64,
+    ) -> Result<Self, HelperError> {
+        // Only a rule without a pattern needs every request to be intercepted, and no
+        // request at all is intercepted without rules
+        let has_rules = !rules.is_empty();
+        let patterns = rules
+            .iter()
+            .map(|rule| rule.pattern.clone())
+            .collect::<Option<Vec<_>>>()
+            .unwrap_or_default();
+        let state = Arc::new(Mutex::new(InjectorState {
+            rules,
+            rng: Rng(seed),
+            injected: Vec::new(),
+        }));
+
+        if !has_rules {
+            return Ok(Self { state, guard: None });
+        }
+        let injector = state.clone();
+        let guard = session
+            .route_patterns(patterns, move |params| {
+                // The faults are drawn before the handler returns, in the order the
+                // requests arrive
+                let (delay, action) = decide(&injector, &params.request);
+                async move {
+                    // The other requests aren't delayed along with this one
+                    tokio::time::sleep(delay).await;
+                    action.unwrap_or_default()
+                }
+            })
+            .await?;
+
+        Ok(Self {
+            state,
+            guard: Some(guard),
+        })
+    }
+
+    /// Return the faults injected so far.
+    pub async fn injected(&self) -> Vec<InjectedFault> {
+        self.state
+            .lock()
+            .expect("the state lock is never poisoned")
+            .injected
+            .clone()
+    }
+
+    /// Stop injecting the faults.
+    pub async fn close(mut self) -> Result<(), HelperError> {
+        match self.guard.take() {
+            Some(guard) => guard.remove().await,
+            None => Ok(()),
+        }
+    }
+}
+
+// The total delay of a request and its outcome, `None` to continue it
+fn decide(state: &Mutex<InjectorState>, request: &RequestData) -> (Duration, Option<RouteAction>) {
+    let mut delay = Duration::ZERO;
+    let mut action: Option<RouteAction> = None;
+    let mut state = state.lock().expect("the state lock is never poisoned");
+    let state = &mut *state;
+    for rule in &state.rules {
+        if !rule.matches(&request.url, &request.method, &request.destination) {
+            continue;
+        }
+        if state.rng.next_f64() >= rule.probability {
+            continue;
+        }
+        match rule.fault {
+            Fault::Delay(duration) => delay += duration,
+            // The outcome is already decided
+            _ if action.is_some() => continue,
+            Fault::Fail => action = Some(RouteAction::Abort),
+            Fault::Status(status) => action = Some(RouteAction::Fulfill(Fulfillment::new(status))),
+        }
+        debug!("Injecting {:?} into {}", rule.fault, request.url);
+        state.injected.push(InjectedFault {
+            request: request.request.clone(),
+            url: request.url.clone(),
+            fault: rule.fault.clone(),
+        });
+    }
+    (delay, action)
+}
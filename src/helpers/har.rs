@@ -0,0 +1,729 @@
+use std::sync::Arc;
+
+use log::debug;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use tokio::sync::{Mutex, mpsc};
+use tokio::task::JoinHandle;
+
+use crate::error::HelperError;
+use crate::events::EventType;
+use crate::helpers::bodies::bytes_to_text;
+use crate::helpers::context_tree::{ContextTree, TreeReader};
+use crate::model::browser::UserContext;
+use crate::model::browsing_context::BrowsingContext;
+use crate::model::common::JsUint;
+use crate::model::network::{
+    AddDataCollectorParameters, BaseParameters, BeforeRequestSentParameters, BytesValue, Collector,
+    Cookie, FetchErrorParameters, FetchTimingInfo, GetDataParameters, Header,
+    RemoveDataCollectorParameters, Request, RequestData, ResponseCompletedParameters, ResponseData,
+    ResponseStartedParameters,
+};
+use crate::model::session::{SubscriptionRequest, UnsubscribeByIDRequest, UnsubscribeParameters};
+use crate::session::WebDriverBiDiSession;
+
+// --------------------------------------------------
+
+const EVENTS: [EventType; 4] = [
+    EventType::NetworkBeforeRequestSent,
+    EventType::NetworkResponseStarted,
+    EventType::NetworkResponseCompleted,
+    EventType::NetworkFetchError,
+];
+
+/// A HAR 1.2 document.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Har {
+    pub log: HarLog,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HarLog {
+    pub version: String,
+    pub creator: HarCreator,
+    pub entries: Vec<HarEntry>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HarCreator {
+    pub name: String,
+    pub version: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct HarEntry {
+    /// The start of the request, as an ISO 8601 date.
+    pub started_date_time: String,
+    /// The total time of the request in milliseconds.
+    pub time: f64,
+    pub request: HarRequest,
+    pub response: HarResponse,
+    pub cache: HarCache,
+    pub timings: HarTimings,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub comment: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct HarRequest {
+    pub method: String,
+    pub url: String,
+    pub http_version: String,
+    pub cookies: Vec<HarCookie>,
+    pub headers: Vec<HarNameValue>,
+    pub query_string: Vec<HarNameValue>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub post_data: Option<HarPostData>,
+    /// The size of the headers in bytes, -1 if unknown.
+    pub headers_size: i64,
+    /// The size of the body in bytes, -1 if unknown.
+    pub body_size: i64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct HarResponse {
+    pub status: JsUint,
+    pub status_text: String,
+    pub http_version: String,
+    pub cookies: Vec<HarCookie>,
+    pub headers: Vec<HarNameValue>,
+    pub content: HarContent,
+    #[serde(rename = "redirectURL")]
+    pub redirect_url: String,
+    /// The size of the headers in bytes, -1 if unknown.
+    pub headers_size: i64,
+    /// The size of the body in bytes, -1 if unknown.
+    pub body_size: i64,
+    /// The network error of a failed request, with a status of 0.
+    #[serde(rename = "_error", default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct HarCookie {
+    pub name: String,
+    pub value: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub path: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub domain: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub expires: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub http_only: Option<bool>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub secure: Option<bool>,
+}
+
+/// A header or a query string parameter.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct HarNameValue {
+    pub name: String,
+    pub value: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct HarPostData {
+    pub mime_type: String,
+    pub text: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct HarContent {
+    pub size: i64,
+    pub mime_type: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub text: Option<String>,
+    /// `base64` when the text is base64 encoded.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub encoding: Option<String>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct HarCache {}
+
+/// The phases of a request in milliseconds, -1 when they don't apply.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct HarTimings {
+    #[serde(default = "not_applicable")]
+    pub blocked: f64,
+    #[serde(default = "not_applicable")]
+    pub dns: f64,
+    #[serde(default = "not_applicable")]
+    pub connect: f64,
+    pub send: f64,
+    pub wait: f64,
+    pub receive: f64,
+    /// The TLS negotiation, also included in `connect`.
+    #[serde(default = "not_applicable")]
+    pub ssl: f64,
+}
+
+// The optional timings are -1 when they're missing
+fn not_applicable() -> f64 {
+    -1.0
+}
+
+impl HarTimings {
+    /// Return the total time of the phases.
+    pub fn total(&self) -> f64 {
+        [
+            self.blocked,
+            self.dns,
+            self.connect,
+            self.send,
+            self.wait,
+            self.receive,
+        ]
+        .iter()
+        .filter(|phase| **phase >= 0.0)
+        .sum()
+    }
+}
+
+impl From<&FetchTimingInfo> for HarTimings {
+    fn from(timings: &FetchTimingInfo) -> Self {
+        // The fetch timings are 0 for the phases that didn't happen
+        let span = |start: f64, end: f64| {
+            if start > 0.0 && end >= start {
+                end - start
+            } else {
+                -1.0
+            }
+        };
+        Self {
+            blocked: -1.0,
+            dns: span(timings.dns_start, timings.dns_end),
+            connect: span(timings.connect_start, timings.connect_end),
+            send: 0.0,
+            wait: span(timings.request_start, timings.response_start).max(0.0),
+            receive: span(timings.response_start, timings.response_end).max(0.0),
+            ssl: span(timings.tls_start, timings.connect_end),
+        }
+    }
+}
+
+/// The browsing contexts whose requests are recorded.
+#[derive(Debug, Clone)]
+pub enum HarScope {
+    /// The requests of every browsing context, including the ones without a context.
+    All,
+    /// The requests of the given top-level browsing contexts and their iframes.
+    Contexts(Vec<BrowsingContext>),
+    /// The requests of the browsing contexts of the given user contexts.
+    UserContexts(Vec<UserContext>),
+}
+
+#[derive(Debug)]
+struct PendingEntry {
+    started: JsUint,
+    redirect_count: JsUint,
+    request: RequestData,
+    response: Option<ResponseData>,
+    request_body: Option<BytesValue>,
+    body: Option<BytesValue>,
+    error: Option<String>,
+    done: bool,
+}
+
+#[derive(Debug, Default)]
+struct Recording {
+    entries: Vec<PendingEntry>,
+}
+
+impl Recording {
+    fn entry_mut(&mut self, base: &BaseParameters) -> Option<&mut PendingEntry> {
+        self.entries.iter_mut().rev().find(|entry| {
+            entry.request.request == base.request.request
+                && entry.redirect_count == base.redirect_count
+        })
+    }
+}
+
+/// Records the requests of a session as a HAR 1.2 document.
+///
+/// The entries are built from the network events, the request and response bodies
+/// are only captured when a maximum size is given, through a network data collector.
+/// The browsing contexts of the requests are looked up in a `ContextTree` when the
+/// scope isn't `HarScope::All`.
+pub struct HarRecorder {
+    session: WebDriverBiDiSession,
+    subscription: Option<String>,
+    collector: Option<Collector>,
+    tree: Option<ContextTree>,
+    recording: Arc<Mutex<Recording>>,
+    task: JoinHandle<()>,
+}
+
+impl HarRecorder {
+    /// Subscribe to the network events and start recording.
+    ///
+    /// # Arguments
+    ///
+    /// * `session` - The session to record the requests of.
+    /// * `scope` - The browsing contexts to record the requests of.
+    /// * `max_body_size` - The maximum size of the captured request and response
+    ///   bodies, `None` to not capture them.
+    pub async fn new(
+        session: &mut WebDriverBiDiSession,
+        scope: HarScope,
+        max_body_size: Option<JsUint>,
+    ) -> Result<Self, HelperError> {
+        let (contexts, user_contexts) = match &scope {
+            HarScope::All => (None, None),
+            HarScope::Contexts(contexts) => (Some(contexts.clone()), None),
+            HarScope::UserContexts(user_contexts) => (None, Some(user_contexts.clone())),
+        };
+
+        let tree = match &scope {
+            HarScope::All => None,
+            _ => Some(ContextTree::new(session).await?),
+        };
+        let receiver = session.add_event_listener(EVENTS.to_vec()).await;
+        let events = EVENTS.iter().map(|e| e.as_str().to_string()).collect();
+        let subscription = session
+            .session_subscribe(SubscriptionRequest::new(
+                events,
+                contexts.clone(),
+                user_contexts.clone(),
+            ))
+            .await?
+            .subscription;
+
+        let collector = match max_body_size {
+            Some(max_body_size) => {
+                let params = AddDataCollectorParameters {
+                    data_types: vec!["request".to_string(), "response".to_string()],
+                    max_encoded_data_size: max_body_size,
+                    collector_type: None,
+                    contexts,
+                    user_contexts,
+                };
+                Some(session.network_add_data_collector(params).await?.collector)
+            }
+            None => None,
+        };
+
+        let recording = Arc::new(Mutex::new(Recording::default()));
+        let reader = tree.as_ref().map(ContextTree::reader);
+        let task = tokio::spawn(record(
+            receiver,
+            session.clone(),
+            collector.clone(),
+            scope,
+            reader,
+            recording.clone(),
+        ));
+
+        Ok(Self {
+            session: session.clone(),
+            subscription,
+            collector,
+            tree,
+            recording,
+            task,
+        })
+    }
+
+    /// Return the HAR document of the requests finished so far.
+    pub async fn har(&self) -> Har {
+        let recording = self.recording.lock().await;
+        let mut entries: Vec<&PendingEntry> = recording
+            .entries
+            .iter()
+            .filter(|entry| entry.done)
+            .collect();
+        entries.sort_by_key(|entry| entry.started);
+
+        Har {
+            log: HarLog {
+                version: "1.2".to_string(),
+                creator: HarCreator {
+                    name: env!("CARGO_PKG_NAME").to_string(),
+                    version: env!("CARGO_PKG_VERSION").to_string(),
+                },
+                entries: entries.into_iter().map(har_entry).collect(),
+            },
+        }
+    }
+
+    /// Forget the requests recorded so far.
+    pub async fn clear(&self) {
+        self.recording.lock().await.entries.clear();
+    }
+
+    /// Stop recording, remove the data collector and unsubscribe from the events.
+    pub async fn close(mut self) -> Result<(), HelperError> {
+        self.task.abort();
+        if let Some(tree) = self.tree.take() {
+            tree.close().await?;
+        }
+        if let Some(collector) = self.collector.take() {
+            self.session
+                .network_remove_data_collector(RemoveDataCollectorParameters::new(collector))
+                .await?;
+        }
+        if let Some(subscription) = self.subscription.take() {
+            self.session
+                .session_unsubscribe(UnsubscribeParameters::UnsubscribeByIDRequest(
+                    UnsubscribeByIDRequest::new(vec![subscription]),
+                ))
+                .await?;
+        }
+        Ok(())
+    }
+}
+
+impl Drop for HarRecorder {
+    fn drop(&mut self) {
+        self.task.abort();
+    }
+}
+
+async fn record(
+    mut receiver: mpsc::UnboundedReceiver<Value>,
+    mut session: WebDriverBiDiSession,
+    collector: Option<Collector>,
+    scope: HarScope,
+    reader: Option<TreeReader>,
+    recording: Arc<Mutex<Recording>>,
+) {
+    while let Some(mut event) = receiver.recv().await {
+        let method = event["method"].as_str().unwrap_or_default().to_string();
+        let params = event["params"].take();
+
+        let result = if method == EventType::NetworkBeforeRequestSent.as_str() {
+            serde_json::from_value::<BeforeRequestSentParameters>(params)
+                .map(|params| Change::Request(Box::new(params.base)))
+        } else if method == EventType::NetworkResponseStarted.as_str() {
+            serde_json::from_value::<ResponseStartedParameters>(params)
+                .map(|params| Change::Response(Box::new(params.base), Box::new(params.response)))
+        } else if method == EventType::NetworkResponseCompleted.as_str() {
+            serde_json::from_value::<ResponseCompletedParameters>(params)
+                .map(|params| Change::Completed(Box::new(params.base), Box::new(params.response)))
+        } else {
+            serde_json::from_value::<FetchErrorParameters>(params)
+                .map(|params| Change::Failed(Box::new(params.base), params.error_text))
+        };
+        let change = match result {
+            Ok(change) => change,
+            Err(e) => {
+                debug!("Ignoring malformed {method} event: {e}");
+                continue;
+            }
+        };
+
+        // The other changes only apply to the entries of the recorded requests
+        if let Change::Request(base) = &change
+            && !in_scope(&scope, reader.as_ref(), base.context.as_ref()).await
+        {
+            continue;
+        }
+
+        let completed = {
+            let mut recording = recording.lock().await;
+            match change {
+                Change::Request(base) => {
+                    recording.entries.push(PendingEntry {
+                        started: base.timestamp,
+                        redirect_count: base.redirect_count,
+                        request: base.request,
+                        response: None,
+                        request_body: None,
+                        body: None,
+                        error: None,
+                        done: false,
+                    });
+                    None
+                }
+                Change::Response(base, response) => {
+                    if let Some(entry) = recording.entry_mut(&base) {
+                        entry.response = Some(*response);
+                    }
+                    None
+                }
+                Change::Completed(base, response) => {
+                    // The requests without a body, such as most GET requests, have
+                    // no request data to fetch
+                    let has_body = base.request.body_size != Some(0);
+                    let completed = (base.request.request.clone(), base.redirect_count, has_body);
+                    recording.entry_mut(&base).map(|entry| {
+                        // The request data of the completion has the final timings
+                        entry.request = base.request;
+                        entry.response = Some(*response);
+                        completed
+                    })
+                }
+                Change::Failed(base, error) => {
+                    if let Some(entry) = recording.entry_mut(&base) {
+                        entry.error = Some(error);
+                        entry.done = true;
+                    }
+                    None
+                }
+            }
+        };
+
+        let Some((request, redirect_count, has_body)) = completed else {
+            continue;
+        };
+        // Only mark the entry as done once its bodies are known, so that a HAR
+        // document never misses the bodies of a finished request
+        let (request_body, body) = match &collector {
+            Some(collector) => {
+                let request_body = match has_body {
+                    true => get_data(&mut session, collector, "request", &request).await,
+                    false => None,
+                };
+                let body = get_data(&mut session, collector, "response", &request).await;
+                (request_body, body)
+            }
+            None => (None, None),
+        };
+        let mut recording = recording.lock().await;
+        if let Some(entry) = recording.entries.iter_mut().rev().find(|entry| {
+            entry.request.request == request && entry.redirect_count == redirect_count
+        }) {
+            entry.request_body = request_body;
+            entry.body = body;
+            entry.done = true;
+        }
+    }
+}
+
+// Whether the requests of a browsing context are recorded, the requests without a
+// context only being recorded with `HarScope::All`
+async fn in_scope(
+    scope: &HarScope,
+    reader: Option<&TreeReader>,
+    context: Option<&BrowsingContext>,
+) -> bool {
+    let (Some(reader), Some(context)) = (reader, context) else {
+        return matches!(scope, HarScope::All);
+    };
+    match scope {
+        HarScope::All => true,
+        HarScope::Contexts(contexts) => reader
+            .top_level_of(context)
+            .await
+            .is_some_and(|top_level| contexts.contains(&top_level.context)),
+        HarScope::UserContexts(user_contexts) => reader
+            .get(context)
+            .await
+            .is_some_and(|node| user_contexts.contains(&node.user_context)),
+    }
+}
+
+async fn get_data(
+    session: &mut WebDriverBiDiSession,
+    collector: &Collector,
+    data_type: &str,
+    request: &Request,
+) -> Option<BytesValue> {
+    let params = GetDataParameters::builder(data_type, request.clone())
+        .collector(collector.clone())
+        .disown(true)
+        .build();
+    match session.network_get_data(params).await {
+        Ok(result) => Some(result.bytes),
+        Err(e) => {
+            debug!("No {data_type} body collected for request {request}: {e}");
+            None
+        }
+    }
+}
+
+enum Change {
+    Request(Box<BaseParameters>),
+    Response(Box<BaseParameters>, Box<ResponseData>),
+    Completed(Box<BaseParameters>, Box<ResponseData>),
+    Failed(Box<BaseParameters>, String),
+}
+
+fn har_entry(entry: &PendingEntry) -> HarEntry {
+    let request = &entry.request;
+    let http_version = entry
+        .response
+        .as_ref()
+        .map(|response| response.protocol.clone())
+        .unwrap_or_default();
+    let timings = HarTimings::from(&request.timings);
+
+    HarEntry {
+        started_date_time: format_timestamp(entry.started),
+        time: timings.total(),
+        request: HarRequest {
+            method: request.method.clone(),
+            url: request.url.clone(),
+            http_version: http_version.clone(),
+            cookies: request.cookies.iter().map(har_cookie).collect(),
+            headers: har_headers(&request.headers),
+            query_string: query_string(&request.url),
+            post_data: entry.request_body.as_ref().map(|body| HarPostData {
+                mime_type: header_values(&request.headers, "content-type")
+                    .next()
+                    .unwrap_or_default(),
+                text: bytes_to_text(body),
+            }),
+            headers_size: request.headers_size as i64,
+            body_size: request.body_size.map_or(-1, |size| size as i64),
+        },
+        response: match &entry.response {
+            Some(response) => har_response(response, entry.body.as_ref(), http_version),
+            None => failed_response(entry.error.clone()),
+        },
+        cache: HarCache::default(),
+        timings,
+        comment: None,
+    }
+}
+
+fn har_response(
+    response: &ResponseData,
+    body: Option<&BytesValue>,
+    http_version: String,
+) -> HarResponse {
+    let (text, encoding) = match body {
+        Some(BytesValue::StringValue(value)) => (Some(value.value.clone()), None),
+        Some(BytesValue::Base64Value(value)) => {
+            (Some(value.value.clone()), Some("base64".to_string()))
+        }
+        None => (None, None),
+    };
+
+    HarResponse {
+        status: response.status,
+        status_text: response.status_text.clone(),
+        http_version,
+        cookies: header_values(&response.headers, "set-cookie")
+            .filter_map(|set_cookie| set_cookie_name_value(&set_cookie))
+            .collect(),
+        headers: har_headers(&response.headers),
+        content: HarContent {
+            size: response.content.size as i64,
+            mime_type: response.mime_type.clone(),
+            text,
+            encoding,
+        },
+        redirect_url: header_values(&response.headers, "location")
+            .next()
+            .unwrap_or_default(),
+        headers_size: response.headers_size.map_or(-1, |size| size as i64),
+        body_size: response.body_size.map_or(-1, |size| size as i64),
+        error: None,
+    }
+}
+
+fn failed_response(error: Option<String>) -> HarResponse {
+    HarResponse {
+        status: 0,
+        status_text: String::new(),
+        http_version: String::new(),
+        cookies: Vec::new(),
+        headers: Vec::new(),
+        content: HarContent {
+            size: 0,
+            mime_type: String::new(),
+            text: None,
+            encoding: None,
+        },
+        redirect_url: String::new(),
+        headers_size: -1,
+        body_size: -1,
+        error,
+    }
+}
+
+fn header_values<'a>(headers: &'a [Header], name: &'a str) -> impl Iterator<Item = String> + 'a {
+    headers
+        .iter()
+        .filter(move |header| header.name.eq_ignore_ascii_case(name))
+        .map(|header| bytes_to_text(&header.value))
+}
+
+fn har_cookie(cookie: &Cookie) -> HarCookie {
+    HarCookie {
+        name: cookie.name.clone(),
+        value: bytes_to_text(&cookie.value),
+        path: Some(cookie.path.clone()),
+        domain: Some(cookie.domain.clone()),
+        expires: cookie.expiry.map(|expiry| format_timestamp(expiry * 1000)),
+        http_only: Some(cookie.http_only),
+        secure: Some(cookie.secure),
+    }
+}
+
+fn har_headers(headers: &[Header]) -> Vec<HarNameValue> {
+    headers
+        .iter()
+        .map(|header| HarNameValue {
+            name: header.name.clone(),
+            value: bytes_to_text(&header.value),
+        })
+        .collect()
+}
+
+fn set_cookie_name_value(set_cookie: &str) -> Option<HarCookie> {
+    let pair = set_cookie.split(';').next()?;
+    let (name, value) = pair.split_once('=')?;
+    Some(HarCookie {
+        name: name.trim().to_string(),
+        value: value.trim().to_string(),
+        path: None,
+        domain: None,
+        expires: None,
+        http_only: None,
+        secure: None,
+    })
+}
+
+/// Return the parameters of the query string of a URL, without decoding them.
+pub fn query_string(url: &str) -> Vec<HarNameValue> {
+    let Some((_, query)) = url.split_once('?') else {
+        return Vec::new();
+    };
+    let query = query.split('#').next().unwrap_or_default();
+    query
+        .split('&')
+        .filter(|pair| !pair.is_empty())
+        .map(|pair| {
+            let (name, value) = pair.split_once('=').unwrap_or((pair, ""));
+            HarNameValue {
+                name: name.to_string(),
+                value: value.to_string(),
+            }
+        })
+        .collect()
+}
+
+/// Format a timestamp, in milliseconds since the epoch, as an ISO 8601 UTC date.
+pub fn format_timestamp(timestamp: JsUint) -> String {
+    let millis = timestamp % 1000;
+    let seconds = timestamp / 1000;
+    let (hours, minutes, seconds) = (seconds % 86_400 / 3600, seconds % 3600 / 60, seconds % 60);
+
+    // Convert the days since the epoch to a proleptic Gregorian date, computed
+    // in eras of 400 years starting on March 1st
+    let days = (timestamp / 86_400_000) as i64 + 719_468;
+    let era = days.div_euclid(146_097);
+    let day_of_era = days.rem_euclid(146_097);
+    let year_of_era =
+        (day_of_era - day_of_era / 1460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let shifted_month = (5 * day_of_year + 2) / 153;
+    let day = day_of_year - (153 * shifted_month + 2) / 5 + 1;
+    let month = if shifted_month < 10 {
+        shifted_month + 3
+    } else {
+        shifted_month - 9
+    };
+    let year = year_of_era + era * 400 + i64::from(month <= 2);
+
+    format!("{year:04}-{month:02}-{day:02}T{hours:02}:{minutes:02}:{seconds:02}.{millis:03}Z")
+}
@@ -0,0 +1,250 @@
+use std::sync::Arc;
+
+use log::debug;
+use tokio::sync::Mutex;
+
+use crate::error::HelperError;
+use crate::helpers::har::{Har, HarEntry};
+use crate::helpers::routes::{Fulfillment, RouteAction, RouteGuard};
+use crate::model::network::{BytesValue, Header, RequestData, UrlPattern, UrlPatternPattern};
+use crate::session::WebDriverBiDiSession;
+
+// --------------------------------------------------
+
+// The body of a HAR entry is decoded, so the headers describing its encoding on the
+// wire no longer apply
+const SKIPPED_HEADERS: [&str; 3] = ["content-encoding", "content-length", "transfer-encoding"];
+
+/// How the URL of a request is compared to the URL of a HAR entry.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum UrlMatch {
+    /// The URLs must be equal, the fragments aside.
+    Exact,
+    /// The URLs must be equal once their query strings and fragments are removed.
+    IgnoreQuery,
+}
+
+/// What happens to a request matching no HAR entry.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum NotFound {
+    /// Send the request to the network.
+    Fallthrough,
+    /// Fail the request with a network error.
+    Abort,
+}
+
+/// The options of a `HarReplay`.
+#[derive(Debug)]
+pub struct HarReplayOptions {
+    pub url_match: UrlMatch,
+    /// Whether the method of the request must match the method of the entry.
+    pub match_method: bool,
+    pub not_found: NotFound,
+    /// The requests to serve from the archive, all of them if `None`.
+    pub pattern: Option<UrlPattern>,
+}
+
+impl Default for HarReplayOptions {
+    fn default() -> Self {
+        Self {
+            url_match: UrlMatch::Exact,
+            match_method: true,
+            not_found: NotFound::Fallthrough,
+            pattern: None,
+        }
+    }
+}
+
+impl HarReplayOptions {
+    /// Create the default options, matching the exact URL and the method and
+    /// sending the unmatched requests to the network.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Set how the URLs are compared.
+    pub fn url_match(mut self, url_match: UrlMatch) -> Self {
+        self.url_match = url_match;
+        self
+    }
+
+    /// Set whether the methods must match.
+    pub fn match_method(mut self, match_method: bool) -> Self {
+        self.match_method = match_method;
+        self
+    }
+
+    /// Set what happens to the unmatched requests.
+    pub fn not_found(mut self, not_found: NotFound) -> Self {
+        self.not_found = not_found;
+        self
+    }
+
+    /// Only serve the requests matching the pattern from the archive.
+    pub fn pattern(mut self, pattern: UrlPattern) -> Self {
+        self.pattern = Some(pattern);
+        self
+    }
+
+    /// Return whether a request matches a HAR entry.
+    pub fn matches(&self, entry: &HarEntry, method: &str, url: &str) -> bool {
+        if self.match_method && !entry.request.method.eq_ignore_ascii_case(method) {
+            return false;
+        }
+        match self.url_match {
+            UrlMatch::Exact => without_fragment(&entry.request.url) == without_fragment(url),
+            UrlMatch::IgnoreQuery => without_query(&entry.request.url) == without_query(url),
+        }
+    }
+}
+
+fn without_fragment(url: &str) -> &str {
+    url.split('#').next().unwrap_or_default()
+}
+
+fn without_query(url: &str) -> &str {
+    without_fragment(url).split('?').next().unwrap_or_default()
+}
+
+#[derive(Debug)]
+struct ReplayEntry {
+    entry: HarEntry,
+    uses: usize,
+}
+
+/// Serves the responses of a HAR archive to the browser.
+///
+/// The requests are routed through `WebDriverBiDiSession::route`. A request is
+/// answered with the first matching entry that wasn't served yet, the last
+/// matching entry being served again once they all were, so that an archive
+/// recording the same request several times replays its responses in order.
+pub struct HarReplay {
+    entries: Arc<Mutex<Vec<ReplayEntry>>>,
+    guard: Option<RouteGuard>,
+}
+
+impl HarReplay {
+    /// Start serving the responses of the archive.
+    ///
+    /// # Arguments
+    ///
+    /// * `session` - The session to serve the responses to.
+    /// * `har` - The archive to serve the responses from.
+    /// * `options` - The matching options as a `HarReplayOptions` instance.
+    pub async fn new(
+        session: &mut WebDriverBiDiSession,
+        har: Har,
+        mut options: HarReplayOptions,
+    ) -> Result<Self, HelperError> {
+        let entries = Arc::new(Mutex::new(
+            har.log
+                .entries
+                .into_iter()
+                .map(|entry| ReplayEntry { entry, uses: 0 })
+                .collect::<Vec<_>>(),
+        ));
+
+        // A pattern without any component matches every URL
+        let pattern = options
+            .pattern
+            .take()
+            .unwrap_or_else(|| UrlPattern::UrlPatternPattern(UrlPatternPattern::builder().build()));
+        let options = Arc::new(options);
+
+        let served = entries.clone();
+        let guard = session
+            .route(pattern, move |request| {
+                let entries = served.clone();
+                let options = options.clone();
+                async move { replay(&entries, &options, request).await }
+            })
+            .await?;
+
+        Ok(Self {
+            entries,
+            guard: Some(guard),
+        })
+    }
+
+    /// Return the entries that weren't served yet.
+    pub async fn unused(&self) -> Vec<HarEntry> {
+        self.entries
+            .lock()
+            .await
+            .iter()
+            .filter(|replay| replay.uses == 0)
+            .map(|replay| replay.entry.clone())
+            .collect()
+    }
+
+    /// Stop serving the responses of the archive.
+    pub async fn close(mut self) -> Result<(), HelperError> {
+        match self.guard.take() {
+            Some(guard) => guard.remove().await,
+            None => Ok(()),
+        }
+    }
+}
+
+async fn replay(
+    entries: &Mutex<Vec<ReplayEntry>>,
+    options: &HarReplayOptions,
+    request: RequestData,
+) -> RouteAction {
+    let mut entries = entries.lock().await;
+    let matching: Vec<usize> = entries
+        .iter()
+        .enumerate()
+        .filter(|(_, replay)| options.matches(&replay.entry, &request.method, &request.url))
+        .map(|(index, _)| index)
+        .collect();
+    let index = matching
+        .iter()
+        .find(|index| entries[**index].uses == 0)
+        .or(matching.last());
+
+    let Some(replay) = index.map(|index| &mut entries[*index]) else {
+        debug!("No HAR entry for {} {}", request.method, request.url);
+        return match options.not_found {
+            NotFound::Fallthrough => RouteAction::default(),
+            NotFound::Abort => RouteAction::Abort,
+        };
+    };
+    replay.uses += 1;
+    response_action(&replay.entry)
+}
+
+fn response_action(entry: &HarEntry) -> RouteAction {
+    let response = &entry.response;
+    // The failed requests are recorded with a status of 0
+    if response.status == 0 {
+        return RouteAction::Abort;
+    }
+
+    let mut fulfillment = Fulfillment::new(response.status);
+    if !response.status_text.is_empty() {
+        fulfillment = fulfillment.reason_phrase(response.status_text.clone());
+    }
+    fulfillment.headers = response
+        .headers
+        .iter()
+        .filter(|header| {
+            !SKIPPED_HEADERS
+                .iter()
+                .any(|skipped| header.name.eq_ignore_ascii_case(skipped))
+        })
+        .map(|header| {
+            Header::new(
+                header.name.clone(),
+                BytesValue::string(header.value.clone()),
+            )
+        })
+        .collect();
+    if let Some(text) = &response.content.text {
+        fulfillment = fulfillment.body(match response.content.encoding.as_deref() {
+            Some("base64") => BytesValue::base64(text.clone()),
+            _ => BytesValue::string(text.clone()),
+        });
+    }
+    RouteAction::Fulfill(fulfillment)
+}
@@ -0,0 +1,236 @@
+use std::time::Duration;
+
+use log::debug;
+use serde_json::Value;
+use tokio::sync::mpsc;
+use tokio::time::{Instant, timeout_at};
+
+use crate::error::HelperError;
+use crate::events::EventType;
+use crate::model::browsing_context::{
+    BrowsingContext, NavigateParameters, Navigation, NavigationInfo, ReadinessState,
+};
+use crate::model::common::JsUint;
+use crate::model::network::{FetchErrorParameters, FetchTimingInfo, ResponseCompletedParameters};
+use crate::model::session::{SubscriptionRequest, UnsubscribeByIDRequest, UnsubscribeParameters};
+use crate::session::WebDriverBiDiSession;
+
+// --------------------------------------------------
+
+const EVENTS: [EventType; 9] = [
+    EventType::BrowsingContextNavigationStarted,
+    EventType::BrowsingContextNavigationCommitted,
+    EventType::BrowsingContextFragmentNavigated,
+    EventType::BrowsingContextNavigationFailed,
+    EventType::BrowsingContextNavigationAborted,
+    EventType::BrowsingContextDomContentLoaded,
+    EventType::BrowsingContextLoad,
+    EventType::NetworkResponseCompleted,
+    EventType::NetworkFetchError,
+];
+
+/// How a navigation ended.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum NavigationState {
+    /// The navigation reached the awaited readiness state.
+    Completed,
+    /// The navigation failed, for example because of a network error.
+    Failed,
+    /// The navigation was aborted, for example by another navigation.
+    Aborted,
+}
+
+/// The timestamps of the navigation events, in milliseconds since the epoch.
+#[derive(Debug, Clone, Default)]
+pub struct NavigationTimings {
+    pub started: Option<JsUint>,
+    pub committed: Option<JsUint>,
+    pub dom_content_loaded: Option<JsUint>,
+    pub load: Option<JsUint>,
+}
+
+/// The outcome of a navigation awaited with `navigate_and_wait`.
+#[derive(Debug, Clone)]
+pub struct NavigationOutcome {
+    pub context: BrowsingContext,
+    pub navigation: Option<Navigation>,
+    pub state: NavigationState,
+    /// The URL of the document, after redirects.
+    pub url: String,
+    /// The HTTP status of the main document response, if there was one.
+    pub status: Option<JsUint>,
+    pub status_text: Option<String>,
+    /// The number of redirects followed to get the main document.
+    pub redirect_count: JsUint,
+    /// The reason of the failure, when the network reported one.
+    pub failure: Option<String>,
+    pub timings: NavigationTimings,
+    /// The fetch timings of the main document response.
+    pub response_timings: Option<FetchTimingInfo>,
+}
+
+pub(crate) async fn navigate_and_wait(
+    session: &mut WebDriverBiDiSession,
+    context: BrowsingContext,
+    url: String,
+    wait: ReadinessState,
+    timeout: Duration,
+) -> Result<NavigationOutcome, HelperError> {
+    let deadline = Instant::now() + timeout;
+    // Listen before navigating, the events may arrive before the command's response
+    let mut receiver = session.add_event_listener(EVENTS.to_vec()).await;
+    let events = EVENTS.iter().map(|e| e.as_str().to_string()).collect();
+    let subscription = session
+        .session_subscribe(SubscriptionRequest::new(
+            events,
+            Some(vec![context.clone()]),
+            None,
+        ))
+        .await?
+        .subscription;
+
+    let result = navigate(session, &mut receiver, context, url, wait, deadline).await;
+
+    if let Some(subscription) = subscription {
+        session
+            .session_unsubscribe(UnsubscribeParameters::UnsubscribeByIDRequest(
+                UnsubscribeByIDRequest::new(vec![subscription]),
+            ))
+            .await?;
+    }
+    result
+}
+
+async fn navigate(
+    session: &mut WebDriverBiDiSession,
+    receiver: &mut mpsc::UnboundedReceiver<Value>,
+    context: BrowsingContext,
+    url: String,
+    wait: ReadinessState,
+    deadline: Instant,
+) -> Result<NavigationOutcome, HelperError> {
+    // The readiness state is awaited through the events, so that a failed or aborted
+    // navigation is reported as such
+    let params = NavigateParameters::builder(context.clone(), url)
+        .wait(ReadinessState::None)
+        .build();
+    let navigate_result = timeout_at(deadline, session.browsing_context_navigate(params))
+        .await
+        .map_err(|_| HelperError::Timeout("the navigation to start".to_string()))??;
+
+    let mut outcome = NavigationOutcome {
+        context,
+        navigation: navigate_result.navigation,
+        state: NavigationState::Completed,
+        url: navigate_result.url,
+        status: None,
+        status_text: None,
+        redirect_count: 0,
+        failure: None,
+        timings: NavigationTimings::default(),
+        response_timings: None,
+    };
+
+    loop {
+        let mut event = timeout_at(deadline, receiver.recv())
+            .await
+            .map_err(|_| HelperError::Timeout(format!("the navigation to {}", outcome.url)))?
+            .ok_or_else(|| HelperError::Other("the session was closed".to_string()))?;
+        let method = event["method"].as_str().unwrap_or_default().to_string();
+        let params = event["params"].take();
+
+        if method == EventType::NetworkResponseCompleted.as_str() {
+            let Ok(params) = serde_json::from_value::<ResponseCompletedParameters>(params) else {
+                debug!("Ignoring malformed {method} event");
+                continue;
+            };
+            if !outcome.is_main_document(params.base.context.as_ref(), &params.base.navigation)
+                || params.base.redirect_count < outcome.redirect_count
+            {
+                continue;
+            }
+            // Each redirect completes a response, the last one is the document's
+            outcome.redirect_count = params.base.redirect_count;
+            outcome.url = params.response.url;
+            outcome.status = Some(params.response.status);
+            outcome.status_text = Some(params.response.status_text);
+            outcome.response_timings = Some(params.base.request.timings);
+            continue;
+        }
+        if method == EventType::NetworkFetchError.as_str() {
+            let Ok(params) = serde_json::from_value::<FetchErrorParameters>(params) else {
+                debug!("Ignoring malformed {method} event");
+                continue;
+            };
+            if outcome.is_main_document(params.base.context.as_ref(), &params.base.navigation) {
+                outcome.failure = Some(params.error_text);
+            }
+            continue;
+        }
+
+        let Ok(info) = serde_json::from_value::<NavigationInfo>(params) else {
+            debug!("Ignoring malformed {method} event");
+            continue;
+        };
+        if !outcome.is_main_document(Some(&info.base.context), &info.base.navigation) {
+            continue;
+        }
+        let timestamp = Some(info.base.timestamp);
+        match EVENTS.iter().find(|e| e.as_str() == method) {
+            Some(EventType::BrowsingContextNavigationStarted) => {
+                outcome.timings.started = timestamp;
+            }
+            Some(EventType::BrowsingContextNavigationCommitted) => {
+                outcome.timings.committed = timestamp;
+                outcome.url = info.base.url;
+                if matches!(wait, ReadinessState::None) {
+                    return Ok(outcome);
+                }
+            }
+            // A same-document navigation doesn't load anything, it's complete as soon
+            // as it's committed
+            Some(EventType::BrowsingContextFragmentNavigated) => {
+                outcome.timings.committed = timestamp;
+                outcome.url = info.base.url;
+                return Ok(outcome);
+            }
+            Some(EventType::BrowsingContextDomContentLoaded) => {
+                outcome.timings.dom_content_loaded = timestamp;
+                if matches!(wait, ReadinessState::Interactive) {
+                    return Ok(outcome);
+                }
+            }
+            Some(EventType::BrowsingContextLoad) => {
+                outcome.timings.load = timestamp;
+                if !matches!(wait, ReadinessState::None | ReadinessState::Interactive) {
+                    return Ok(outcome);
+                }
+            }
+            Some(EventType::BrowsingContextNavigationFailed) => {
+                outcome.state = NavigationState::Failed;
+                return Ok(outcome);
+            }
+            Some(EventType::BrowsingContextNavigationAborted) => {
+                outcome.state = NavigationState::Aborted;
+                return Ok(outcome);
+            }
+            _ => {}
+        }
+    }
+}
+
+impl NavigationOutcome {
+    // Whether an event belongs to the awaited navigation, falling back to its
+    // context when the remote end didn't report a navigation id
+    fn is_main_document(
+        &self,
+        context: Option<&BrowsingContext>,
+        navigation: &Option<Navigation>,
+    ) -> bool {
+        match (&self.navigation, navigation) {
+            (Some(expected), Some(navigation)) => expected == navigation,
+            (None, _) => context == Some(&self.context),
+            (Some(_), None) => false,
+        }
+    }
+}
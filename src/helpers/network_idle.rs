@@ -0,0 +1,245 @@
+use std::collections::{HashMap, HashSet};
+use std::sync::Arc;
+use std::time::Duration;
+
+use log::debug;
+use reqwest::Url;
+use serde_json::Value;
+use tokio::sync::{Mutex, Notify, mpsc};
+use tokio::task::JoinHandle;
+use tokio::time::{Instant, timeout_at};
+
+use crate::error::HelperError;
+use crate::events::EventType;
+use crate::helpers::url_pattern;
+use crate::model::browsing_context::{BrowsingContext, Info};
+use crate::model::network::{BaseParameters, Request, UrlPattern};
+use crate::model::session::{SubscriptionRequest, UnsubscribeByIDRequest, UnsubscribeParameters};
+use crate::session::WebDriverBiDiSession;
+
+// --------------------------------------------------
+
+const EVENTS: [EventType; 4] = [
+    EventType::NetworkBeforeRequestSent,
+    EventType::NetworkResponseCompleted,
+    EventType::NetworkFetchError,
+    EventType::BrowsingContextContextDestroyed,
+];
+
+/// Tracks the requests in flight in each browsing context of a session.
+///
+/// A request is in flight from its `network.beforeRequestSent` event until its
+/// `network.responseCompleted` or `network.fetchError` event. The requests are
+/// counted against the browsing context that issued them, the requests of a
+/// child frame aren't counted against its parent.
+pub struct NetworkIdle {
+    session: WebDriverBiDiSession,
+    subscription: Option<String>,
+    activity: Arc<Mutex<HashMap<BrowsingContext, Activity>>>,
+    notify: Arc<Notify>,
+    task: JoinHandle<()>,
+}
+
+// The requests in flight in a browsing context, and when one last started or finished
+struct Activity {
+    inflight: HashMap<Request, String>,
+    changed_at: Instant,
+}
+
+impl NetworkIdle {
+    /// Subscribe to the network events and start tracking the requests.
+    ///
+    /// # Arguments
+    ///
+    /// * `session` - The session to track the requests of.
+    /// * `ignored` - The URLs of the requests that are never counted, such as
+    ///   long-polling or streaming endpoints that stay open.
+    pub async fn new(
+        session: &mut WebDriverBiDiSession,
+        ignored: Vec<UrlPattern>,
+    ) -> Result<Self, HelperError> {
+        let receiver = session.add_event_listener(EVENTS.to_vec()).await;
+        let events = EVENTS.iter().map(|e| e.as_str().to_string()).collect();
+        let subscription = session
+            .session_subscribe(SubscriptionRequest::new(events, None, None))
+            .await?
+            .subscription;
+
+        let activity = Arc::new(Mutex::new(HashMap::new()));
+        let notify = Arc::new(Notify::new());
+        let task = tokio::spawn(track(receiver, ignored, activity.clone(), notify.clone()));
+
+        Ok(Self {
+            session: session.clone(),
+            subscription,
+            activity,
+            notify,
+            task,
+        })
+    }
+
+    /// Return the URLs of the requests in flight in a browsing context.
+    pub async fn inflight(&self, context: &BrowsingContext) -> Vec<String> {
+        self.activity
+            .lock()
+            .await
+            .get(context)
+            .map(|activity| activity.inflight.values().cloned().collect())
+            .unwrap_or_default()
+    }
+
+    /// Wait until the network of a browsing context is idle.
+    ///
+    /// # Arguments
+    ///
+    /// * `context` - The browsing context to wait for.
+    /// * `quiet_period` - How long no request must start or finish, with the number
+    ///   of requests in flight at or below `max_inflight`.
+    /// * `max_inflight` - The number of requests in flight tolerated while idle.
+    /// * `timeout` - The maximum time to wait for.
+    ///
+    /// # Returns
+    ///
+    /// A result that is a `HelperError::Timeout` if the network didn't settle in time.
+    pub async fn wait_for_network_idle(
+        &self,
+        context: &BrowsingContext,
+        quiet_period: Duration,
+        max_inflight: usize,
+        timeout: Duration,
+    ) -> Result<(), HelperError> {
+        let start = Instant::now();
+        let deadline = start + timeout;
+        loop {
+            // Register for notifications before counting the requests, so that a
+            // request sent in between isn't missed
+            let notified = self.notify.notified();
+            tokio::pin!(notified);
+            notified.as_mut().enable();
+
+            // The quiet period is measured from the last request that started or
+            // finished, even if it did so between two wakeups, but not from before
+            // the call
+            let (count, changed_at) = match self.activity.lock().await.get(context) {
+                Some(activity) => (activity.inflight.len(), activity.changed_at.max(start)),
+                None => (0, start),
+            };
+            let quiet_since = (count <= max_inflight).then_some(changed_at);
+            let now = Instant::now();
+            if quiet_since.is_some_and(|since| since + quiet_period <= now) {
+                return Ok(());
+            }
+            if now >= deadline {
+                return Err(HelperError::Timeout(format!(
+                    "the network of {context} to be idle, {count} requests in flight"
+                )));
+            }
+
+            // Wake up when the quiet period ends or the requests change
+            let wake_at =
+                quiet_since.map_or(deadline, |since| (since + quiet_period).min(deadline));
+            let _ = timeout_at(wake_at, notified).await;
+        }
+    }
+
+    /// Stop tracking the requests and unsubscribe from the network events.
+    pub async fn close(mut self) -> Result<(), HelperError> {
+        self.task.abort();
+        if let Some(subscription) = self.subscription.take() {
+            self.session
+                .session_unsubscribe(UnsubscribeParameters::UnsubscribeByIDRequest(
+                    UnsubscribeByIDRequest::new(vec![subscription]),
+                ))
+                .await?;
+        }
+        Ok(())
+    }
+}
+
+impl Drop for NetworkIdle {
+    fn drop(&mut self) {
+        self.task.abort();
+    }
+}
+
+async fn track(
+    mut receiver: mpsc::UnboundedReceiver<Value>,
+    ignored: Vec<UrlPattern>,
+    activity: Arc<Mutex<HashMap<BrowsingContext, Activity>>>,
+    notify: Arc<Notify>,
+) {
+    // The ignored requests, skipped when they complete too
+    let mut skipped: HashSet<Request> = HashSet::new();
+
+    while let Some(mut event) = receiver.recv().await {
+        let method = event["method"].as_str().unwrap_or_default().to_string();
+        let params = event["params"].take();
+
+        let result = if method == EventType::BrowsingContextContextDestroyed.as_str() {
+            serde_json::from_value::<Info>(params).map(|info| Change::Destroyed(info.context))
+        } else {
+            serde_json::from_value::<BaseParameters>(params).map(|params| {
+                match method == EventType::NetworkBeforeRequestSent.as_str() {
+                    true => Change::Sent(params),
+                    false => Change::Done(params),
+                }
+            })
+        };
+
+        let change = match result {
+            Ok(change) => change,
+            Err(e) => {
+                debug!("Ignoring malformed {method} event: {e}");
+                continue;
+            }
+        };
+
+        let mut activity = activity.lock().await;
+        let now = Instant::now();
+        match change {
+            Change::Sent(params) => {
+                let Some(context) = params.context else {
+                    continue;
+                };
+                let request = params.request.request;
+                let url = params.request.url;
+                let is_ignored = Url::parse(&url)
+                    .is_ok_and(|parsed| ignored.iter().any(|p| url_pattern::matches(p, &parsed)));
+                if is_ignored {
+                    skipped.insert(request);
+                    continue;
+                }
+                // A redirect keeps the request id, the request stays in flight
+                let activity = activity.entry(context).or_insert_with(|| Activity {
+                    inflight: HashMap::new(),
+                    changed_at: now,
+                });
+                activity.inflight.insert(request, url);
+                activity.changed_at = now;
+            }
+            Change::Done(params) => {
+                let request = params.request.request;
+                if skipped.remove(&request) {
+                    continue;
+                }
+                let Some(context) = params.context else {
+                    continue;
+                };
+                if let Some(activity) = activity.get_mut(&context) {
+                    activity.inflight.remove(&request);
+                    activity.changed_at = now;
+                }
+            }
+            Change::Destroyed(context) => {
+                activity.remove(&context);
+            }
+        }
+        notify.notify_waiters();
+    }
+}
+
+enum Change {
+    Sent(BaseParameters),
+    Done(BaseParameters),
+    Destroyed(BrowsingContext),
+}
@@ -0,0 +1,227 @@
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use log::debug;
+use tokio::sync::Mutex;
+
+use crate::error::HelperError;
+use crate::model::browser::UserContext;
+use crate::model::browsing_context::BrowsingContext;
+use crate::model::script::{
+    AddPreloadScriptParameters, PreloadScript, RemovePreloadScriptParameters,
+};
+use crate::session::WebDriverBiDiSession;
+
+// --------------------------------------------------
+
+/// A preload script installed through a `PreloadScripts` manager.
+#[derive(Debug, Clone)]
+pub struct InstalledPreloadScript {
+    /// The id returned when the script was added, identifying it in the manager.
+    pub id: PreloadScript,
+    /// The ids of the copies added by `apply_to_user_context`.
+    pub reapplied: Vec<PreloadScript>,
+    pub function_declaration: String,
+    pub contexts: Option<Vec<BrowsingContext>>,
+    pub user_contexts: Option<Vec<UserContext>>,
+    pub sandbox: Option<String>,
+}
+
+#[derive(Debug)]
+struct Entry {
+    params: AddPreloadScriptParameters,
+    reapplied: Vec<PreloadScript>,
+}
+
+/// Keeps track of the preload scripts added to a session.
+///
+/// Each script added through the manager is removed when the returned guard is
+/// dropped, so independent components can layer their own scripts without keeping
+/// track of the ids. The manager is cheap to clone and its clones share the scripts.
+#[derive(Clone)]
+pub struct PreloadScripts {
+    session: WebDriverBiDiSession,
+    scripts: Arc<Mutex<HashMap<PreloadScript, Entry>>>,
+}
+
+impl PreloadScripts {
+    /// Create a manager adding the preload scripts to the given session.
+    pub fn new(session: &WebDriverBiDiSession) -> Self {
+        Self {
+            session: session.clone(),
+            scripts: Arc::new(Mutex::new(HashMap::new())),
+        }
+    }
+
+    /// Add a preload script.
+    ///
+    /// # Arguments
+    ///
+    /// * `params` - The parameters as an `AddPreloadScriptParameters` instance.
+    ///
+    /// # Returns
+    ///
+    /// A result containing the `PreloadScriptGuard` removing the script once dropped
+    /// or a `HelperError`.
+    pub async fn add(
+        &self,
+        params: AddPreloadScriptParameters,
+    ) -> Result<PreloadScriptGuard, HelperError> {
+        let id = self
+            .session
+            .clone()
+            .script_add_preload_script(params.clone())
+            .await?
+            .script;
+        debug!("Added preload script {id}");
+        self.scripts.lock().await.insert(
+            id.clone(),
+            Entry {
+                params,
+                reapplied: Vec::new(),
+            },
+        );
+
+        Ok(PreloadScriptGuard {
+            manager: self.clone(),
+            id: Some(id),
+        })
+    }
+
+    /// Add some of the scripts scoped to user contexts to a newly created user
+    /// context.
+    ///
+    /// Only the given scripts are copied, so that a component doesn't copy the
+    /// scripts of the others. The copies are removed along with the original script,
+    /// when its guard is dropped. Scripts that aren't scoped to user contexts are left
+    /// untouched, as they either apply to every user context or to specific browsing
+    /// contexts, and the ids the manager doesn't know are ignored.
+    ///
+    /// # Arguments
+    ///
+    /// * `scripts` - The ids of the scripts to add, as returned by
+    ///   `PreloadScriptGuard::id`.
+    /// * `user_context` - The user context to add the scripts to.
+    ///
+    /// # Returns
+    ///
+    /// A result containing the number of scripts added or a `HelperError`.
+    pub async fn apply_to_user_context(
+        &self,
+        scripts: &[PreloadScript],
+        user_context: &UserContext,
+    ) -> Result<usize, HelperError> {
+        let mut entries = self.scripts.lock().await;
+        let mut session = self.session.clone();
+        let mut applied = 0;
+        for id in scripts {
+            let Some(entry) = entries.get_mut(id) else {
+                continue;
+            };
+            match &entry.params.user_contexts {
+                Some(user_contexts) if !user_contexts.contains(user_context) => {}
+                _ => continue,
+            }
+
+            let mut params = entry.params.clone();
+            params.user_contexts = Some(vec![user_context.clone()]);
+            let id = session.script_add_preload_script(params).await?.script;
+            if let Some(user_contexts) = &mut entry.params.user_contexts {
+                user_contexts.push(user_context.clone());
+            }
+            entry.reapplied.push(id);
+            applied += 1;
+        }
+
+        Ok(applied)
+    }
+
+    /// List the installed preload scripts.
+    pub async fn list(&self) -> Vec<InstalledPreloadScript> {
+        self.scripts
+            .lock()
+            .await
+            .iter()
+            .map(|(id, entry)| InstalledPreloadScript {
+                id: id.clone(),
+                reapplied: entry.reapplied.clone(),
+                function_declaration: entry.params.function_declaration.clone(),
+                contexts: entry.params.contexts.clone(),
+                user_contexts: entry.params.user_contexts.clone(),
+                sandbox: entry.params.sandbox.clone(),
+            })
+            .collect()
+    }
+
+    /// Remove the preload script, along with its copies, from the session.
+    ///
+    /// # Arguments
+    ///
+    /// * `id` - The id returned when the script was added.
+    pub async fn remove(&self, id: &PreloadScript) -> Result<(), HelperError> {
+        let Some(entry) = self.scripts.lock().await.remove(id) else {
+            return Ok(());
+        };
+        let mut session = self.session.clone();
+        let mut result = Ok(());
+        // Keep removing the copies when one of the removals fails
+        for script in std::iter::once(id.clone()).chain(entry.reapplied) {
+            if let Err(e) = session
+                .script_remove_preload_script(RemovePreloadScriptParameters::new(script))
+                .await
+            {
+                result = Err(e.into());
+            }
+        }
+        result
+    }
+}
+
+/// Removes a preload script when dropped.
+///
+/// The removal is spawned on the current Tokio runtime, use `remove` to wait for it
+/// and get its result.
+#[must_use = "the preload script is removed as soon as the guard is dropped"]
+pub struct PreloadScriptGuard {
+    manager: PreloadScripts,
+    id: Option<PreloadScript>,
+}
+
+impl PreloadScriptGuard {
+    /// Return the id of the preload script.
+    pub fn id(&self) -> &PreloadScript {
+        self.id.as_ref().expect("the id is only taken on removal")
+    }
+
+    /// Keep the preload script installed for the rest of the session.
+    pub fn forget(mut self) {
+        self.id = None;
+    }
+
+    /// Remove the preload script and wait for the removal.
+    pub async fn remove(mut self) -> Result<(), HelperError> {
+        match self.id.take() {
+            Some(id) => self.manager.remove(&id).await,
+            None => Ok(()),
+        }
+    }
+}
+
+impl Drop for PreloadScriptGuard {
+    fn drop(&mut self) {
+        let Some(id) = self.id.take() else {
+            return;
+        };
+        let manager = self.manager.clone();
+        match tokio::runtime::Handle::try_current() {
+            Ok(handle) => {
+                handle.spawn(async move {
+                    if let Err(e) = manager.remove(&id).await {
+                        debug!("Failed to remove preload script {id}: {e}");
+                    }
+                });
+            }
+            Err(_) => debug!("No runtime to remove preload script {id}"),
+        }
+    }
+}
@@ -0,0 +1,272 @@
+use std::sync::Arc;
+use std::time::Duration;
+
+use log::debug;
+use serde_json::Value;
+use tokio::sync::{Mutex, Notify, mpsc};
+use tokio::task::JoinHandle;
+use tokio::time::{Instant, timeout_at};
+
+use crate::error::HelperError;
+use crate::events::EventType;
+use crate::model::browsing_context::BrowsingContext;
+use crate::model::script::{
+    GetRealmsParameters, Realm, RealmDestroyedParameters, RealmInfo, RealmType, Target,
+};
+use crate::model::session::{SubscriptionRequest, UnsubscribeByIDRequest, UnsubscribeParameters};
+use crate::session::WebDriverBiDiSession;
+
+// --------------------------------------------------
+
+const EVENTS: [EventType; 2] = [
+    EventType::ScriptRealmCreated,
+    EventType::ScriptRealmDestroyed,
+];
+
+/// A live registry of the realms of a session.
+///
+/// The registry is seeded with `script.getRealms` and kept up to date with the
+/// `script.realmCreated` and `script.realmDestroyed` events. The realms are listed
+/// in the order they were created.
+pub struct Realms {
+    session: WebDriverBiDiSession,
+    subscription: Option<String>,
+    realms: Arc<Mutex<Vec<RealmInfo>>>,
+    notify: Arc<Notify>,
+    task: JoinHandle<()>,
+}
+
+impl Realms {
+    /// Subscribe to the realm events and start tracking the realms.
+    pub async fn new(session: &mut WebDriverBiDiSession) -> Result<Self, HelperError> {
+        // Listen before fetching the existing realms, so that none is missed
+        let receiver = session.add_event_listener(EVENTS.to_vec()).await;
+        let events = EVENTS.iter().map(|e| e.as_str().to_string()).collect();
+        let subscription = session
+            .session_subscribe(SubscriptionRequest::new(events, None, None))
+            .await?
+            .subscription;
+        let seed = session
+            .script_get_realms(GetRealmsParameters::new(None, None))
+            .await?
+            .realms;
+
+        let realms = Arc::new(Mutex::new(seed));
+        let notify = Arc::new(Notify::new());
+        let task = tokio::spawn(track(receiver, realms.clone(), notify.clone()));
+
+        Ok(Self {
+            session: session.clone(),
+            subscription,
+            realms,
+            notify,
+            task,
+        })
+    }
+
+    /// Return all the known realms.
+    pub async fn all(&self) -> Vec<RealmInfo> {
+        self.realms.lock().await.clone()
+    }
+
+    /// Return the realm with the given id.
+    pub async fn get(&self, realm: &Realm) -> Option<RealmInfo> {
+        self.find(|info| info.realm() == realm).await
+    }
+
+    /// Return the realms of the given type.
+    pub async fn of_type(&self, realm_type: RealmType) -> Vec<RealmInfo> {
+        self.filter(|info| info.realm_type() == realm_type).await
+    }
+
+    /// Return the window realm of a browsing context.
+    ///
+    /// # Arguments
+    ///
+    /// * `context` - The browsing context of the realm.
+    /// * `sandbox` - The sandbox of the realm, `None` for the page's own realm.
+    pub async fn window(
+        &self,
+        context: &BrowsingContext,
+        sandbox: Option<&str>,
+    ) -> Option<RealmInfo> {
+        self.find(|info| is_window_of(info, context, sandbox)).await
+    }
+
+    /// Return the dedicated workers owned, directly or through other workers, by a
+    /// realm of the given browsing context.
+    pub async fn dedicated_workers(&self, context: &BrowsingContext) -> Vec<RealmInfo> {
+        let realms = self.realms.lock().await;
+        let mut owners: Vec<&Realm> = realms
+            .iter()
+            .filter(|info| matches!(info, RealmInfo::WindowRealmInfo(w) if &w.context == context))
+            .map(RealmInfo::realm)
+            .collect();
+
+        // Workers can spawn workers, so follow the owners until no new worker is found
+        let mut workers: Vec<RealmInfo> = Vec::new();
+        loop {
+            let found: Vec<&RealmInfo> = realms
+                .iter()
+                .filter(|info| match info {
+                    RealmInfo::DedicatedWorkerRealmInfo(worker) => {
+                        !owners.contains(&info.realm())
+                            && worker.owners.iter().any(|owner| owners.contains(&owner))
+                    }
+                    _ => false,
+                })
+                .collect();
+            if found.is_empty() {
+                return workers;
+            }
+            owners.extend(found.iter().map(|info| info.realm()));
+            workers.extend(found.into_iter().cloned());
+        }
+    }
+
+    /// Return a target for the window realm of a browsing context.
+    pub async fn window_target(
+        &self,
+        context: &BrowsingContext,
+        sandbox: Option<&str>,
+    ) -> Option<Target> {
+        self.window(context, sandbox)
+            .await
+            .map(|info| info.target())
+    }
+
+    /// Wait until a realm matching the predicate is known.
+    ///
+    /// # Arguments
+    ///
+    /// * `predicate` - The condition the realm must meet.
+    /// * `timeout` - The maximum time to wait for.
+    ///
+    /// # Returns
+    ///
+    /// A result containing the first matching `RealmInfo` or a `HelperError::Timeout`.
+    pub async fn wait_for<P>(
+        &self,
+        predicate: P,
+        timeout: Duration,
+    ) -> Result<RealmInfo, HelperError>
+    where
+        P: Fn(&RealmInfo) -> bool,
+    {
+        let deadline = Instant::now() + timeout;
+        loop {
+            // Register for notifications before checking the realms, so that a realm
+            // created in between isn't missed
+            let notified = self.notify.notified();
+            tokio::pin!(notified);
+            notified.as_mut().enable();
+
+            if let Some(info) = self.find(&predicate).await {
+                return Ok(info);
+            }
+
+            timeout_at(deadline, notified)
+                .await
+                .map_err(|_| HelperError::Timeout("a matching realm".to_string()))?;
+        }
+    }
+
+    /// Wait until a service worker realm is known.
+    pub async fn wait_for_service_worker(
+        &self,
+        timeout: Duration,
+    ) -> Result<RealmInfo, HelperError> {
+        self.wait_for(
+            |info| matches!(info, RealmInfo::ServiceWorkerRealmInfo(_)),
+            timeout,
+        )
+        .await
+    }
+
+    /// Stop tracking the realms and unsubscribe from the realm events.
+    pub async fn close(mut self) -> Result<(), HelperError> {
+        self.task.abort();
+        if let Some(subscription) = self.subscription.take() {
+            self.session
+                .session_unsubscribe(UnsubscribeParameters::UnsubscribeByIDRequest(
+                    UnsubscribeByIDRequest::new(vec![subscription]),
+                ))
+                .await?;
+        }
+        Ok(())
+    }
+
+    async fn find<P>(&self, predicate: P) -> Option<RealmInfo>
+    where
+        P: Fn(&RealmInfo) -> bool,
+    {
+        self.realms
+            .lock()
+            .await
+            .iter()
+            .find(|info| predicate(info))
+            .cloned()
+    }
+
+    async fn filter<P>(&self, predicate: P) -> Vec<RealmInfo>
+    where
+        P: Fn(&RealmInfo) -> bool,
+    {
+        self.realms
+            .lock()
+            .await
+            .iter()
+            .filter(|info| predicate(info))
+            .cloned()
+            .collect()
+    }
+}
+
+impl Drop for Realms {
+    fn drop(&mut self) {
+        self.task.abort();
+    }
+}
+
+fn is_window_of(info: &RealmInfo, context: &BrowsingContext, sandbox: Option<&str>) -> bool {
+    match info {
+        RealmInfo::WindowRealmInfo(window) => {
+            &window.context == context && window.sandbox.as_deref() == sandbox
+        }
+        _ => false,
+    }
+}
+
+async fn track(
+    mut receiver: mpsc::UnboundedReceiver<Value>,
+    realms: Arc<Mutex<Vec<RealmInfo>>>,
+    notify: Arc<Notify>,
+) {
+    while let Some(mut event) = receiver.recv().await {
+        let method = event["method"].as_str().unwrap_or_default().to_string();
+        let params = event["params"].take();
+
+        if method == EventType::ScriptRealmCreated.as_str() {
+            match serde_json::from_value::<RealmInfo>(params) {
+                Ok(info) => {
+                    let mut realms = realms.lock().await;
+                    // The realm may already be part of the getRealms seed
+                    realms.retain(|known| known.realm() != info.realm());
+                    realms.push(info);
+                    notify.notify_waiters();
+                }
+                Err(e) => debug!("Ignoring malformed {method} event: {e}"),
+            }
+        } else if method == EventType::ScriptRealmDestroyed.as_str() {
+            match serde_json::from_value::<RealmDestroyedParameters>(params) {
+                Ok(destroyed) => {
+                    realms
+                        .lock()
+                        .await
+                        .retain(|known| known.realm() != &destroyed.realm);
+                }
+                Err(e) => debug!("Ignoring malformed {method} event: {e}"),
+            }
+        }
+    }
+}
@@ -0,0 +1,413 @@
+use std::collections::HashSet;
+use std::future::Future;
+use std::panic::{self, AssertUnwindSafe};
+use std::pin::Pin;
+use std::sync::Arc;
+
+use log::debug;
+use serde_json::Value;
+use tokio::sync::{Mutex, mpsc};
+use tokio::task::JoinHandle;
+
+use crate::error::HelperError;
+use crate::events::EventType;
+use crate::model::common::JsUint;
+use crate::model::network::{
+    AddInterceptParameters, BaseParameters, BeforeRequestSentParameters, BytesValue,
+    ContinueRequestParameters, FailRequestParameters, Header, Intercept, InterceptPhase,
+    ProvideResponseParameters, RemoveInterceptParameters, Request, UrlPattern,
+};
+use crate::model::session::SubscriptionRequest;
+use crate::session::WebDriverBiDiSession;
+
+// --------------------------------------------------
+
+type RouteFuture = Pin<Box<dyn Future<Output = RouteAction> + Send>>;
+
+// The handlers get the whole event, so that the helpers built on routes know the
+// browsing context of the request
+type RouteHandler = Arc<dyn Fn(BaseParameters) -> RouteFuture + Send + Sync>;
+
+/// The overrides applied to a request continued by a route.
+///
+/// The fields left to `None` keep the values of the original request.
+#[derive(Debug, Clone, Default)]
+pub struct ContinueOverrides {
+    pub url: Option<String>,
+    pub method: Option<String>,
+    /// The headers sent instead of the original ones.
+    pub headers: Option<Vec<Header>>,
+    pub body: Option<BytesValue>,
+}
+
+impl ContinueOverrides {
+    /// Create overrides continuing the request unchanged.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Send the request to another URL.
+    pub fn url(mut self, url: impl Into<String>) -> Self {
+        self.url = Some(url.into());
+        self
+    }
+
+    /// Send the request with another method.
+    pub fn method(mut self, method: impl Into<String>) -> Self {
+        self.method = Some(method.into());
+        self
+    }
+
+    /// Replace the headers of the request.
+    pub fn headers(mut self, headers: Vec<Header>) -> Self {
+        self.headers = Some(headers);
+        self
+    }
+
+    /// Replace the body of the request.
+    pub fn body(mut self, body: BytesValue) -> Self {
+        self.body = Some(body);
+        self
+    }
+}
+
+/// A response provided by a route instead of the network's.
+#[derive(Debug, Clone)]
+pub struct Fulfillment {
+    pub status: JsUint,
+    pub reason_phrase: Option<String>,
+    pub headers: Vec<Header>,
+    pub body: Option<BytesValue>,
+}
+
+impl Fulfillment {
+    /// Create an empty response with the given HTTP status.
+    pub fn new(status: JsUint) -> Self {
+        Self {
+            status,
+            reason_phrase: None,
+            headers: Vec::new(),
+            body: None,
+        }
+    }
+
+    /// Set the reason phrase of the status line.
+    pub fn reason_phrase(mut self, reason_phrase: impl Into<String>) -> Self {
+        self.reason_phrase = Some(reason_phrase.into());
+        self
+    }
+
+    /// Add a header to the response.
+    pub fn header(mut self, name: impl Into<String>, value: impl Into<String>) -> Self {
+        self.headers
+            .push(Header::new(name.into(), BytesValue::string(value.into())));
+        self
+    }
+
+    /// Set the body of the response.
+    pub fn body(mut self, body: BytesValue) -> Self {
+        self.body = Some(body);
+        self
+    }
+}
+
+/// What a route does with an intercepted request.
+#[derive(Debug, Clone)]
+pub enum RouteAction {
+    /// Send the request to the network, with optional overrides.
+    Continue(ContinueOverrides),
+    /// Answer the request without reaching the network.
+    Fulfill(Fulfillment),
+    /// Fail the request with a network error.
+    Abort,
+    /// Let the previously added route matching the request handle it, or continue
+    /// the request unchanged if there's none.
+    Fallback,
+}
+
+impl Default for RouteAction {
+    /// Continue the request unchanged.
+    fn default() -> Self {
+        Self::Continue(ContinueOverrides::default())
+    }
+}
+
+impl RouteAction {
+    async fn apply(
+        self,
+        session: &mut WebDriverBiDiSession,
+        request: Request,
+    ) -> Result<(), HelperError> {
+        match self {
+            RouteAction::Continue(overrides) => {
+                let params = ContinueRequestParameters::new(
+                    request,
+                    overrides.body,
+                    None,
+                    overrides.headers,
+                    overrides.method,
+                    overrides.url,
+                );
+                session.network_continue_request(params).await?;
+            }
+            RouteAction::Fulfill(fulfillment) => {
+                let headers = (!fulfillment.headers.is_empty()).then_some(fulfillment.headers);
+                let params = ProvideResponseParameters::new(
+                    request,
+                    fulfillment.body,
+                    None,
+                    headers,
+                    fulfillment.reason_phrase,
+                    Some(fulfillment.status),
+                );
+                session.network_provide_response(params).await?;
+            }
+            RouteAction::Abort => {
+                session
+                    .network_fail_request(FailRequestParameters::new(request))
+                    .await?;
+            }
+            // Only applied once no route is left to fall back to
+            RouteAction::Fallback => {
+                let params = ContinueRequestParameters::new(request, None, None, None, None, None);
+                session.network_continue_request(params).await?;
+            }
+        }
+        Ok(())
+    }
+}
+
+struct Route {
+    intercept: Intercept,
+    handler: RouteHandler,
+}
+
+#[derive(Default)]
+struct RouterState {
+    routes: Vec<Route>,
+    // Every intercept added by the router, so that the requests blocked by a route
+    // removed in the meantime are still continued
+    intercepts: HashSet<Intercept>,
+    task: Option<JoinHandle<()>>,
+}
+
+/// The request routes of a session.
+///
+/// Each route adds a `beforeRequestSent` intercept for its URL patterns and a single
+/// task dispatches the blocked requests to the route handlers. The handlers are
+/// called in the order the requests arrive and the futures they return are awaited
+/// concurrently. The router keeps listening for the rest of the session once the
+/// first route is added.
+#[derive(Clone, Default)]
+pub(crate) struct Router {
+    state: Arc<Mutex<RouterState>>,
+}
+
+impl Router {
+    pub(crate) async fn route<F, Fut>(
+        &self,
+        session: &mut WebDriverBiDiSession,
+        patterns: Vec<UrlPattern>,
+        handler: F,
+    ) -> Result<RouteGuard, HelperError>
+    where
+        F: Fn(BaseParameters) -> Fut + Send + Sync + 'static,
+        Fut: Future<Output = RouteAction> + Send + 'static,
+    {
+        let mut state = self.state.lock().await;
+        if state.task.is_none() {
+            let event = EventType::NetworkBeforeRequestSent;
+            let receiver = session.add_event_listener(vec![event]).await;
+            session
+                .session_subscribe(SubscriptionRequest::new(
+                    vec![event.as_str().to_string()],
+                    None,
+                    None,
+                ))
+                .await?;
+            state.task = Some(tokio::spawn(dispatch(
+                receiver,
+                session.clone(),
+                self.clone(),
+            )));
+        }
+
+        // Without any pattern, the intercept blocks every request
+        let patterns = (!patterns.is_empty()).then_some(patterns);
+        let params =
+            AddInterceptParameters::new(vec![InterceptPhase::BeforeRequestSent], None, patterns);
+        let intercept = session.network_add_intercept(params).await?.intercept;
+        debug!("Added route for intercept {intercept}");
+        state.intercepts.insert(intercept.clone());
+        state.routes.push(Route {
+            intercept: intercept.clone(),
+            handler: Arc::new(move |request| Box::pin(handler(request))),
+        });
+
+        Ok(RouteGuard {
+            session: session.clone(),
+            router: self.clone(),
+            intercept: Some(intercept),
+        })
+    }
+
+    async fn remove(
+        &self,
+        session: &mut WebDriverBiDiSession,
+        intercept: &Intercept,
+    ) -> Result<(), HelperError> {
+        let mut state = self.state.lock().await;
+        let count = state.routes.len();
+        state.routes.retain(|route| &route.intercept != intercept);
+        if state.routes.len() < count {
+            session
+                .network_remove_intercept(RemoveInterceptParameters::new(intercept.clone()))
+                .await?;
+        }
+        Ok(())
+    }
+
+    // The handlers of the routes matching the request, the most recently added
+    // first, none when the request was only blocked by removed routes. `None` means
+    // that the request wasn't blocked by the router at all.
+    async fn handlers_for(&self, intercepts: &[Intercept]) -> Option<Vec<RouteHandler>> {
+        let state = self.state.lock().await;
+        if !intercepts
+            .iter()
+            .any(|intercept| state.intercepts.contains(intercept))
+        {
+            return None;
+        }
+        Some(
+            state
+                .routes
+                .iter()
+                .rev()
+                .filter(|route| intercepts.contains(&route.intercept))
+                .map(|route| route.handler.clone())
+                .collect(),
+        )
+    }
+}
+
+/// Removes a route when dropped.
+///
+/// The removal is spawned on the current Tokio runtime, use `remove` to wait for it
+/// and get its result.
+#[must_use = "the route is removed as soon as the guard is dropped"]
+pub struct RouteGuard {
+    session: WebDriverBiDiSession,
+    router: Router,
+    intercept: Option<Intercept>,
+}
+
+impl RouteGuard {
+    /// Return the network intercept of the route.
+    pub fn intercept(&self) -> &Intercept {
+        self.intercept
+            .as_ref()
+            .expect("the intercept is only taken on removal")
+    }
+
+    /// Keep the route for the rest of the session.
+    pub fn forget(mut self) {
+        self.intercept = None;
+    }
+
+    /// Remove the route and wait for the removal.
+    pub async fn remove(mut self) -> Result<(), HelperError> {
+        match self.intercept.take() {
+            Some(intercept) => self.router.remove(&mut self.session, &intercept).await,
+            None => Ok(()),
+        }
+    }
+}
+
+impl Drop for RouteGuard {
+    fn drop(&mut self) {
+        let Some(intercept) = self.intercept.take() else {
+            return;
+        };
+        let router = self.router.clone();
+        let mut session = self.session.clone();
+        match tokio::runtime::Handle::try_current() {
+            Ok(handle) => {
+                handle.spawn(async move {
+                    if let Err(e) = router.remove(&mut session, &intercept).await {
+                        debug!("Failed to remove the route of intercept {intercept}: {e}");
+                    }
+                });
+            }
+            Err(_) => debug!("No runtime to remove the route of intercept {intercept}"),
+        }
+    }
+}
+
+async fn dispatch(
+    mut receiver: mpsc::UnboundedReceiver<Value>,
+    session: WebDriverBiDiSession,
+    router: Router,
+) {
+    while let Some(mut event) = receiver.recv().await {
+        let method = event["method"].as_str().unwrap_or_default().to_string();
+        let params =
+            match serde_json::from_value::<BeforeRequestSentParameters>(event["params"].take()) {
+                Ok(params) => params.base,
+                Err(e) => {
+                    debug!("Ignoring malformed {method} event: {e}");
+                    continue;
+                }
+            };
+        if !params.is_blocked {
+            continue;
+        }
+        let intercepts = params.intercepts.as_deref().unwrap_or_default();
+        let Some(handlers) = router.handlers_for(intercepts).await else {
+            // Blocked by an intercept added outside of the router
+            continue;
+        };
+        // The first handler is called here, so that it sees the requests in the
+        // order they arrive, but its future may be slow and is awaited concurrently
+        let mut handlers = handlers.into_iter();
+        let action = handlers
+            .next()
+            .map(|handler| call(&handler, params.clone()));
+        tokio::spawn(resolve(session.clone(), params, action, handlers));
+    }
+}
+
+// A panicking handler mustn't leave the request blocked, it continues the request
+fn call(handler: &RouteHandler, params: BaseParameters) -> RouteFuture {
+    let id = params.request.request.clone();
+    panic::catch_unwind(AssertUnwindSafe(|| handler(params))).unwrap_or_else(|_| {
+        debug!("Route handler of request {id} panicked");
+        Box::pin(async { RouteAction::default() })
+    })
+}
+
+async fn resolve(
+    mut session: WebDriverBiDiSession,
+    params: BaseParameters,
+    mut action: Option<RouteFuture>,
+    mut fallbacks: impl Iterator<Item = RouteHandler>,
+) {
+    let id = params.request.request.clone();
+    let action = loop {
+        let resolved = match action {
+            Some(action) => tokio::spawn(action).await.unwrap_or_else(|e| {
+                debug!("Route handler of request {id} failed: {e}");
+                RouteAction::default()
+            }),
+            None => RouteAction::default(),
+        };
+        match (resolved, fallbacks.next()) {
+            (RouteAction::Fallback, Some(handler)) => {
+                action = Some(call(&handler, params.clone()));
+            }
+            (resolved, _) => break resolved,
+        }
+    };
+    if let Err(e) = action.apply(&mut session, id.clone()).await {
+        debug!("Failed to resolve request {id}: {e}");
+    }
+}
@@ -0,0 +1,463 @@
+use std::path::{Path, PathBuf};
+
+use base64::Engine;
+use base64::engine::general_purpose::STANDARD;
+use serde::Deserialize;
+
+use crate::error::{CommandError, HelperError};
+use crate::model::browsing_context::{
+    BrowsingContext, CaptureScreenshotParameters, CaptureScreenshotParametersOrigin,
+    CaptureScreenshotResult, ClipRectangle, ElementClipRectangle, ImageFormat,
+    LocateNodesParameters, Locator,
+};
+use crate::model::error::ErrorCode;
+use crate::model::script::{
+    CallFunctionParameters, ContextTarget, EvaluateResult, LocalValue, PrimitiveProtocolValue,
+    RemoteValue, SharedReference, StringValue, Target,
+};
+use crate::session::WebDriverBiDiSession;
+
+// --------------------------------------------------
+
+// The scripts of the stitching fallback run in a sandbox, out of reach of the page
+const SANDBOX: &str = "webdriverbidi-screenshots";
+
+// Returns the size of the document and of the viewport in CSS pixels, along with the
+// scroll position to restore
+const MEASURE: &str = r#"() => {
+    const root = document.scrollingElement ?? document.documentElement;
+    return JSON.stringify({
+        width: root.scrollWidth,
+        height: root.scrollHeight,
+        viewportWidth: document.documentElement.clientWidth,
+        viewportHeight: document.documentElement.clientHeight,
+        scrollX: window.scrollX,
+        scrollY: window.scrollY,
+        devicePixelRatio: window.devicePixelRatio,
+    });
+}"#;
+
+// Scrolls and returns the position actually reached once it has been painted
+const SCROLL: &str = r#"async (x, y) => {
+    window.scrollTo(x, y);
+    await new Promise((resolve) =>
+        requestAnimationFrame(() => requestAnimationFrame(resolve))
+    );
+    return JSON.stringify({ x: window.scrollX, y: window.scrollY });
+}"#;
+
+// Draws the viewport captures, given as base64 along with their positions, on a canvas
+// the size of the document and returns it as base64. The images are decoded from blobs
+// so that the CSP of the page can't block them.
+const STITCH: &str = r#"async (positions, tiles, width, height, ratio, type, quality) => {
+    const canvas = document.createElement("canvas");
+    canvas.width = Math.round(width * ratio);
+    canvas.height = Math.round(height * ratio);
+    const context = canvas.getContext("2d");
+    for (const [index, [x, y]] of positions.entries()) {
+        const bytes = Uint8Array.from(atob(tiles[index]), (c) => c.charCodeAt(0));
+        const bitmap = await createImageBitmap(new Blob([bytes], { type: "image/png" }));
+        context.drawImage(bitmap, Math.round(x * ratio), Math.round(y * ratio));
+        bitmap.close();
+    }
+    const url = canvas.toDataURL(type, quality ?? undefined);
+    return url.slice(url.indexOf(",") + 1);
+}"#;
+
+const PNG_SIGNATURE: &[u8] = b"\x89PNG\r\n\x1a\n";
+const JPEG_SIGNATURE: &[u8] = b"\xff\xd8\xff";
+
+/// The encoding of a screenshot.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ScreenshotFormat {
+    Png,
+    Jpeg,
+}
+
+impl ScreenshotFormat {
+    /// Return the MIME type of the format.
+    pub fn mime_type(&self) -> &'static str {
+        match self {
+            Self::Png => "image/png",
+            Self::Jpeg => "image/jpeg",
+        }
+    }
+
+    /// Return the usual file extension of the format.
+    pub fn extension(&self) -> &'static str {
+        match self {
+            Self::Png => "png",
+            Self::Jpeg => "jpg",
+        }
+    }
+
+    // The format requested with an `ImageFormat`, PNG being the default of the protocol
+    fn requested(format: Option<&ImageFormat>) -> Self {
+        match format.map(|format| format.image_format_type.to_ascii_lowercase()) {
+            Some(format) if format == "image/jpeg" || format == "image/jpg" => Self::Jpeg,
+            _ => Self::Png,
+        }
+    }
+
+    fn sniff(bytes: &[u8]) -> Option<Self> {
+        if bytes.starts_with(PNG_SIGNATURE) {
+            Some(Self::Png)
+        } else if bytes.starts_with(JPEG_SIGNATURE) {
+            Some(Self::Jpeg)
+        } else {
+            None
+        }
+    }
+
+    fn matches_extension(&self, extension: &str) -> bool {
+        match self {
+            Self::Png => extension.eq_ignore_ascii_case("png"),
+            Self::Jpeg => ["jpg", "jpeg"]
+                .iter()
+                .any(|jpeg| extension.eq_ignore_ascii_case(jpeg)),
+        }
+    }
+}
+
+/// A decoded screenshot.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Screenshot {
+    pub format: ScreenshotFormat,
+    pub bytes: Vec<u8>,
+}
+
+impl Screenshot {
+    /// Decode the base64 data of a `CaptureScreenshotResult`.
+    ///
+    /// The format is read from the image itself, as a browser may not support the
+    /// requested one. The requested format is only used for data it doesn't recognize.
+    ///
+    /// # Arguments
+    ///
+    /// * `result` - The result of `browsing_context_capture_screenshot`.
+    /// * `format` - The format passed in the `CaptureScreenshotParameters`.
+    ///
+    /// # Returns
+    ///
+    /// The `Screenshot` or a `HelperError::Other` if the data isn't valid base64.
+    pub fn decode(
+        result: &CaptureScreenshotResult,
+        format: Option<&ImageFormat>,
+    ) -> Result<Self, HelperError> {
+        let bytes = STANDARD
+            .decode(&result.data)
+            .map_err(|e| HelperError::Other(format!("invalid screenshot data: {e}")))?;
+        let format =
+            ScreenshotFormat::sniff(&bytes).unwrap_or_else(|| ScreenshotFormat::requested(format));
+        Ok(Self { format, bytes })
+    }
+
+    /// Write the screenshot to a file.
+    ///
+    /// A path without an extension gets the one of the format, a path whose extension
+    /// names another format is rejected rather than written with a misleading name.
+    ///
+    /// # Returns
+    ///
+    /// A result containing the path of the written file or a `HelperError`.
+    pub async fn save(&self, path: impl AsRef<Path>) -> Result<PathBuf, HelperError> {
+        let path = path.as_ref();
+        let path = match path.extension().and_then(|extension| extension.to_str()) {
+            None => path.with_extension(self.format.extension()),
+            Some(extension) if self.format.matches_extension(extension) => path.to_path_buf(),
+            Some(_) => {
+                return Err(HelperError::Other(format!(
+                    "can't save a {} screenshot as {}",
+                    self.format.mime_type(),
+                    path.display()
+                )));
+            }
+        };
+        tokio::fs::write(&path, &self.bytes)
+            .await
+            .map_err(|e| HelperError::Other(format!("failed to write {}: {e}", path.display())))?;
+        Ok(path)
+    }
+}
+
+/// Capture a screenshot and decode it.
+///
+/// # Arguments
+///
+/// * `session` - The session to capture the screenshot in.
+/// * `params` - The parameters as a `CaptureScreenshotParameters` instance.
+pub async fn capture(
+    session: &mut WebDriverBiDiSession,
+    params: CaptureScreenshotParameters,
+) -> Result<Screenshot, HelperError> {
+    let format = params.format.clone();
+    let result = session.browsing_context_capture_screenshot(params).await?;
+    Screenshot::decode(&result, format.as_ref())
+}
+
+/// Capture a screenshot of an element.
+///
+/// # Arguments
+///
+/// * `session` - The session to capture the screenshot in.
+/// * `context` - The browsing context of the element.
+/// * `element` - The reference of the element, such as the `sharedId` of a node
+///   returned by `browsing_context_locate_nodes`.
+/// * `format` - The format of the image, PNG if `None`.
+pub async fn capture_element(
+    session: &mut WebDriverBiDiSession,
+    context: &BrowsingContext,
+    element: SharedReference,
+    format: Option<ImageFormat>,
+) -> Result<Screenshot, HelperError> {
+    let clip = ClipRectangle::ElementClipRectangle(ElementClipRectangle::new(element));
+    let params = CaptureScreenshotParameters::new(context.clone(), None, format, Some(clip));
+    capture(session, params).await
+}
+
+/// Capture a screenshot of the first element matching a locator.
+///
+/// # Arguments
+///
+/// * `session` - The session to capture the screenshot in.
+/// * `context` - The browsing context to locate the element in.
+/// * `locator` - The locator of the element.
+/// * `format` - The format of the image, PNG if `None`.
+///
+/// # Returns
+///
+/// A result containing the `Screenshot` or a `HelperError::Other` if no element
+/// matches the locator.
+pub async fn capture_locator(
+    session: &mut WebDriverBiDiSession,
+    context: &BrowsingContext,
+    locator: Locator,
+    format: Option<ImageFormat>,
+) -> Result<Screenshot, HelperError> {
+    let params = LocateNodesParameters::builder(context.clone(), locator)
+        .max_node_count(1)
+        .build();
+    let node = session
+        .browsing_context_locate_nodes(params)
+        .await?
+        .nodes
+        .into_iter()
+        .next();
+    let Some(shared_id) = node.and_then(|node| node.shared_id) else {
+        return Err(HelperError::Other(format!(
+            "no element of {context} matches the locator"
+        )));
+    };
+    capture_element(
+        session,
+        context,
+        SharedReference::new(shared_id, None),
+        format,
+    )
+    .await
+}
+
+/// Capture a screenshot of the whole document.
+///
+/// The document origin is used when the browser supports it, the page is otherwise
+/// scrolled and captured one viewport at a time, see `capture_full_page_stitched`.
+///
+/// # Arguments
+///
+/// * `session` - The session to capture the screenshot in.
+/// * `context` - The browsing context to capture.
+/// * `format` - The format of the image, PNG if `None`.
+pub async fn capture_full_page(
+    session: &mut WebDriverBiDiSession,
+    context: &BrowsingContext,
+    format: Option<ImageFormat>,
+) -> Result<Screenshot, HelperError> {
+    let params = CaptureScreenshotParameters::new(
+        context.clone(),
+        Some(CaptureScreenshotParametersOrigin::Document),
+        format.clone(),
+        None,
+    );
+    match capture(session, params).await {
+        Err(HelperError::CommandError(CommandError::Error(response)))
+            if is_unsupported_origin(&response) =>
+        {
+            capture_full_page_stitched(session, context, format).await
+        }
+        result => result,
+    }
+}
+
+/// Capture a screenshot of the whole document by scrolling through it.
+///
+/// Each viewport is captured and the captures are drawn together on a canvas by the
+/// page, so that no image decoder is needed. The scroll position is restored
+/// afterwards. The fixed and sticky elements appear in every viewport, and a document
+/// larger than the maximum canvas size of the browser can't be captured.
+///
+/// # Arguments
+///
+/// * `session` - The session to capture the screenshot in.
+/// * `context` - The browsing context to capture.
+/// * `format` - The format of the image, PNG if `None`.
+pub async fn capture_full_page_stitched(
+    session: &mut WebDriverBiDiSession,
+    context: &BrowsingContext,
+    format: Option<ImageFormat>,
+) -> Result<Screenshot, HelperError> {
+    let metrics: PageMetrics =
+        serde_json::from_str(&call(session, context, MEASURE, vec![]).await?)?;
+    let tiles = capture_tiles(session, context, &metrics).await;
+    // Restore the scroll position even when a capture failed
+    scroll_to(session, context, metrics.scroll_x, metrics.scroll_y).await?;
+    let tiles = tiles?;
+
+    let requested = ScreenshotFormat::requested(format.as_ref());
+    let quality = format.as_ref().and_then(|format| format.quality);
+    let positions: Vec<(f64, f64)> = tiles.iter().map(|tile| (tile.x, tile.y)).collect();
+    // The captures can add up to megabytes, they're passed as arguments rather than
+    // inlined in the declaration
+    let declaration = format!(
+        "(...tiles) => ({STITCH})({}, tiles, {}, {}, {}, {}, {})",
+        serde_json::to_string(&positions)?,
+        metrics.width,
+        metrics.height,
+        metrics.device_pixel_ratio,
+        serde_json::to_string(requested.mime_type())?,
+        serde_json::to_string(&quality)?,
+    );
+    let arguments = tiles
+        .into_iter()
+        .map(|tile| {
+            LocalValue::PrimitiveProtocolValue(PrimitiveProtocolValue::StringValue(
+                StringValue::new(tile.data),
+            ))
+        })
+        .collect();
+    let data = call(session, context, &declaration, arguments).await?;
+    Screenshot::decode(&CaptureScreenshotResult { data }, format.as_ref())
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct PageMetrics {
+    width: f64,
+    height: f64,
+    viewport_width: f64,
+    viewport_height: f64,
+    scroll_x: f64,
+    scroll_y: f64,
+    device_pixel_ratio: f64,
+}
+
+#[derive(Debug)]
+struct Tile {
+    x: f64,
+    y: f64,
+    data: String,
+}
+
+async fn capture_tiles(
+    session: &mut WebDriverBiDiSession,
+    context: &BrowsingContext,
+    metrics: &PageMetrics,
+) -> Result<Vec<Tile>, HelperError> {
+    // A viewport without a size would never reach the end of the document
+    if metrics.viewport_width <= 0.0 || metrics.viewport_height <= 0.0 {
+        return Err(HelperError::Other(format!(
+            "the viewport of {context} is empty"
+        )));
+    }
+
+    let mut tiles = Vec::new();
+    let mut y = 0.0;
+    while y < metrics.height {
+        let mut x = 0.0;
+        while x < metrics.width {
+            // The browser stops at the end of the document, the last tiles overlap
+            // the previous ones
+            let position = scroll_to(session, context, x, y).await?;
+            let params = CaptureScreenshotParameters::new(
+                context.clone(),
+                Some(CaptureScreenshotParametersOrigin::Viewport),
+                None,
+                None,
+            );
+            let data = session
+                .browsing_context_capture_screenshot(params)
+                .await?
+                .data;
+            tiles.push(Tile {
+                x: position.x,
+                y: position.y,
+                data,
+            });
+            x += metrics.viewport_width;
+        }
+        y += metrics.viewport_height;
+    }
+    Ok(tiles)
+}
+
+#[derive(Debug, Deserialize)]
+struct ScrollPosition {
+    x: f64,
+    y: f64,
+}
+
+async fn scroll_to(
+    session: &mut WebDriverBiDiSession,
+    context: &BrowsingContext,
+    x: f64,
+    y: f64,
+) -> Result<ScrollPosition, HelperError> {
+    let declaration = format!("() => ({SCROLL})({x}, {y})");
+    Ok(serde_json::from_str(
+        &call(session, context, &declaration, vec![]).await?,
+    )?)
+}
+
+// Call a function returning a string in the sandbox of the context
+async fn call(
+    session: &mut WebDriverBiDiSession,
+    context: &BrowsingContext,
+    declaration: &str,
+    arguments: Vec<LocalValue>,
+) -> Result<String, HelperError> {
+    let target = Target::ContextTarget(ContextTarget::new(
+        context.clone(),
+        Some(SANDBOX.to_string()),
+    ));
+    let params = CallFunctionParameters::builder(declaration, true, target)
+        .arguments(arguments)
+        .build();
+    match session.script_call_function(params).await? {
+        EvaluateResult::EvaluateResultSuccess(success) => match success.result {
+            RemoteValue::PrimitiveProtocolValue(PrimitiveProtocolValue::StringValue(value)) => {
+                Ok(value.value)
+            }
+            result => Err(HelperError::Other(format!(
+                "unexpected result in {context}: {result:?}"
+            ))),
+        },
+        EvaluateResult::EvaluateResultException(exception) => Err(HelperError::Other(format!(
+            "failed to capture {context}: {}",
+            exception.exception_details.text
+        ))),
+        result => Err(HelperError::Other(format!(
+            "unexpected result in {context}: {result:?}"
+        ))),
+    }
+}
+
+// A browser without the document origin either rejects the operation or the origin
+// argument, the other invalid arguments are real errors
+fn is_unsupported_origin(response: &serde_json::Value) -> bool {
+    match serde_json::from_value::<ErrorCode>(response["error"].clone()) {
+        Ok(ErrorCode::UnsupportedOperation) => true,
+        Ok(ErrorCode::InvalidArgument) => response["message"]
+            .as_str()
+            .is_some_and(|message| message.to_lowercase().contains("origin")),
+        _ => false,
+    }
+}
@@ -0,0 +1,282 @@
+use std::path::Path;
+
+use log::debug;
+use serde::{Deserialize, Serialize};
+
+use crate::error::HelperError;
+use crate::helpers::bodies::bytes_to_text;
+use crate::helpers::cookies::{parse_same_site, settable_same_site};
+use crate::helpers::preload_scripts::{PreloadScriptGuard, PreloadScripts};
+use crate::model::browser::UserContext;
+use crate::model::browsing_context::BrowsingContext;
+use crate::model::network::{BytesValue, Cookie, SameSite};
+use crate::model::script::{
+    AddPreloadScriptParameters, CallFunctionParameters, ContextTarget, EvaluateResult,
+    PrimitiveProtocolValue, RemoteValue, Target,
+};
+use crate::model::storage::{
+    GetCookiesParameters, PartialCookie, PartitionDescriptor, SetCookieParameters,
+    StorageKeyPartitionDescriptor,
+};
+use crate::session::WebDriverBiDiSession;
+
+// --------------------------------------------------
+
+// Returns the origin of the document and the content of its storages as JSON, the
+// storages of an opaque origin can't be accessed
+const COLLECT: &str = r#"() => {
+    const items = (storage) =>
+        Object.entries(storage).map(([name, value]) => ({ name, value }));
+    const origin = location.origin;
+    if (origin === "null") {
+        return JSON.stringify({ origin, localStorage: [] });
+    }
+    return JSON.stringify({
+        origin,
+        localStorage: items(localStorage),
+        sessionStorage: items(sessionStorage),
+    });
+}"#;
+
+// Fills the storages of the document from the state of its origin, keeping the
+// values the page already has so that a reload doesn't undo its changes
+const RESTORE: &str = r#"(origins) => {
+    const state = origins.find((state) => state.origin === location.origin);
+    if (!state) {
+        return;
+    }
+    const fill = (storage, items) => {
+        for (const { name, value } of items ?? []) {
+            if (storage.getItem(name) === null) {
+                storage.setItem(name, value);
+            }
+        }
+    };
+    fill(localStorage, state.localStorage);
+    fill(sessionStorage, state.sessionStorage);
+}"#;
+
+/// The cookies and the web storage of a browser, in the storage state format of
+/// Playwright, extended with the `sessionStorage` of each origin.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct StorageState {
+    pub cookies: Vec<StateCookie>,
+    pub origins: Vec<OriginState>,
+}
+
+/// A cookie of a `StorageState`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StateCookie {
+    pub name: String,
+    pub value: String,
+    pub domain: String,
+    pub path: String,
+    /// The expiry in seconds since the epoch, -1 for a session cookie.
+    pub expires: f64,
+    pub http_only: bool,
+    pub secure: bool,
+    /// `Strict`, `Lax` or `None`, unset when the browser applies its default.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub same_site: Option<String>,
+}
+
+/// The web storage of an origin.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OriginState {
+    pub origin: String,
+    pub local_storage: Vec<StorageItem>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub session_storage: Vec<StorageItem>,
+}
+
+/// An item of a `localStorage` or `sessionStorage`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct StorageItem {
+    pub name: String,
+    pub value: String,
+}
+
+impl From<&Cookie> for StateCookie {
+    fn from(cookie: &Cookie) -> Self {
+        let value = bytes_to_text(&cookie.value);
+        let same_site = settable_same_site(&cookie.same_site).map(|same_site| {
+            match same_site {
+                SameSite::Strict => "Strict",
+                SameSite::None => "None",
+                _ => "Lax",
+            }
+            .to_string()
+        });
+        Self {
+            name: cookie.name.clone(),
+            value,
+            domain: cookie.domain.clone(),
+            path: cookie.path.clone(),
+            expires: cookie.expiry.map_or(-1.0, |expiry| expiry as f64),
+            http_only: cookie.http_only,
+            secure: cookie.secure,
+            same_site,
+        }
+    }
+}
+
+impl From<&StateCookie> for PartialCookie {
+    fn from(cookie: &StateCookie) -> Self {
+        let mut builder = PartialCookie::builder(
+            cookie.name.clone(),
+            BytesValue::string(cookie.value.clone()),
+            cookie.domain.clone(),
+        )
+        .path(cookie.path.clone())
+        .http_only(cookie.http_only)
+        .secure(cookie.secure);
+        let same_site = cookie.same_site.as_deref().map(parse_same_site);
+        if let Some(same_site) = same_site.as_ref().and_then(settable_same_site) {
+            builder = builder.same_site(same_site);
+        }
+        if cookie.expires >= 0.0 {
+            builder = builder.expiry(cookie.expires as u64);
+        }
+        builder.build()
+    }
+}
+
+impl StorageState {
+    /// Collect the cookies of a partition and the web storage of browsing contexts.
+    ///
+    /// The storage of an origin can only be read from a document of that origin, so
+    /// the web storage is read from the given contexts. When several contexts share
+    /// an origin, the `sessionStorage` of the first one is kept. The contexts whose
+    /// document has an opaque origin, such as `about:blank`, have no storage to read
+    /// and are skipped, as are the contexts whose storage can't be read, for example
+    /// because they were closed or their document forbids access to the storage.
+    ///
+    /// # Arguments
+    ///
+    /// * `session` - The session to collect the state from.
+    /// * `partition` - The partition of the cookies, the default one if `None`.
+    /// * `contexts` - The browsing contexts to read the web storage of.
+    pub async fn save(
+        session: &mut WebDriverBiDiSession,
+        partition: Option<PartitionDescriptor>,
+        contexts: &[BrowsingContext],
+    ) -> Result<Self, HelperError> {
+        let cookies = session
+            .storage_get_cookies(GetCookiesParameters::new(None, partition))
+            .await?
+            .cookies;
+
+        let mut origins: Vec<OriginState> = Vec::new();
+        for context in contexts {
+            let origin = match collect_origin(session, context).await {
+                Ok(origin) => origin,
+                Err(e) => {
+                    debug!("Skipping the storage of {context}: {e}");
+                    continue;
+                }
+            };
+            // Opaque origins, such as the one of about:blank, have no storage to restore
+            if origin.origin == "null" || origins.iter().any(|o| o.origin == origin.origin) {
+                continue;
+            }
+            origins.push(origin);
+        }
+
+        Ok(Self {
+            cookies: cookies.iter().map(StateCookie::from).collect(),
+            origins,
+        })
+    }
+
+    /// Restore the state into a user context.
+    ///
+    /// The cookies are set at once. The web storage is filled by a preload script
+    /// when a document of a saved origin is created in the user context, only the
+    /// items the document doesn't have yet are set. The script is removed when the
+    /// returned guard is dropped.
+    ///
+    /// # Arguments
+    ///
+    /// * `session` - The session to restore the state into.
+    /// * `preload_scripts` - The manager adding the preload script.
+    /// * `user_context` - The user context to restore the state into, usually a
+    ///   freshly created one.
+    pub async fn restore(
+        &self,
+        session: &mut WebDriverBiDiSession,
+        preload_scripts: &PreloadScripts,
+        user_context: &UserContext,
+    ) -> Result<PreloadScriptGuard, HelperError> {
+        for cookie in &self.cookies {
+            let partition = PartitionDescriptor::StorageKeyPartitionDescriptor(
+                StorageKeyPartitionDescriptor::builder()
+                    .user_context(user_context.clone())
+                    .build(),
+            );
+            session
+                .storage_set_cookie(SetCookieParameters::new(cookie.into(), Some(partition)))
+                .await?;
+        }
+
+        let origins = serde_json::to_string(&self.origins)?;
+        let declaration = format!("() => ({RESTORE})({origins})");
+        let params = AddPreloadScriptParameters::builder(declaration)
+            .user_contexts(vec![user_context.clone()])
+            .build();
+        preload_scripts.add(params).await
+    }
+
+    /// Serialize the state as JSON.
+    pub fn to_json(&self) -> Result<String, serde_json::Error> {
+        serde_json::to_string_pretty(self)
+    }
+
+    /// Deserialize a state from JSON.
+    pub fn from_json(json: &str) -> Result<Self, serde_json::Error> {
+        serde_json::from_str(json)
+    }
+
+    /// Write the state to a JSON file.
+    pub async fn write(&self, path: impl AsRef<Path>) -> Result<(), HelperError> {
+        let path = path.as_ref();
+        tokio::fs::write(path, self.to_json()?)
+            .await
+            .map_err(|e| HelperError::Other(format!("failed to write {}: {e}", path.display())))
+    }
+
+    /// Read a state from a JSON file.
+    pub async fn read(path: impl AsRef<Path>) -> Result<Self, HelperError> {
+        let path = path.as_ref();
+        let json = tokio::fs::read_to_string(path)
+            .await
+            .map_err(|e| HelperError::Other(format!("failed to read {}: {e}", path.display())))?;
+        Ok(Self::from_json(&json)?)
+    }
+}
+
+async fn collect_origin(
+    session: &mut WebDriverBiDiSession,
+    context: &BrowsingContext,
+) -> Result<OriginState, HelperError> {
+    let target = Target::ContextTarget(ContextTarget::new(context.clone(), None));
+    let params = CallFunctionParameters::builder(COLLECT, false, target).build();
+    match session.script_call_function(params).await? {
+        EvaluateResult::EvaluateResultSuccess(success) => match success.result {
+            RemoteValue::PrimitiveProtocolValue(PrimitiveProtocolValue::StringValue(json)) => {
+                Ok(serde_json::from_str(&json.value)?)
+            }
+            result => Err(HelperError::Other(format!(
+                "unexpected storage of {context}: {result:?}"
+            ))),
+        },
+        EvaluateResult::EvaluateResultException(exception) => Err(HelperError::Other(format!(
+            "failed to read the storage of {context}: {}",
+            exception.exception_details.text
+        ))),
+        result => Err(HelperError::Other(format!(
+            "unexpected storage of {context}: {result:?}"
+        ))),
+    }
+}
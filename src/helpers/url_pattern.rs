@@ -0,0 +1,52 @@
+use reqwest::Url;
+
+use crate::model::network::UrlPattern;
+
+// --------------------------------------------------
+
+/// Return whether a URL matches a BiDi URL pattern.
+///
+/// The components of the pattern are compared literally, as the remote end does. The
+/// components missing from a pattern object match any value, while a string pattern
+/// sets all of them, so that one without a query only matches URLs without one. A
+/// port matches the default port of the scheme when the URL doesn't name one.
+pub fn matches(pattern: &UrlPattern, url: &Url) -> bool {
+    let port = url
+        .port_or_known_default()
+        .map(|port| port.to_string())
+        .unwrap_or_default();
+    let (protocol, hostname, pattern_port, pathname, search) = match pattern {
+        UrlPattern::UrlPatternPattern(pattern) => (
+            pattern.protocol.clone(),
+            pattern.hostname.clone(),
+            pattern.port.clone(),
+            pattern.pathname.clone(),
+            pattern.search.clone(),
+        ),
+        UrlPattern::UrlPatternString(pattern) => {
+            let Ok(pattern) = Url::parse(&pattern.pattern) else {
+                return false;
+            };
+            (
+                Some(pattern.scheme().to_string()),
+                pattern.host_str().map(str::to_string),
+                pattern.port_or_known_default().map(|port| port.to_string()),
+                Some(pattern.path().to_string()),
+                Some(pattern.query().unwrap_or_default().to_string()),
+            )
+        }
+    };
+
+    let protocol = protocol.map(|protocol| protocol.trim_end_matches(':').to_lowercase());
+    let pathname = pathname.map(|pathname| match pathname.starts_with('/') {
+        true => pathname,
+        false => format!("/{pathname}"),
+    });
+    let search = search.map(|search| search.trim_start_matches('?').to_string());
+
+    protocol.is_none_or(|protocol| protocol == url.scheme())
+        && hostname.is_none_or(|hostname| Some(hostname.to_lowercase().as_str()) == url.host_str())
+        && pattern_port.is_none_or(|pattern_port| pattern_port == port)
+        && pathname.is_none_or(|pathname| pathname == url.path())
+        && search.is_none_or(|search| search == url.query().unwrap_or_default())
+}
@@ -0,0 +1,239 @@
+use std::sync::Arc;
+
+use log::debug;
+use tokio::sync::Mutex;
+
+use crate::error::HelperError;
+use crate::model::browser::{
+    CreateUserContextParameters, RemoveUserContextParameters, UserContext,
+};
+use crate::model::browsing_context::{
+    BrowsingContext, CloseParameters, CreateParameters, CreateType, GetTreeParameters,
+};
+use crate::model::storage::{
+    DeleteCookiesParameters, PartitionDescriptor, StorageKeyPartitionDescriptor,
+};
+use crate::session::WebDriverBiDiSession;
+
+// --------------------------------------------------
+
+/// A user context that is removed when dropped.
+///
+/// The removal is spawned on the current Tokio runtime, so the context doesn't leak
+/// when a test panics. Use `remove` to wait for it and get its result. A guard
+/// acquired from a `UserContextPool` returns its context to the pool instead.
+#[must_use = "the user context is removed as soon as the guard is dropped"]
+pub struct UserContextGuard {
+    session: WebDriverBiDiSession,
+    user_context: Option<UserContext>,
+    pool: Option<UserContextPool>,
+}
+
+impl UserContextGuard {
+    /// Create a user context.
+    ///
+    /// # Arguments
+    ///
+    /// * `session` - The session to create the user context in.
+    /// * `params` - The parameters as a `CreateUserContextParameters` instance, for the
+    ///   proxy, the certificate errors and the prompt handler of the context.
+    ///
+    /// # Returns
+    ///
+    /// A result containing the `UserContextGuard` or a `HelperError`.
+    pub async fn new(
+        session: &WebDriverBiDiSession,
+        params: CreateUserContextParameters,
+    ) -> Result<Self, HelperError> {
+        let user_context = session
+            .clone()
+            .browser_create_user_context(params)
+            .await?
+            .user_context;
+        debug!("Created user context {user_context}");
+        Ok(Self {
+            session: session.clone(),
+            user_context: Some(user_context),
+            pool: None,
+        })
+    }
+
+    /// Return the id of the user context.
+    pub fn user_context(&self) -> &UserContext {
+        self.user_context
+            .as_ref()
+            .expect("the user context is only taken on removal")
+    }
+
+    /// Open a new tab in the user context.
+    pub async fn new_tab(&self) -> Result<BrowsingContext, HelperError> {
+        let params = CreateParameters::builder(CreateType::Tab)
+            .user_context(self.user_context().clone())
+            .build();
+        Ok(self
+            .session
+            .clone()
+            .browsing_context_create(params)
+            .await?
+            .context)
+    }
+
+    /// Keep the user context for the rest of the session.
+    pub fn forget(mut self) {
+        self.user_context = None;
+    }
+
+    /// Remove the user context, or return it to its pool, and wait for it.
+    pub async fn remove(mut self) -> Result<(), HelperError> {
+        let Some(user_context) = self.user_context.take() else {
+            return Ok(());
+        };
+        match self.pool.take() {
+            Some(pool) => pool.release(user_context).await,
+            None => remove_user_context(&mut self.session, user_context).await,
+        }
+    }
+}
+
+impl Drop for UserContextGuard {
+    fn drop(&mut self) {
+        let Some(user_context) = self.user_context.take() else {
+            return;
+        };
+        let mut session = self.session.clone();
+        let pool = self.pool.take();
+        match tokio::runtime::Handle::try_current() {
+            Ok(handle) => {
+                handle.spawn(async move {
+                    let result = match pool {
+                        Some(pool) => pool.release(user_context.clone()).await,
+                        None => remove_user_context(&mut session, user_context.clone()).await,
+                    };
+                    if let Err(e) = result {
+                        debug!("Failed to release user context {user_context}: {e}");
+                    }
+                });
+            }
+            Err(_) => debug!("No runtime to remove user context {user_context}"),
+        }
+    }
+}
+
+/// A pool of user contexts created with the same parameters.
+///
+/// A released context has its tabs closed and its cookies deleted before it's
+/// handed out again, which is cheaper than creating a new one. The pool is cheap to
+/// clone and its clones share the idle contexts.
+#[derive(Clone)]
+pub struct UserContextPool {
+    session: WebDriverBiDiSession,
+    params: CreateUserContextParameters,
+    idle: Arc<Mutex<Vec<UserContext>>>,
+}
+
+impl UserContextPool {
+    /// Create an empty pool.
+    ///
+    /// # Arguments
+    ///
+    /// * `session` - The session to create the user contexts in.
+    /// * `params` - The parameters of the user contexts created by the pool.
+    pub fn new(session: &WebDriverBiDiSession, params: CreateUserContextParameters) -> Self {
+        Self {
+            session: session.clone(),
+            params,
+            idle: Arc::new(Mutex::new(Vec::new())),
+        }
+    }
+
+    /// Take an idle user context, or create one if none is left.
+    ///
+    /// # Returns
+    ///
+    /// A result containing the `UserContextGuard` returning the context to the pool
+    /// once dropped or a `HelperError`.
+    pub async fn acquire(&self) -> Result<UserContextGuard, HelperError> {
+        let idle = self.idle.lock().await.pop();
+        let mut guard = match idle {
+            Some(user_context) => UserContextGuard {
+                session: self.session.clone(),
+                user_context: Some(user_context),
+                pool: None,
+            },
+            None => UserContextGuard::new(&self.session, self.params.clone()).await?,
+        };
+        guard.pool = Some(self.clone());
+        Ok(guard)
+    }
+
+    /// Return the number of idle user contexts.
+    pub async fn idle(&self) -> usize {
+        self.idle.lock().await.len()
+    }
+
+    /// Remove the idle user contexts.
+    ///
+    /// The contexts still held by guards are removed when they're released.
+    pub async fn close(self) -> Result<(), HelperError> {
+        let idle = std::mem::take(&mut *self.idle.lock().await);
+        let mut session = self.session.clone();
+        let mut result = Ok(());
+        // Keep removing the contexts when one of the removals fails
+        for user_context in idle {
+            if let Err(e) = remove_user_context(&mut session, user_context).await {
+                result = Err(e);
+            }
+        }
+        result
+    }
+
+    // Close the tabs of the context and delete its cookies, a context that can't be
+    // cleared is removed rather than reused
+    async fn release(&self, user_context: UserContext) -> Result<(), HelperError> {
+        let mut session = self.session.clone();
+        if let Err(e) = clear(&mut session, &user_context).await {
+            debug!("Failed to clear user context {user_context}: {e}");
+            return remove_user_context(&mut session, user_context).await;
+        }
+        self.idle.lock().await.push(user_context);
+        Ok(())
+    }
+}
+
+async fn clear(
+    session: &mut WebDriverBiDiSession,
+    user_context: &UserContext,
+) -> Result<(), HelperError> {
+    let contexts = session
+        .browsing_context_get_tree(GetTreeParameters::new(Some(0), None))
+        .await?
+        .contexts;
+    for info in contexts {
+        if &info.user_context == user_context {
+            session
+                .browsing_context_close(CloseParameters::new(info.context, None))
+                .await?;
+        }
+    }
+
+    let partition = PartitionDescriptor::StorageKeyPartitionDescriptor(
+        StorageKeyPartitionDescriptor::builder()
+            .user_context(user_context.clone())
+            .build(),
+    );
+    session
+        .storage_delete_cookies(DeleteCookiesParameters::new(None, Some(partition)))
+        .await?;
+    Ok(())
+}
+
+async fn remove_user_context(
+    session: &mut WebDriverBiDiSession,
+    user_context: UserContext,
+) -> Result<(), HelperError> {
+    session
+        .browser_remove_user_context(RemoveUserContextParameters::new(user_context.clone()))
+        .await?;
+    debug!("Removed user context {user_context}");
+    Ok(())
+}
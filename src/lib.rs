@@ -6,11 +6,13 @@ pub mod session;
 pub mod model {
     pub mod browser;
     pub mod browsing_context;
+    mod builder;
     pub mod command;
     pub mod common;
     pub mod emulation;
     pub mod error;
     pub mod event;
+    mod id;
     pub mod input;
     pub mod log;
     pub mod message;
@@ -38,4 +40,28 @@ mod commands {
 mod command_sender;
 pub mod error;
 pub mod events;
+pub mod helpers {
+    pub mod auth;
+    pub mod blocking;
+    pub mod bodies;
+    pub mod console;
+    pub mod context_tree;
+    pub mod cookies;
+    pub mod downloads;
+    pub mod export;
+    pub mod expose_function;
+    pub mod faults;
+    pub mod har;
+    pub mod har_replay;
+    pub mod navigation;
+    pub mod network_idle;
+    pub mod preload_scripts;
+    pub mod realms;
+    pub mod routes;
+    pub mod screenshots;
+    pub mod storage_state;
+    pub mod url_pattern;
+    pub mod user_contexts;
+}
 mod message_handler;
+pub mod validation;
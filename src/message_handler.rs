@@ -13,7 +13,7 @@ use tokio_tungstenite::tungstenite::protocol::Message;
 use tokio_tungstenite::{MaybeTlsStream, WebSocketStream};
 
 use crate::events::EventType;
-use crate::session::EventHandler;
+use crate::session::{EventHandler, EventListener};
 
 const ID_FIELD: &str = "id";
 const TYPE_FIELD: &str = "type";
@@ -25,6 +25,7 @@ pub async fn handle_messages(
     websocket_stream: Arc<Mutex<WebSocketStream<MaybeTlsStream<TcpStream>>>>,
     pending_commands: Arc<Mutex<HashMap<u64, oneshot::Sender<Value>>>>,
     event_handlers: Arc<Mutex<HashMap<EventType, EventHandler>>>,
+    event_listeners: Arc<Mutex<Vec<EventListener>>>,
 ) {
     loop {
         let message = {
@@ -63,6 +64,12 @@ pub async fn handle_messages(
                             json.get(METHOD_FIELD).and_then(|method| method.as_str())
                         {
                             if let Ok(event_type) = EventType::from_str(event_type_str) {
+                                // Listeners are fed from the loop to preserve the order of the events
+                                event_listeners.lock().await.retain(|listener| {
+                                    !listener.sender.is_closed()
+                                        && (!listener.event_types.contains(&event_type)
+                                            || listener.sender.send(json.clone()).is_ok())
+                                });
                                 let event_handlers = Arc::clone(&event_handlers);
                                 let json = json.clone();
                                 tokio::spawn(async move {
@@ -1,5 +1,6 @@
 use serde::{Deserialize, Serialize};
 
+use crate::define_id;
 use crate::model::session::{ProxyConfiguration, UserPromptHandler};
 use crate::{
     commands::session,
@@ -10,11 +11,12 @@ use crate::{
 #[serde(untagged)]
 pub enum BrowserCommand {
     Close(Close),
-    CreateUserContext(CreateUserContext),
+    CreateUserContext(Box<CreateUserContext>),
     GetClientWindows(GetClientWindows),
     GetUserContexts(GetUserContexts),
     RemoveUserContext(RemoveUserContext),
     SetClientWindowState(SetClientWindowState),
+    SetDownloadBehavior(SetDownloadBehavior),
 }
 
 #[derive(Serialize, Deserialize, Debug)]
@@ -67,9 +69,14 @@ pub enum ClientWindowState {
     Maximized,
     Minimized,
     Normal,
+    #[serde(untagged)]
+    Unknown(String),
 }
 
-pub type UserContext = String;
+define_id!(
+    /// The id of a user context, `browser.UserContext`.
+    UserContext
+);
 
 #[derive(Serialize, Deserialize, Debug)]
 pub struct UserContextInfo {
@@ -107,7 +114,7 @@ impl CreateUserContext {
     }
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct CreateUserContextParameters {
     #[serde(
         rename = "acceptInsecureCerts",
@@ -291,3 +298,74 @@ impl ClientWindowRectState {
         }
     }
 }
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct SetDownloadBehavior {
+    pub method: String,
+    pub params: SetDownloadBehaviorParameters,
+}
+
+impl SetDownloadBehavior {
+    pub fn new(params: SetDownloadBehaviorParameters) -> Self {
+        Self {
+            method: "browser.setDownloadBehavior".to_string(),
+            params,
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct SetDownloadBehaviorParameters {
+    #[serde(rename = "downloadBehavior")]
+    pub download_behavior: Option<DownloadBehavior>,
+    #[serde(rename = "userContexts", skip_serializing_if = "Option::is_none")]
+    pub user_contexts: Option<Vec<UserContext>>,
+}
+
+impl SetDownloadBehaviorParameters {
+    pub fn new(
+        download_behavior: Option<DownloadBehavior>,
+        user_contexts: Option<Vec<UserContext>>,
+    ) -> Self {
+        Self {
+            download_behavior,
+            user_contexts,
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum DownloadBehavior {
+    DownloadBehaviorAllowed(DownloadBehaviorAllowed),
+    DownloadBehaviorDenied(DownloadBehaviorDenied),
+}
+
+impl DownloadBehavior {
+    pub fn allowed(destination_folder: String) -> Self {
+        Self::DownloadBehaviorAllowed(DownloadBehaviorAllowed {
+            download_behavior_type: "allowed".to_string(),
+            destination_folder,
+        })
+    }
+
+    pub fn denied() -> Self {
+        Self::DownloadBehaviorDenied(DownloadBehaviorDenied {
+            download_behavior_type: "denied".to_string(),
+        })
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct DownloadBehaviorAllowed {
+    #[serde(rename = "type")]
+    pub download_behavior_type: String,
+    #[serde(rename = "destinationFolder")]
+    pub destination_folder: String,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct DownloadBehaviorDenied {
+    #[serde(rename = "type")]
+    pub download_behavior_type: String,
+}
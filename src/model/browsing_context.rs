@@ -1,7 +1,10 @@
-use serde::{Deserialize, Serialize};
+use serde::{Deserialize, Deserializer, Serialize, de};
+use serde_json::from_value;
 
+use crate::define_builder;
+use crate::define_id;
 use crate::model::browser::{ClientWindow, UserContext};
-use crate::model::common::{JsInt, JsUint};
+use crate::model::common::{Extensible, JsInt, JsUint};
 use crate::model::script::{NodeRemoteValue, SerializationOptions, SharedReference};
 use crate::model::session::UserPromptHandlerType;
 
@@ -53,7 +56,10 @@ pub enum BrowsingContextEvent {
     UserPromptOpened(UserPromptOpened),
 }
 
-pub type BrowsingContext = String;
+define_id!(
+    /// The id of a navigable, `browsingContext.BrowsingContext`.
+    BrowsingContext
+);
 
 pub type InfoList = Vec<Info>;
 
@@ -112,6 +118,16 @@ impl AccessibilityLocatorValue {
     }
 }
 
+define_builder!(
+    AccessibilityLocatorValue,
+    AccessibilityLocatorValueBuilder,
+    required {},
+    optional {
+        name: into String,
+        role: into String,
+    },
+);
+
 #[derive(Debug, Serialize, Deserialize)]
 pub struct CssLocator {
     #[serde(rename = "type")]
@@ -185,11 +201,29 @@ impl InnerTextLocator {
     }
 }
 
+define_builder!(
+    InnerTextLocator,
+    InnerTextLocatorBuilder,
+    required {
+        value: into String,
+    },
+    optional {
+        ignore_case: value bool,
+        match_type: into InnerTextLocatorMatchType,
+        max_depth: value JsUint,
+    },
+    fixed {
+        locator_type: "innerText".to_string(),
+    },
+);
+
 #[derive(Debug, Serialize, Deserialize)]
 #[serde(rename_all = "lowercase")]
 pub enum InnerTextLocatorMatchType {
     Full,
     Partial,
+    #[serde(untagged)]
+    Unknown(String),
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -230,6 +264,8 @@ pub enum ReadinessState {
     Complete,
     Interactive,
     None,
+    #[serde(untagged)]
+    Unknown(String),
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -239,6 +275,8 @@ pub enum UserPromptType {
     BeforeUnload,
     Confirm,
     Prompt,
+    #[serde(untagged)]
+    Unknown(String),
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -309,14 +347,29 @@ impl CaptureScreenshotParameters {
     }
 }
 
+define_builder!(
+    CaptureScreenshotParameters,
+    CaptureScreenshotParametersBuilder,
+    required {
+        context: into BrowsingContext,
+    },
+    optional {
+        origin: into CaptureScreenshotParametersOrigin,
+        format: into ImageFormat,
+        clip: into ClipRectangle,
+    },
+);
+
 #[derive(Debug, Serialize, Deserialize)]
 #[serde(rename_all = "lowercase")]
 pub enum CaptureScreenshotParametersOrigin {
     Document,
     Viewport,
+    #[serde(untagged)]
+    Unknown(String),
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ImageFormat {
     #[serde(rename = "type")]
     pub image_format_type: String,
@@ -333,6 +386,17 @@ impl ImageFormat {
     }
 }
 
+define_builder!(
+    ImageFormat,
+    ImageFormatBuilder,
+    required {
+        image_format_type: into String,
+    },
+    optional {
+        quality: value f32,
+    },
+);
+
 #[derive(Debug, Serialize, Deserialize)]
 #[serde(untagged)]
 pub enum ClipRectangle {
@@ -414,6 +478,17 @@ impl CloseParameters {
     }
 }
 
+define_builder!(
+    CloseParameters,
+    CloseParametersBuilder,
+    required {
+        context: into BrowsingContext,
+    },
+    optional {
+        prompt_unload: value bool,
+    },
+);
+
 #[derive(Debug, Serialize, Deserialize)]
 pub struct Create {
     pub method: String,
@@ -434,6 +509,8 @@ impl Create {
 pub enum CreateType {
     Tab,
     Window,
+    #[serde(untagged)]
+    Unknown(String),
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -464,6 +541,19 @@ impl CreateParameters {
     }
 }
 
+define_builder!(
+    CreateParameters,
+    CreateParametersBuilder,
+    required {
+        create_type: into CreateType,
+    },
+    optional {
+        reference_context: into BrowsingContext,
+        background: value bool,
+        user_context: into UserContext,
+    },
+);
+
 #[derive(Serialize, Deserialize, Debug)]
 pub struct CreateResult {
     pub context: BrowsingContext,
@@ -498,6 +588,16 @@ impl GetTreeParameters {
     }
 }
 
+define_builder!(
+    GetTreeParameters,
+    GetTreeParametersBuilder,
+    required {},
+    optional {
+        max_depth: value JsUint,
+        root: into BrowsingContext,
+    },
+);
+
 #[derive(Serialize, Deserialize, Debug)]
 pub struct GetTreeResult {
     pub contexts: InfoList,
@@ -537,6 +637,18 @@ impl HandleUserPromptParameters {
     }
 }
 
+define_builder!(
+    HandleUserPromptParameters,
+    HandleUserPromptParametersBuilder,
+    required {
+        context: into BrowsingContext,
+    },
+    optional {
+        accept: value bool,
+        user_text: into String,
+    },
+);
+
 #[derive(Debug, Serialize, Deserialize)]
 pub struct LocateNodes {
     pub method: String,
@@ -585,6 +697,20 @@ impl LocateNodesParameters {
     }
 }
 
+define_builder!(
+    LocateNodesParameters,
+    LocateNodesParametersBuilder,
+    required {
+        context: into BrowsingContext,
+        locator: into Locator,
+    },
+    optional {
+        max_node_count: value JsUint,
+        serialization_options: into SerializationOptions,
+        start_nodes: into Vec<SharedReference>,
+    },
+);
+
 #[derive(Serialize, Deserialize, Debug)]
 pub struct LocateNodesResult {
     pub nodes: Vec<NodeRemoteValue>,
@@ -619,6 +745,18 @@ impl NavigateParameters {
     }
 }
 
+define_builder!(
+    NavigateParameters,
+    NavigateParametersBuilder,
+    required {
+        context: into BrowsingContext,
+        url: into String,
+    },
+    optional {
+        wait: into ReadinessState,
+    },
+);
+
 #[derive(Serialize, Deserialize, Debug)]
 pub struct NavigateResult {
     pub navigation: Option<Navigation>,
@@ -683,11 +821,30 @@ impl PrintParameters {
     }
 }
 
+define_builder!(
+    PrintParameters,
+    PrintParametersBuilder,
+    required {
+        context: into BrowsingContext,
+    },
+    optional {
+        background: value bool,
+        margin: into PrintMarginParameters,
+        orientation: into PrintParametersOrientation,
+        page: into PrintPageParameters,
+        page_ranges: into Vec<JsUintOrText>,
+        scale: value f32,
+        shrink_to_fit: value bool,
+    },
+);
+
 #[derive(Debug, Serialize, Deserialize)]
 #[serde(rename_all = "lowercase")]
 pub enum PrintParametersOrientation {
     Landscape,
     Portrait,
+    #[serde(untagged)]
+    Unknown(String),
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -725,6 +882,18 @@ impl PrintMarginParameters {
     }
 }
 
+define_builder!(
+    PrintMarginParameters,
+    PrintMarginParametersBuilder,
+    required {},
+    optional {
+        bottom: value f32,
+        left: value f32,
+        right: value f32,
+        top: value f32,
+    },
+);
+
 #[derive(Debug, Serialize, Deserialize)]
 pub struct PrintPageParameters {
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -739,6 +908,16 @@ impl PrintPageParameters {
     }
 }
 
+define_builder!(
+    PrintPageParameters,
+    PrintPageParametersBuilder,
+    required {},
+    optional {
+        height: value f32,
+        width: value f32,
+    },
+);
+
 #[derive(Serialize, Deserialize, Debug)]
 pub struct PrintResult {
     pub data: String,
@@ -782,6 +961,18 @@ impl ReloadParameters {
     }
 }
 
+define_builder!(
+    ReloadParameters,
+    ReloadParametersBuilder,
+    required {
+        context: into BrowsingContext,
+    },
+    optional {
+        ignore_cache: value bool,
+        wait: into ReadinessState,
+    },
+);
+
 #[derive(Debug, Serialize, Deserialize)]
 pub struct SetViewport {
     pub method: String,
@@ -825,6 +1016,18 @@ impl SetViewportParameters {
     }
 }
 
+define_builder!(
+    SetViewportParameters,
+    SetViewportParametersBuilder,
+    required {},
+    optional {
+        context: into BrowsingContext,
+        viewport: into Viewport,
+        device_pixel_ratio: value f32,
+        user_contexts: into Vec<UserContext>,
+    },
+);
+
 #[derive(Debug, Serialize, Deserialize)]
 pub struct Viewport {
     pub width: JsUint,
@@ -859,7 +1062,7 @@ pub struct TraverseHistoryParameters {
 }
 
 impl TraverseHistoryParameters {
-    pub fn new(context: String, delta: i64) -> Self {
+    pub fn new(context: BrowsingContext, delta: JsInt) -> Self {
         Self { context, delta }
     }
 }
@@ -936,11 +1139,47 @@ pub struct DownloadEnd {
     pub params: DownloadEndParams,
 }
 
-#[derive(Serialize, Deserialize, Debug)]
+// Both variants share the navigation info fields, so they're told apart by `status`
+// when deserialized
+#[derive(Serialize, Debug)]
 #[serde(untagged)]
 pub enum DownloadEndParams {
     DownloadCanceled(DownloadCanceledParams),
     DownloadComplete(DownloadCompleteParams),
+    Unknown(UnknownDownloadEndParams),
+}
+
+impl<'de> Deserialize<'de> for DownloadEndParams {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let value = serde_json::Value::deserialize(deserializer)?;
+        match value.get("status").and_then(serde_json::Value::as_str) {
+            Some("canceled") => from_value(value).map(DownloadEndParams::DownloadCanceled),
+            Some("complete") => from_value(value).map(DownloadEndParams::DownloadComplete),
+            Some(_) => from_value(value).map(DownloadEndParams::Unknown),
+            None => return Err(de::Error::missing_field("status")),
+        }
+        .map_err(de::Error::custom)
+    }
+}
+
+impl DownloadEndParams {
+    /// Returns the status of the download.
+    pub fn status(&self) -> &str {
+        match self {
+            DownloadEndParams::DownloadCanceled(params) => &params.status,
+            DownloadEndParams::DownloadComplete(params) => &params.status,
+            DownloadEndParams::Unknown(params) => &params.status,
+        }
+    }
+
+    /// Returns the navigation info fields of the download.
+    pub fn base(&self) -> &BaseNavigationInfo {
+        match self {
+            DownloadEndParams::DownloadCanceled(params) => &params.base,
+            DownloadEndParams::DownloadComplete(params) => &params.base,
+            DownloadEndParams::Unknown(params) => &params.base,
+        }
+    }
 }
 
 #[derive(Serialize, Deserialize, Debug)]
@@ -958,6 +1197,16 @@ pub struct DownloadCompleteParams {
     pub base: BaseNavigationInfo,
 }
 
+/// The end of a download with a status this version doesn't know.
+#[derive(Serialize, Deserialize, Debug)]
+pub struct UnknownDownloadEndParams {
+    pub status: String,
+    #[serde(flatten)]
+    pub base: BaseNavigationInfo,
+    #[serde(flatten)]
+    pub extensible: Extensible,
+}
+
 #[derive(Serialize, Deserialize, Debug)]
 pub struct NavigationAborted {
     pub method: String,
@@ -0,0 +1,71 @@
+/// Macro to define a fluent builder for a parameter struct.
+///
+/// This macro generates a builder struct, a `builder` associated function on the
+/// parameter struct taking the required fields, one chainable setter per optional
+/// field and a `build` method returning the parameter struct. Because the required
+/// fields are arguments of `builder`, a parameter struct can't be built without them.
+///
+/// Each field is declared as `name: kind Type` where `kind` is either `into`, for
+/// arguments accepted as `impl Into<Type>`, or `value`, for arguments taken as `Type`
+/// (numbers and booleans, so that literals keep their inferred type).
+///
+/// # Parameters
+///
+/// - `$params`: The parameter struct to build.
+/// - `$builder`: The name of the builder struct to be generated.
+/// - `required`: The required fields and their types.
+/// - `optional`: The optional fields and the types wrapped in their `Option`.
+/// - `fixed`: The fields set to a constant value, such as the `type` tag of a struct.
+#[macro_export]
+macro_rules! define_builder {
+    (@arg into $ty:ty) => { impl Into<$ty> };
+    (@arg value $ty:ty) => { $ty };
+    (
+        $params:ident,
+        $builder:ident,
+        required { $($req:ident: $req_kind:ident $req_ty:ty),* $(,)? },
+        optional { $($opt:ident: $opt_kind:ident $opt_ty:ty),* $(,)? }
+        $(, fixed { $($fixed:ident: $fixed_value:expr),* $(,)? })?
+        $(,)?
+    ) => {
+        #[doc = concat!("Builder for [`", stringify!($params), "`].")]
+        #[derive(Debug)]
+        pub struct $builder {
+            $($req: $req_ty,)*
+            $($opt: Option<$opt_ty>,)*
+        }
+
+        impl $params {
+            #[doc = concat!("Create a [`", stringify!($builder), "`] from the required fields.")]
+            #[allow(clippy::too_many_arguments, clippy::useless_conversion)]
+            pub fn builder(
+                $($req: $crate::define_builder!(@arg $req_kind $req_ty)),*
+            ) -> $builder {
+                $builder {
+                    $($req: $req.into(),)*
+                    $($opt: None,)*
+                }
+            }
+        }
+
+        impl $builder {
+            $(
+                #[doc = concat!("Set the optional `", stringify!($opt), "` field.")]
+                #[allow(clippy::useless_conversion)]
+                pub fn $opt(mut self, $opt: $crate::define_builder!(@arg $opt_kind $opt_ty)) -> Self {
+                    self.$opt = Some($opt.into());
+                    self
+                }
+            )*
+
+            #[doc = concat!("Build the [`", stringify!($params), "`].")]
+            pub fn build(self) -> $params {
+                $params {
+                    $($req: self.$req,)*
+                    $($opt: self.$opt,)*
+                    $($($fixed: $fixed_value,)*)?
+                }
+            }
+        }
+    };
+}
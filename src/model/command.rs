@@ -16,6 +16,7 @@ use crate::model::web_extension::WebExtensionCommand;
 pub struct Command {
     pub id: JsUint,
     pub command_data: CommandData,
+    #[serde(flatten)]
     pub extensible: Extensible,
 }
 
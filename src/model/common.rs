@@ -11,6 +11,7 @@ pub type JsUint = u64;
 
 #[derive(Debug, Serialize, Deserialize)]
 pub struct EmptyParams {
+    #[serde(flatten)]
     pub extensible: Extensible,
 }
 
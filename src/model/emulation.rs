@@ -2,14 +2,68 @@ use serde::{Deserialize, Serialize};
 
 use crate::model::browser::UserContext;
 use crate::model::browsing_context::BrowsingContext;
+use crate::model::common::JsUint;
 
 #[derive(Serialize, Deserialize, Debug)]
 #[serde(untagged)]
 pub enum EmulationCommand {
+    SetForcedColorsModeThemeOverride(SetForcedColorsModeThemeOverride),
     SetGeolocationOverride(SetGeolocationOverride),
     SetLocaleOverride(SetLocaleOverride),
+    SetNetworkConditions(SetNetworkConditions),
     SetScreenOrientationOverride(SetScreenOrientationOverride),
+    SetScreenSettingsOverride(SetScreenSettingsOverride),
+    SetScriptingEnabled(SetScriptingEnabled),
     SetTimezoneOverride(SetTimezoneOverride),
+    SetTouchOverride(SetTouchOverride),
+    SetUserAgentOverride(SetUserAgentOverride),
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct SetForcedColorsModeThemeOverride {
+    pub method: String,
+    pub params: SetForcedColorsModeThemeOverrideParameters,
+}
+
+impl SetForcedColorsModeThemeOverride {
+    pub fn new(params: SetForcedColorsModeThemeOverrideParameters) -> Self {
+        Self {
+            method: "emulation.setForcedColorsModeThemeOverride".to_string(),
+            params,
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ForcedColorsModeTheme {
+    Light,
+    Dark,
+    #[serde(untagged)]
+    Unknown(String),
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct SetForcedColorsModeThemeOverrideParameters {
+    pub theme: Option<ForcedColorsModeTheme>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub contexts: Option<Vec<BrowsingContext>>,
+    #[serde(rename = "userContexts", skip_serializing_if = "Option::is_none")]
+    pub user_contexts: Option<Vec<UserContext>>,
+}
+
+impl SetForcedColorsModeThemeOverrideParameters {
+    pub fn new(
+        theme: Option<ForcedColorsModeTheme>,
+        contexts: Option<Vec<BrowsingContext>>,
+        user_contexts: Option<Vec<UserContext>>,
+    ) -> Self {
+        Self {
+            theme,
+            contexts,
+            user_contexts,
+        }
+    }
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -148,6 +202,65 @@ pub struct SetLocaleOverrideParameters {
     pub user_contexts: Option<Vec<UserContext>>,
 }
 
+#[derive(Debug, Serialize, Deserialize)]
+pub struct SetNetworkConditions {
+    pub method: String,
+    pub params: SetNetworkConditionsParameters,
+}
+
+impl SetNetworkConditions {
+    pub fn new(params: SetNetworkConditionsParameters) -> Self {
+        Self {
+            method: "emulation.setNetworkConditions".to_string(),
+            params,
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum NetworkConditions {
+    NetworkConditionsOffline(NetworkConditionsOffline),
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct NetworkConditionsOffline {
+    #[serde(rename = "type")]
+    pub network_conditions_type: String,
+}
+
+impl NetworkConditions {
+    pub fn offline() -> Self {
+        Self::NetworkConditionsOffline(NetworkConditionsOffline {
+            network_conditions_type: "offline".to_string(),
+        })
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct SetNetworkConditionsParameters {
+    #[serde(rename = "networkConditions")]
+    pub network_conditions: Option<NetworkConditions>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub contexts: Option<Vec<BrowsingContext>>,
+    #[serde(rename = "userContexts", skip_serializing_if = "Option::is_none")]
+    pub user_contexts: Option<Vec<UserContext>>,
+}
+
+impl SetNetworkConditionsParameters {
+    pub fn new(
+        network_conditions: Option<NetworkConditions>,
+        contexts: Option<Vec<BrowsingContext>>,
+        user_contexts: Option<Vec<UserContext>>,
+    ) -> Self {
+        Self {
+            network_conditions,
+            contexts,
+            user_contexts,
+        }
+    }
+}
+
 #[derive(Debug, Serialize, Deserialize)]
 pub struct SetScreenOrientationOverride {
     pub method: String,
@@ -164,19 +277,23 @@ impl SetScreenOrientationOverride {
 }
 
 #[derive(Debug, Serialize, Deserialize)]
-#[serde(rename_all = "lowercase", untagged)]
+#[serde(rename_all = "lowercase")]
 pub enum ScreenOrientationNatural {
     Portrait,
     Landscape,
+    #[serde(untagged)]
+    Unknown(String),
 }
 
 #[derive(Debug, Serialize, Deserialize)]
-#[serde(rename_all = "kebab-case", untagged)]
+#[serde(rename_all = "kebab-case")]
 pub enum ScreenOrientationType {
     PortraitPrimary,
     PortraitSecondary,
     LandscapePrimary,
     LandscapeSecondary,
+    #[serde(untagged)]
+    Unknown(String),
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -196,6 +313,96 @@ pub struct SetScreenOrientationOverrideParameters {
     pub user_contexts: Option<Vec<UserContext>>,
 }
 
+#[derive(Debug, Serialize, Deserialize)]
+pub struct SetScreenSettingsOverride {
+    pub method: String,
+    pub params: SetScreenSettingsOverrideParameters,
+}
+
+impl SetScreenSettingsOverride {
+    pub fn new(params: SetScreenSettingsOverrideParameters) -> Self {
+        Self {
+            method: "emulation.setScreenSettingsOverride".to_string(),
+            params,
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ScreenArea {
+    pub width: JsUint,
+    pub height: JsUint,
+}
+
+impl ScreenArea {
+    pub fn new(width: JsUint, height: JsUint) -> Self {
+        Self { width, height }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct SetScreenSettingsOverrideParameters {
+    #[serde(rename = "screenArea")]
+    pub screen_area: Option<ScreenArea>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub contexts: Option<Vec<BrowsingContext>>,
+    #[serde(rename = "userContexts", skip_serializing_if = "Option::is_none")]
+    pub user_contexts: Option<Vec<UserContext>>,
+}
+
+impl SetScreenSettingsOverrideParameters {
+    pub fn new(
+        screen_area: Option<ScreenArea>,
+        contexts: Option<Vec<BrowsingContext>>,
+        user_contexts: Option<Vec<UserContext>>,
+    ) -> Self {
+        Self {
+            screen_area,
+            contexts,
+            user_contexts,
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct SetScriptingEnabled {
+    pub method: String,
+    pub params: SetScriptingEnabledParameters,
+}
+
+impl SetScriptingEnabled {
+    pub fn new(params: SetScriptingEnabledParameters) -> Self {
+        Self {
+            method: "emulation.setScriptingEnabled".to_string(),
+            params,
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct SetScriptingEnabledParameters {
+    /// `Some(false)` disables scripting, `None` restores the default behavior.
+    pub enabled: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub contexts: Option<Vec<BrowsingContext>>,
+    #[serde(rename = "userContexts", skip_serializing_if = "Option::is_none")]
+    pub user_contexts: Option<Vec<UserContext>>,
+}
+
+impl SetScriptingEnabledParameters {
+    pub fn new(
+        enabled: Option<bool>,
+        contexts: Option<Vec<BrowsingContext>>,
+        user_contexts: Option<Vec<UserContext>>,
+    ) -> Self {
+        Self {
+            enabled,
+            contexts,
+            user_contexts,
+        }
+    }
+}
+
 #[derive(Debug, Serialize, Deserialize)]
 pub struct SetTimezoneOverride {
     pub method: String,
@@ -219,3 +426,81 @@ pub struct SetTimezoneOverrideParameters {
     #[serde(rename = "userContexts", skip_serializing_if = "Option::is_none")]
     pub user_contexts: Option<Vec<UserContext>>,
 }
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct SetTouchOverride {
+    pub method: String,
+    pub params: SetTouchOverrideParameters,
+}
+
+impl SetTouchOverride {
+    pub fn new(params: SetTouchOverrideParameters) -> Self {
+        Self {
+            method: "emulation.setTouchOverride".to_string(),
+            params,
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct SetTouchOverrideParameters {
+    #[serde(rename = "maxTouchPoints")]
+    pub max_touch_points: Option<JsUint>, // 1..
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub contexts: Option<Vec<BrowsingContext>>,
+    #[serde(rename = "userContexts", skip_serializing_if = "Option::is_none")]
+    pub user_contexts: Option<Vec<UserContext>>,
+}
+
+impl SetTouchOverrideParameters {
+    pub fn new(
+        max_touch_points: Option<JsUint>,
+        contexts: Option<Vec<BrowsingContext>>,
+        user_contexts: Option<Vec<UserContext>>,
+    ) -> Self {
+        Self {
+            max_touch_points,
+            contexts,
+            user_contexts,
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct SetUserAgentOverride {
+    pub method: String,
+    pub params: SetUserAgentOverrideParameters,
+}
+
+impl SetUserAgentOverride {
+    pub fn new(params: SetUserAgentOverrideParameters) -> Self {
+        Self {
+            method: "emulation.setUserAgentOverride".to_string(),
+            params,
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct SetUserAgentOverrideParameters {
+    #[serde(rename = "userAgent")]
+    pub user_agent: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub contexts: Option<Vec<BrowsingContext>>,
+    #[serde(rename = "userContexts", skip_serializing_if = "Option::is_none")]
+    pub user_contexts: Option<Vec<UserContext>>,
+}
+
+impl SetUserAgentOverrideParameters {
+    pub fn new(
+        user_agent: Option<String>,
+        contexts: Option<Vec<BrowsingContext>>,
+        user_contexts: Option<Vec<UserContext>>,
+    ) -> Self {
+        Self {
+            user_agent,
+            contexts,
+            user_contexts,
+        }
+    }
+}
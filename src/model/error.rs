@@ -96,4 +96,6 @@ pub enum ErrorCode {
     UnknownError,
     #[serde(rename = "unsupported operation")]
     UnsupportedOperation,
+    #[serde(untagged)]
+    Unknown(String),
 }
@@ -1,3 +1,4 @@
+use crate::define_builder;
 use crate::model::browsing_context::BrowsingContext;
 use crate::model::common::{JsInt, JsUint};
 use crate::model::script::SharedReference;
@@ -139,12 +140,29 @@ impl PointerSourceActions {
     }
 }
 
+define_builder!(
+    PointerSourceActions,
+    PointerSourceActionsBuilder,
+    required {
+        id: into String,
+        actions: into Vec<PointerSourceAction>,
+    },
+    optional {
+        parameters: into PointerParameters,
+    },
+    fixed {
+        pointer_source_actions_type: "pointer".to_string(),
+    },
+);
+
 #[derive(Debug, Serialize, Deserialize)]
 #[serde(rename_all = "lowercase")]
 pub enum PointerType {
     Mouse,
     Pen,
     Touch,
+    #[serde(untagged)]
+    Unknown(String),
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -310,6 +328,23 @@ impl PointerMoveAction {
     }
 }
 
+define_builder!(
+    PointerMoveAction,
+    PointerMoveActionBuilder,
+    required {
+        x: value f64,
+        y: value f64,
+        pointer_common_properties: into PointerCommonProperties,
+    },
+    optional {
+        duration: value JsUint,
+        origin: into Origin,
+    },
+    fixed {
+        pointer_move_action_type: "pointerMove".to_string(),
+    },
+);
+
 #[derive(Debug, Serialize, Deserialize)]
 pub struct WheelScrollAction {
     #[serde(rename = "type")]
@@ -347,6 +382,24 @@ impl WheelScrollAction {
     }
 }
 
+define_builder!(
+    WheelScrollAction,
+    WheelScrollActionBuilder,
+    required {
+        x: value JsInt,
+        y: value JsInt,
+        delta_x: value JsInt,
+        delta_y: value JsInt,
+    },
+    optional {
+        duration: value JsUint,
+        origin: into Origin,
+    },
+    fixed {
+        wheel_scroll_action_type: "scroll".to_string(),
+    },
+);
+
 #[derive(Debug, Serialize, Deserialize)]
 pub struct PointerCommonProperties {
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -387,6 +440,21 @@ impl PointerCommonProperties {
     }
 }
 
+define_builder!(
+    PointerCommonProperties,
+    PointerCommonPropertiesBuilder,
+    required {},
+    optional {
+        width: value JsUint,
+        height: value JsUint,
+        pressure: value f64,
+        tangential_pressure: value f64,
+        twist: value JsUint,
+        altitude_angle: value f64,
+        azimuth_angle: value f64,
+    },
+);
+
 #[derive(Debug, Serialize, Deserialize)]
 #[serde(untagged)]
 pub enum Origin {
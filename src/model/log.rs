@@ -1,4 +1,4 @@
-use serde::{Deserialize, Serialize};
+use serde::{Deserialize, Deserializer, Serialize, de};
 
 use crate::model::common::JsUint;
 use crate::model::script::{RemoteValue, Source, StackTrace};
@@ -9,21 +9,24 @@ pub enum LogEvent {
     EntryAdded(EntryAdded),
 }
 
-#[derive(Serialize, Deserialize, Debug)]
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
 #[serde(rename_all = "lowercase")]
 pub enum Level {
     Debug,
     Info,
     Warn,
     Error,
+    #[serde(untagged)]
+    Unknown(String),
 }
 
 #[derive(Serialize, Deserialize, Debug)]
 #[serde(untagged)]
 pub enum Entry {
-    GenericLogEntry(GenericLogEntry),
+    // The generic entry matches any entry, so it's tried last
     ConsoleLogEntry(ConsoleLogEntry),
     JavascriptLogEntry(JavascriptLogEntry),
+    GenericLogEntry(GenericLogEntry),
 }
 
 #[derive(Serialize, Deserialize, Debug)]
@@ -58,10 +61,27 @@ pub struct ConsoleLogEntry {
 pub struct JavascriptLogEntry {
     #[serde(flatten)]
     pub base: BaseLogEntry,
-    #[serde(rename = "type")]
+    #[serde(rename = "type", deserialize_with = "deserialize_javascript_type")]
     pub log_type: String,
 }
 
+// Only accept the `javascript` type, so that the other entries are left to
+// `GenericLogEntry`
+fn deserialize_javascript_type<'de, D>(deserializer: D) -> Result<String, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    let log_type = String::deserialize(deserializer)?;
+    if log_type == "javascript" {
+        Ok(log_type)
+    } else {
+        Err(de::Error::invalid_value(
+            de::Unexpected::Str(&log_type),
+            &"javascript",
+        ))
+    }
+}
+
 #[derive(Serialize, Deserialize, Debug)]
 pub struct EntryAdded {
     pub method: String,
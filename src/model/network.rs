@@ -1,5 +1,8 @@
-use serde::{Deserialize, Serialize};
+use serde::{Deserialize, Deserializer, Serialize, de};
+use serde_json::from_value;
 
+use crate::define_builder;
+use crate::define_id;
 use crate::model::browser::UserContext;
 use crate::model::browsing_context::{BrowsingContext, Navigation};
 use crate::model::common::{Extensible, JsInt, JsUint};
@@ -37,13 +40,13 @@ pub enum NetworkEvent {
     ResponseStarted(ResponseStarted),
 }
 
-#[derive(Serialize, Deserialize, Debug)]
+#[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct AuthChallenge {
     pub scheme: String,
     pub realm: String,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct AuthCredentials {
     #[serde(rename = "type")]
     pub auth_credentials_type: String,
@@ -61,11 +64,13 @@ impl AuthCredentials {
     }
 }
 
-#[derive(Serialize, Deserialize, Debug)]
+#[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct BaseParameters {
     pub context: Option<BrowsingContext>,
+    #[serde(rename = "isBlocked")]
     pub is_blocked: bool,
     pub navigation: Option<Navigation>,
+    #[serde(rename = "redirectCount")]
     pub redirect_count: JsUint,
     pub request: RequestData,
     pub timestamp: JsUint,
@@ -73,39 +78,86 @@ pub struct BaseParameters {
     pub intercepts: Option<Vec<Intercept>>,
 }
 
-#[derive(Serialize, Deserialize, Debug)]
+// Both variants have the same fields, so they're deserialized by their `type`
+#[derive(Serialize, Debug, Clone, PartialEq, Eq)]
+#[serde(untagged)]
 pub enum BytesValue {
     StringValue(StringValue),
     Base64Value(Base64Value),
 }
 
-#[derive(Serialize, Deserialize, Debug)]
+impl<'de> Deserialize<'de> for BytesValue {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let value = serde_json::Value::deserialize(deserializer)?;
+        match value.get("type").and_then(serde_json::Value::as_str) {
+            Some("string") => from_value(value).map(BytesValue::StringValue),
+            Some("base64") => from_value(value).map(BytesValue::Base64Value),
+            Some(other) => return Err(de::Error::unknown_variant(other, &["string", "base64"])),
+            None => return Err(de::Error::missing_field("type")),
+        }
+        .map_err(de::Error::custom)
+    }
+}
+
+impl BytesValue {
+    /// Create a `string` bytes value.
+    pub fn string(value: impl Into<String>) -> Self {
+        BytesValue::StringValue(StringValue::new(value.into()))
+    }
+
+    /// Create a `base64` bytes value.
+    pub fn base64(value: impl Into<String>) -> Self {
+        BytesValue::Base64Value(Base64Value::new(value.into()))
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
 pub struct StringValue {
     #[serde(rename = "type")]
     pub value_type: String,
     pub value: String,
 }
 
-#[derive(Serialize, Deserialize, Debug)]
+impl StringValue {
+    pub fn new(value: String) -> Self {
+        Self {
+            value_type: "string".to_string(),
+            value,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
 pub struct Base64Value {
     #[serde(rename = "type")]
     pub value_type: String,
     pub value: String,
 }
 
+impl Base64Value {
+    pub fn new(value: String) -> Self {
+        Self {
+            value_type: "base64".to_string(),
+            value,
+        }
+    }
+}
+
 pub type Collector = String;
 pub type CollectorType = String;
 
-#[derive(Serialize, Deserialize, Debug)]
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
 #[serde(rename_all = "lowercase")]
 pub enum SameSite {
     Strict,
     Lax,
     None,
     Default,
+    #[serde(untagged)]
+    Unknown(String),
 }
 
-#[derive(Serialize, Deserialize, Debug)]
+#[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct Cookie {
     pub name: String,
     pub value: BytesValue,
@@ -137,7 +189,7 @@ impl CookieHeader {
 
 pub type DataType = String;
 
-#[derive(Serialize, Deserialize, Debug)]
+#[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct FetchTimingInfo {
     #[serde(rename = "timeOrigin")]
     pub time_origin: f64,
@@ -167,7 +219,7 @@ pub struct FetchTimingInfo {
     pub response_end: f64,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Header {
     pub name: String,
     pub value: BytesValue,
@@ -193,10 +245,17 @@ pub struct Initiator {
     pub initiator_type: Option<String>,
 }
 
-pub type Intercept = String;
-pub type Request = String;
+define_id!(
+    /// The id of a network intercept, `network.Intercept`.
+    Intercept
+);
 
-#[derive(Serialize, Deserialize, Debug)]
+define_id!(
+    /// The id of a network request, `network.Request`.
+    Request
+);
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct RequestData {
     pub request: Request,
     pub url: String,
@@ -287,14 +346,32 @@ impl SetCookieHeader {
     }
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+define_builder!(
+    SetCookieHeader,
+    SetCookieHeaderBuilder,
+    required {
+        name: into String,
+        value: into BytesValue,
+    },
+    optional {
+        domain: into String,
+        http_only: value bool,
+        expiry: into String,
+        max_age: value JsInt,
+        path: into String,
+        same_site: into SameSite,
+        secure: value bool,
+    },
+);
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
 #[serde(untagged)]
 pub enum UrlPattern {
     UrlPatternPattern(UrlPatternPattern),
     UrlPatternString(UrlPatternString),
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct UrlPatternPattern {
     #[serde(rename = "type")]
     pub url_pattern_pattern_type: String,
@@ -329,7 +406,23 @@ impl UrlPatternPattern {
     }
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+define_builder!(
+    UrlPatternPattern,
+    UrlPatternPatternBuilder,
+    required {},
+    optional {
+        protocol: into String,
+        hostname: into String,
+        port: into String,
+        pathname: into String,
+        search: into String,
+    },
+    fixed {
+        url_pattern_pattern_type: "pattern".to_string(),
+    },
+);
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct UrlPatternString {
     #[serde(rename = "type")]
     pub url_pattern_string_type: String,
@@ -378,6 +471,20 @@ pub struct AddDataCollectorParameters {
     pub user_contexts: Option<Vec<UserContext>>,
 }
 
+define_builder!(
+    AddDataCollectorParameters,
+    AddDataCollectorParametersBuilder,
+    required {
+        data_types: into Vec<DataType>,
+        max_encoded_data_size: value JsUint,
+    },
+    optional {
+        collector_type: into CollectorType,
+        contexts: into Vec<BrowsingContext>,
+        user_contexts: into Vec<UserContext>,
+    },
+);
+
 #[derive(Serialize, Deserialize, Debug)]
 pub struct AddDataCollectorResult {
     pub collector: Collector,
@@ -403,7 +510,7 @@ pub struct AddInterceptParameters {
     pub phases: Vec<InterceptPhase>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub contexts: Option<Vec<BrowsingContext>>,
-    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(rename = "urlPatterns", skip_serializing_if = "Option::is_none")]
     pub url_patterns: Option<Vec<UrlPattern>>,
 }
 
@@ -421,6 +528,18 @@ impl AddInterceptParameters {
     }
 }
 
+define_builder!(
+    AddInterceptParameters,
+    AddInterceptParametersBuilder,
+    required {
+        phases: into Vec<InterceptPhase>,
+    },
+    optional {
+        contexts: into Vec<BrowsingContext>,
+        url_patterns: into Vec<UrlPattern>,
+    },
+);
+
 #[derive(Debug, Serialize, Deserialize)]
 pub enum InterceptPhase {
     #[serde(rename = "beforeRequestSent")]
@@ -429,6 +548,8 @@ pub enum InterceptPhase {
     ResponseStarted,
     #[serde(rename = "authRequired")]
     AuthRequired,
+    #[serde(untagged)]
+    Unknown(String),
 }
 
 #[derive(Serialize, Deserialize, Debug)]
@@ -486,6 +607,21 @@ impl ContinueRequestParameters {
     }
 }
 
+define_builder!(
+    ContinueRequestParameters,
+    ContinueRequestParametersBuilder,
+    required {
+        request: into Request,
+    },
+    optional {
+        body: into BytesValue,
+        cookies: into Vec<CookieHeader>,
+        headers: into Vec<Header>,
+        method: into String,
+        url: into String,
+    },
+);
+
 #[derive(Debug, Serialize, Deserialize)]
 pub struct ContinueResponse {
     pub method: String,
@@ -536,6 +672,21 @@ impl ContinueResponseParameters {
     }
 }
 
+define_builder!(
+    ContinueResponseParameters,
+    ContinueResponseParametersBuilder,
+    required {
+        request: into Request,
+    },
+    optional {
+        cookies: into Vec<SetCookieHeader>,
+        credentials: into AuthCredentials,
+        headers: into Vec<Header>,
+        reason_phrase: into String,
+        status_code: value JsUint,
+    },
+);
+
 #[derive(Debug, Serialize, Deserialize)]
 pub struct ContinueWithAuth {
     pub method: String,
@@ -567,6 +718,17 @@ impl ContinueWithAuthParameters {
     }
 }
 
+define_builder!(
+    ContinueWithAuthParameters,
+    ContinueWithAuthParametersBuilder,
+    required {
+        request: into Request,
+    },
+    optional {
+        auth_option: into ContinueWithAuthOption,
+    },
+);
+
 #[derive(Debug, Serialize, Deserialize)]
 #[serde(untagged)]
 pub enum ContinueWithAuthOption {
@@ -600,11 +762,13 @@ impl ContinueWithAuthNoCredentials {
     }
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, Clone)]
 #[serde(rename_all = "lowercase")]
 pub enum NoCredentialsAction {
     Default,
     Cancel,
+    #[serde(untagged)]
+    Unknown(String),
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -682,6 +846,19 @@ pub struct GetDataParameters {
     pub request: Request,
 }
 
+define_builder!(
+    GetDataParameters,
+    GetDataParametersBuilder,
+    required {
+        data_type: into DataType,
+        request: into Request,
+    },
+    optional {
+        collector: into Collector,
+        disown: value bool,
+    },
+);
+
 #[derive(Debug, Serialize, Deserialize)]
 pub struct GetDataResult {
     pub bytes: BytesValue,
@@ -737,6 +914,21 @@ impl ProvideResponseParameters {
     }
 }
 
+define_builder!(
+    ProvideResponseParameters,
+    ProvideResponseParametersBuilder,
+    required {
+        request: into Request,
+    },
+    optional {
+        body: into BytesValue,
+        cookies: into Vec<SetCookieHeader>,
+        headers: into Vec<Header>,
+        reason_phrase: into String,
+        status_code: value JsUint,
+    },
+);
+
 #[derive(Debug, Serialize, Deserialize)]
 pub struct RemoveDataCollector {
     pub method: String,
@@ -757,6 +949,12 @@ pub struct RemoveDataCollectorParameters {
     pub collector: Collector,
 }
 
+impl RemoveDataCollectorParameters {
+    pub fn new(collector: Collector) -> Self {
+        Self { collector }
+    }
+}
+
 #[derive(Debug, Serialize, Deserialize)]
 pub struct RemoveIntercept {
     pub method: String,
@@ -814,11 +1012,24 @@ impl SetCacheBehaviorParameters {
     }
 }
 
+define_builder!(
+    SetCacheBehaviorParameters,
+    SetCacheBehaviorParametersBuilder,
+    required {
+        cache_behavior: into CacheBehavior,
+    },
+    optional {
+        contexts: into Vec<BrowsingContext>,
+    },
+);
+
 #[derive(Debug, Serialize, Deserialize)]
 #[serde(rename_all = "lowercase")]
 pub enum CacheBehavior {
     Default,
     Bypass,
+    #[serde(untagged)]
+    Unknown(String),
 }
 
 #[derive(Serialize, Deserialize, Debug)]
@@ -911,3 +1122,15 @@ pub struct SetExtraHeadersParameters {
     #[serde(rename = "userContexts", skip_serializing_if = "Option::is_none")]
     pub user_contexts: Option<Vec<UserContext>>,
 }
+
+define_builder!(
+    SetExtraHeadersParameters,
+    SetExtraHeadersParametersBuilder,
+    required {
+        headers: into Vec<Header>,
+    },
+    optional {
+        contexts: into Vec<BrowsingContext>,
+        user_contexts: into Vec<UserContext>,
+    },
+);
@@ -15,7 +15,7 @@ pub enum ResultData {
     EmptyResult(EmptyResult),
     NetworkResult(NetworkResult),
     ScriptResult(ScriptResult),
-    SessionResult(SessionResult),
+    SessionResult(Box<SessionResult>),
     StorageResult(StorageResult),
     WebExtensionResult(WebExtensionResult),
 }
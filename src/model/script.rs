@@ -1,7 +1,10 @@
 use std::collections::HashMap;
 
-use serde::{Deserialize, Serialize};
+use serde::{Deserialize, Deserializer, Serialize, de};
+use serde_json::from_value;
 
+use crate::define_builder;
+use crate::define_id;
 use crate::model::browser::UserContext;
 use crate::model::browsing_context::BrowsingContext;
 use crate::model::common::{Extensible, JsUint};
@@ -36,14 +39,23 @@ pub enum ScriptEvent {
 
 pub type Channel = String;
 
-#[derive(Serialize, Deserialize, Debug)]
+#[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct ChannelValue {
     #[serde(rename = "type")]
     pub value_type: String,
     pub value: ChannelProperties,
 }
 
-#[derive(Serialize, Deserialize, Debug)]
+impl ChannelValue {
+    pub fn new(value: ChannelProperties) -> Self {
+        Self {
+            value_type: "channel".to_string(),
+            value,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct ChannelProperties {
     pub channel: Channel,
     #[serde(
@@ -55,6 +67,20 @@ pub struct ChannelProperties {
     pub ownership: Option<ResultOwnership>,
 }
 
+impl ChannelProperties {
+    pub fn new(
+        channel: Channel,
+        serialization_options: Option<SerializationOptions>,
+        ownership: Option<ResultOwnership>,
+    ) -> Self {
+        Self {
+            channel,
+            serialization_options,
+            ownership,
+        }
+    }
+}
+
 #[derive(Serialize, Deserialize, Debug)]
 #[serde(untagged)]
 pub enum EvaluateResult {
@@ -92,11 +118,15 @@ pub struct ExceptionDetails {
     pub text: String,
 }
 
-pub type Handle = String;
+define_id!(
+    /// The handle of an object owned by a realm, `script.Handle`.
+    Handle
+);
 
 pub type InternalId = String;
 
-#[derive(Serialize, Deserialize, Debug)]
+// Deserialized by `type`, a value without one being a remote reference
+#[derive(Serialize, Debug)]
 #[serde(untagged)]
 pub enum LocalValue {
     RemoteReference(RemoteReference),
@@ -110,6 +140,25 @@ pub enum LocalValue {
     SetLocalValue(SetLocalValue),
 }
 
+impl<'de> Deserialize<'de> for LocalValue {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let value = serde_json::Value::deserialize(deserializer)?;
+        let value_type = value.get("type").and_then(serde_json::Value::as_str);
+        match value_type {
+            None => from_value(value).map(LocalValue::RemoteReference),
+            Some("channel") => from_value(value).map(LocalValue::ChannelValue),
+            Some("array") => from_value(value).map(LocalValue::ArrayLocalValue),
+            Some("date") => from_value(value).map(LocalValue::DateLocalValue),
+            Some("map") => from_value(value).map(LocalValue::MapLocalValue),
+            Some("object") => from_value(value).map(LocalValue::ObjectLocalValue),
+            Some("regexp") => from_value(value).map(LocalValue::RegExpLocalValue),
+            Some("set") => from_value(value).map(LocalValue::SetLocalValue),
+            Some(_) => from_value(value).map(LocalValue::PrimitiveProtocolValue),
+        }
+        .map_err(de::Error::custom)
+    }
+}
+
 pub type ListLocalValue = Vec<LocalValue>;
 
 #[derive(Serialize, Deserialize, Debug)]
@@ -171,11 +220,18 @@ pub struct SetLocalValue {
     pub value: ListLocalValue,
 }
 
-pub type PreloadScript = String;
+define_id!(
+    /// The id of a preload script, `script.PreloadScript`.
+    PreloadScript
+);
 
-pub type Realm = String;
+define_id!(
+    /// The id of a realm, `script.Realm`.
+    Realm
+);
 
-#[derive(Serialize, Deserialize, Debug)]
+// Deserialized by `type`, see `deserialize_by_type`
+#[derive(Serialize, Debug)]
 #[serde(untagged)]
 pub enum PrimitiveProtocolValue {
     StringValue(StringValue),
@@ -184,6 +240,48 @@ pub enum PrimitiveProtocolValue {
     BigIntValue(BigIntValue),
     NullValue(NullValue),
     UndefinedValue(UndefinedValue),
+    Unknown(UnknownValue),
+}
+
+impl<'de> Deserialize<'de> for PrimitiveProtocolValue {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        deserialize_by_type(deserializer, |value_type, value| match value_type {
+            "string" => from_value(value).map(PrimitiveProtocolValue::StringValue),
+            "number" => from_value(value).map(PrimitiveProtocolValue::NumberValue),
+            "boolean" => from_value(value).map(PrimitiveProtocolValue::BooleanValue),
+            "bigint" => from_value(value).map(PrimitiveProtocolValue::BigIntValue),
+            "null" => from_value(value).map(PrimitiveProtocolValue::NullValue),
+            "undefined" => from_value(value).map(PrimitiveProtocolValue::UndefinedValue),
+            _ => from_value(value).map(PrimitiveProtocolValue::Unknown),
+        })
+    }
+}
+
+// Many values only differ by their `type`, which an untagged enum can't tell apart,
+// and the ones without a `value` would match any other value.
+fn deserialize_by_type<'de, D, T, F>(deserializer: D, from_type: F) -> Result<T, D::Error>
+where
+    D: Deserializer<'de>,
+    F: FnOnce(&str, serde_json::Value) -> Result<T, serde_json::Error>,
+{
+    let value = serde_json::Value::deserialize(deserializer)?;
+    let value_type = value
+        .get("type")
+        .and_then(serde_json::Value::as_str)
+        .ok_or_else(|| de::Error::missing_field("type"))?
+        .to_string();
+    from_type(&value_type, value).map_err(de::Error::custom)
+}
+
+/// A value of a type this version doesn't know, kept as it was received.
+#[derive(Serialize, Deserialize, Debug)]
+pub struct UnknownValue {
+    #[serde(rename = "type")]
+    pub value_type: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub value: Option<serde_json::Value>,
+    #[serde(flatten)]
+    pub extensible: Extensible,
 }
 
 #[derive(Serialize, Deserialize, Debug)]
@@ -222,6 +320,8 @@ pub enum SpecialNumber {
     Infinity,
     #[serde(rename = "-Infinity")]
     NegativeInfinity,
+    #[serde(untagged)]
+    Unknown(String),
 }
 
 #[derive(Serialize, Deserialize, Debug)]
@@ -252,7 +352,9 @@ pub struct BigIntValue {
     pub value: String,
 }
 
-#[derive(Serialize, Deserialize, Debug)]
+// Most variants only differ by their `type`, so they're deserialized by it, the types
+// this version doesn't know fall back to `Unknown`
+#[derive(Serialize, Debug, Clone)]
 #[serde(untagged)]
 pub enum RealmInfo {
     WindowRealmInfo(WindowRealmInfo),
@@ -263,15 +365,77 @@ pub enum RealmInfo {
     PaintWorkletRealmInfo(PaintWorkletRealmInfo),
     AudioWorkletRealmInfo(AudioWorkletRealmInfo),
     WorkletRealmInfo(WorkletRealmInfo),
+    Unknown(UnknownRealmInfo),
+}
+
+impl<'de> Deserialize<'de> for RealmInfo {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let value = serde_json::Value::deserialize(deserializer)?;
+        match value.get("type").and_then(serde_json::Value::as_str) {
+            Some("window") => from_value(value).map(RealmInfo::WindowRealmInfo),
+            Some("dedicated-worker") => from_value(value).map(RealmInfo::DedicatedWorkerRealmInfo),
+            Some("shared-worker") => from_value(value).map(RealmInfo::SharedWorkerRealmInfo),
+            Some("service-worker") => from_value(value).map(RealmInfo::ServiceWorkerRealmInfo),
+            Some("worker") => from_value(value).map(RealmInfo::WorkerRealmInfo),
+            Some("paint-worklet") => from_value(value).map(RealmInfo::PaintWorkletRealmInfo),
+            Some("audio-worklet") => from_value(value).map(RealmInfo::AudioWorkletRealmInfo),
+            Some("worklet") => from_value(value).map(RealmInfo::WorkletRealmInfo),
+            Some(_) => from_value(value).map(RealmInfo::Unknown),
+            None => return Err(de::Error::missing_field("type")),
+        }
+        .map_err(de::Error::custom)
+    }
 }
 
-#[derive(Serialize, Deserialize, Debug)]
+impl RealmInfo {
+    /// Returns the fields shared by every kind of realm.
+    pub fn base(&self) -> &BaseRealmInfo {
+        match self {
+            RealmInfo::WindowRealmInfo(info) => &info.base,
+            RealmInfo::DedicatedWorkerRealmInfo(info) => &info.base,
+            RealmInfo::SharedWorkerRealmInfo(info) => &info.base,
+            RealmInfo::ServiceWorkerRealmInfo(info) => &info.base,
+            RealmInfo::WorkerRealmInfo(info) => &info.base,
+            RealmInfo::PaintWorkletRealmInfo(info) => &info.base,
+            RealmInfo::AudioWorkletRealmInfo(info) => &info.base,
+            RealmInfo::WorkletRealmInfo(info) => &info.base,
+            RealmInfo::Unknown(info) => &info.base,
+        }
+    }
+
+    /// Returns the id of the realm.
+    pub fn realm(&self) -> &Realm {
+        &self.base().realm
+    }
+
+    /// Returns the type of the realm.
+    pub fn realm_type(&self) -> RealmType {
+        match self {
+            RealmInfo::WindowRealmInfo(_) => RealmType::Window,
+            RealmInfo::DedicatedWorkerRealmInfo(_) => RealmType::DedicatedWorker,
+            RealmInfo::SharedWorkerRealmInfo(_) => RealmType::SharedWorker,
+            RealmInfo::ServiceWorkerRealmInfo(_) => RealmType::ServiceWorker,
+            RealmInfo::WorkerRealmInfo(_) => RealmType::Worker,
+            RealmInfo::PaintWorkletRealmInfo(_) => RealmType::PaintWorklet,
+            RealmInfo::AudioWorkletRealmInfo(_) => RealmType::AudioWorklet,
+            RealmInfo::WorkletRealmInfo(_) => RealmType::Worklet,
+            RealmInfo::Unknown(info) => RealmType::Unknown(info.realm_type.clone()),
+        }
+    }
+
+    /// Returns a target to evaluate scripts in the realm.
+    pub fn target(&self) -> Target {
+        Target::RealmTarget(RealmTarget::new(self.realm().clone()))
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct BaseRealmInfo {
     pub realm: Realm,
     pub origin: String,
 }
 
-#[derive(Serialize, Deserialize, Debug)]
+#[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct WindowRealmInfo {
     #[serde(flatten)]
     pub base: BaseRealmInfo,
@@ -282,7 +446,7 @@ pub struct WindowRealmInfo {
     pub sandbox: Option<String>,
 }
 
-#[derive(Serialize, Deserialize, Debug)]
+#[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct DedicatedWorkerRealmInfo {
     #[serde(flatten)]
     pub base: BaseRealmInfo,
@@ -291,7 +455,7 @@ pub struct DedicatedWorkerRealmInfo {
     pub owners: Vec<Realm>,
 }
 
-#[derive(Serialize, Deserialize, Debug)]
+#[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct SharedWorkerRealmInfo {
     #[serde(flatten)]
     pub base: BaseRealmInfo,
@@ -299,7 +463,7 @@ pub struct SharedWorkerRealmInfo {
     pub realm_type: String,
 }
 
-#[derive(Serialize, Deserialize, Debug)]
+#[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct ServiceWorkerRealmInfo {
     #[serde(flatten)]
     pub base: BaseRealmInfo,
@@ -307,7 +471,7 @@ pub struct ServiceWorkerRealmInfo {
     pub realm_type: String,
 }
 
-#[derive(Serialize, Deserialize, Debug)]
+#[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct WorkerRealmInfo {
     #[serde(flatten)]
     pub base: BaseRealmInfo,
@@ -315,7 +479,7 @@ pub struct WorkerRealmInfo {
     pub realm_type: String,
 }
 
-#[derive(Serialize, Deserialize, Debug)]
+#[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct PaintWorkletRealmInfo {
     #[serde(flatten)]
     pub base: BaseRealmInfo,
@@ -323,7 +487,7 @@ pub struct PaintWorkletRealmInfo {
     pub realm_type: String,
 }
 
-#[derive(Serialize, Deserialize, Debug)]
+#[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct AudioWorkletRealmInfo {
     #[serde(flatten)]
     pub base: BaseRealmInfo,
@@ -331,7 +495,7 @@ pub struct AudioWorkletRealmInfo {
     pub realm_type: String,
 }
 
-#[derive(Serialize, Deserialize, Debug)]
+#[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct WorkletRealmInfo {
     #[serde(flatten)]
     pub base: BaseRealmInfo,
@@ -339,7 +503,18 @@ pub struct WorkletRealmInfo {
     pub realm_type: String,
 }
 
-#[derive(Serialize, Deserialize, Debug)]
+/// A realm of a type this version doesn't know, its other fields are kept as received.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct UnknownRealmInfo {
+    #[serde(rename = "type")]
+    pub realm_type: String,
+    #[serde(flatten)]
+    pub base: BaseRealmInfo,
+    #[serde(flatten)]
+    pub extensible: Extensible,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
 pub enum RealmType {
     #[serde(rename = "window")]
     Window,
@@ -357,6 +532,8 @@ pub enum RealmType {
     AudioWorklet,
     #[serde(rename = "worklet")]
     Worklet,
+    #[serde(untagged)]
+    Unknown(String),
 }
 
 #[derive(Serialize, Deserialize, Debug)]
@@ -376,6 +553,16 @@ pub struct SharedReference {
     pub extensible: Extensible,
 }
 
+impl SharedReference {
+    pub fn new(shared_id: SharedId, handle: Option<Handle>) -> Self {
+        Self {
+            shared_id,
+            handle,
+            extensible: Extensible::new(),
+        }
+    }
+}
+
 #[derive(Serialize, Deserialize, Debug)]
 pub struct RemoteObjectReference {
     pub handle: Handle,
@@ -385,7 +572,8 @@ pub struct RemoteObjectReference {
     pub extensible: Extensible,
 }
 
-#[derive(Serialize, Deserialize, Debug)]
+// Deserialized by `type`, see `deserialize_by_type`
+#[derive(Serialize, Debug)]
 #[serde(untagged)]
 pub enum RemoteValue {
     PrimitiveProtocolValue(PrimitiveProtocolValue),
@@ -409,6 +597,38 @@ pub enum RemoteValue {
     HTMLCollectionRemoteValue(HTMLCollectionRemoteValue),
     NodeRemoteValue(NodeRemoteValue),
     WindowProxyRemoteValue(WindowProxyRemoteValue),
+    Unknown(UnknownValue),
+}
+
+impl<'de> Deserialize<'de> for RemoteValue {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        deserialize_by_type(deserializer, |value_type, value| match value_type {
+            "undefined" | "null" | "string" | "number" | "boolean" | "bigint" => {
+                from_value(value).map(RemoteValue::PrimitiveProtocolValue)
+            }
+            "symbol" => from_value(value).map(RemoteValue::SymbolRemoteValue),
+            "array" => from_value(value).map(RemoteValue::ArrayRemoteValue),
+            "object" => from_value(value).map(RemoteValue::ObjectRemoteValue),
+            "function" => from_value(value).map(RemoteValue::FunctionRemoteValue),
+            "regexp" => from_value(value).map(RemoteValue::RegExpRemoteValue),
+            "date" => from_value(value).map(RemoteValue::DateRemoteValue),
+            "map" => from_value(value).map(RemoteValue::MapRemoteValue),
+            "set" => from_value(value).map(RemoteValue::SetRemoteValue),
+            "weakmap" => from_value(value).map(RemoteValue::WeakMapRemoteValue),
+            "weakset" => from_value(value).map(RemoteValue::WeakSetRemoteValue),
+            "generator" => from_value(value).map(RemoteValue::GeneratorRemoteValue),
+            "error" => from_value(value).map(RemoteValue::ErrorRemoteValue),
+            "proxy" => from_value(value).map(RemoteValue::ProxyRemoteValue),
+            "promise" => from_value(value).map(RemoteValue::PromiseRemoteValue),
+            "typedarray" => from_value(value).map(RemoteValue::TypedArrayRemoteValue),
+            "arraybuffer" => from_value(value).map(RemoteValue::ArrayBufferRemoteValue),
+            "nodelist" => from_value(value).map(RemoteValue::NodeListRemoteValue),
+            "htmlcollection" => from_value(value).map(RemoteValue::HTMLCollectionRemoteValue),
+            "node" => from_value(value).map(RemoteValue::NodeRemoteValue),
+            "window" => from_value(value).map(RemoteValue::WindowProxyRemoteValue),
+            _ => from_value(value).map(RemoteValue::Unknown),
+        })
+    }
 }
 
 pub type ListRemoteValue = Vec<RemoteValue>;
@@ -660,6 +880,8 @@ pub struct NodeProperties {
 pub enum NodePropertiesMode {
     Open,
     Closed,
+    #[serde(untagged)]
+    Unknown(String),
 }
 
 #[derive(Serialize, Deserialize, Debug)]
@@ -678,14 +900,16 @@ pub struct WindowProxyProperties {
     pub context: BrowsingContext,
 }
 
-#[derive(Serialize, Deserialize, Debug)]
+#[derive(Serialize, Deserialize, Debug, Clone)]
 #[serde(rename_all = "lowercase")]
 pub enum ResultOwnership {
     Root,
     None,
+    #[serde(untagged)]
+    Unknown(String),
 }
 
-#[derive(Serialize, Deserialize, Debug)]
+#[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct SerializationOptions {
     #[serde(rename = "maxDomDepth", skip_serializing_if = "Option::is_none")]
     pub max_dom_depth: Option<JsUint>,
@@ -695,12 +919,25 @@ pub struct SerializationOptions {
     pub include_shadow_tree: Option<IncludeShadowTree>,
 }
 
-#[derive(Serialize, Deserialize, Debug)]
+define_builder!(
+    SerializationOptions,
+    SerializationOptionsBuilder,
+    required {},
+    optional {
+        max_dom_depth: value JsUint,
+        max_object_depth: value JsUint,
+        include_shadow_tree: into IncludeShadowTree,
+    },
+);
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
 #[serde(rename_all = "lowercase")]
 pub enum IncludeShadowTree {
     None,
     Open,
     All,
+    #[serde(untagged)]
+    Unknown(String),
 }
 
 pub type SharedId = String;
@@ -753,6 +990,17 @@ impl ContextTarget {
     }
 }
 
+define_builder!(
+    ContextTarget,
+    ContextTargetBuilder,
+    required {
+        context: into BrowsingContext,
+    },
+    optional {
+        sandbox: into String,
+    },
+);
+
 #[derive(Debug, Serialize, Deserialize)]
 #[serde(untagged)]
 pub enum Target {
@@ -775,7 +1023,7 @@ impl AddPreloadScript {
     }
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct AddPreloadScriptParameters {
     #[serde(rename = "functionDeclaration")]
     pub function_declaration: String,
@@ -807,6 +1055,20 @@ impl AddPreloadScriptParameters {
     }
 }
 
+define_builder!(
+    AddPreloadScriptParameters,
+    AddPreloadScriptParametersBuilder,
+    required {
+        function_declaration: into String,
+    },
+    optional {
+        arguments: into Vec<ChannelValue>,
+        contexts: into Vec<BrowsingContext>,
+        user_contexts: into Vec<UserContext>,
+        sandbox: into String,
+    },
+);
+
 #[derive(Serialize, Deserialize, Debug)]
 pub struct AddPreloadScriptResult {
     pub script: PreloadScript,
@@ -899,6 +1161,23 @@ impl CallFunctionParameters {
     }
 }
 
+define_builder!(
+    CallFunctionParameters,
+    CallFunctionParametersBuilder,
+    required {
+        function_declaration: into String,
+        await_promise: value bool,
+        target: into Target,
+    },
+    optional {
+        arguments: into Vec<LocalValue>,
+        result_ownership: into ResultOwnership,
+        serialization_options: into SerializationOptions,
+        this: into LocalValue,
+        user_activation: value bool,
+    },
+);
+
 #[derive(Debug, Serialize, Deserialize)]
 pub struct Evaluate {
     pub method: String,
@@ -950,6 +1229,21 @@ impl EvaluateParameters {
     }
 }
 
+define_builder!(
+    EvaluateParameters,
+    EvaluateParametersBuilder,
+    required {
+        expression: into String,
+        target: into Target,
+        await_promise: value bool,
+    },
+    optional {
+        result_ownership: into ResultOwnership,
+        serialization_options: into SerializationOptions,
+        user_activation: value bool,
+    },
+);
+
 #[derive(Debug, Serialize, Deserialize)]
 pub struct GetRealms {
     pub method: String,
@@ -983,6 +1277,16 @@ impl GetRealmsParameters {
     }
 }
 
+define_builder!(
+    GetRealmsParameters,
+    GetRealmsParametersBuilder,
+    required {},
+    optional {
+        context: into BrowsingContext,
+        realm_type: into RealmType,
+    },
+);
+
 #[derive(Serialize, Deserialize, Debug)]
 pub struct GetRealmsResult {
     pub realms: Vec<RealmInfo>,
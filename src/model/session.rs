@@ -54,7 +54,7 @@ pub struct CapabilityRequest {
     pub extensible: Extensible,
 }
 
-#[derive(Serialize, Deserialize, Debug)]
+#[derive(Serialize, Deserialize, Debug, Clone)]
 #[serde(untagged)]
 pub enum ProxyConfiguration {
     AutodetectProxyConfiguration(AutodetectProxyConfiguration),
@@ -64,7 +64,7 @@ pub enum ProxyConfiguration {
     SystemProxyConfiguration(SystemProxyConfiguration),
 }
 
-#[derive(Serialize, Deserialize, Debug)]
+#[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct AutodetectProxyConfiguration {
     #[serde(rename = "proxyType")]
     pub proxy_type: String,
@@ -72,7 +72,7 @@ pub struct AutodetectProxyConfiguration {
     pub extensible: Extensible,
 }
 
-#[derive(Serialize, Deserialize, Debug)]
+#[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct DirectProxyConfiguration {
     #[serde(rename = "proxyType")]
     pub proxy_type: String,
@@ -80,7 +80,7 @@ pub struct DirectProxyConfiguration {
     pub extensible: Extensible,
 }
 
-#[derive(Serialize, Deserialize, Debug)]
+#[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct ManualProxyConfiguration {
     #[serde(rename = "proxyType")]
     pub proxy_type: String,
@@ -96,7 +96,7 @@ pub struct ManualProxyConfiguration {
     pub extensible: Extensible,
 }
 
-#[derive(Serialize, Deserialize, Debug)]
+#[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct SocksProxyConfiguration {
     #[serde(rename = "socksProxy")]
     pub socks_proxy: String,
@@ -104,7 +104,7 @@ pub struct SocksProxyConfiguration {
     pub socks_version: u8,
 }
 
-#[derive(Serialize, Deserialize, Debug)]
+#[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct PacProxyConfiguration {
     #[serde(rename = "proxyType")]
     pub proxy_type: String,
@@ -114,7 +114,7 @@ pub struct PacProxyConfiguration {
     pub extensible: Extensible,
 }
 
-#[derive(Serialize, Deserialize, Debug)]
+#[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct SystemProxyConfiguration {
     #[serde(rename = "proxyType")]
     pub proxy_type: String,
@@ -122,7 +122,7 @@ pub struct SystemProxyConfiguration {
     pub extensible: Extensible,
 }
 
-#[derive(Serialize, Deserialize, Debug)]
+#[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct UserPromptHandler {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub alert: Option<UserPromptHandlerType>,
@@ -138,12 +138,14 @@ pub struct UserPromptHandler {
     pub prompt: Option<UserPromptHandlerType>,
 }
 
-#[derive(Serialize, Deserialize, Debug)]
+#[derive(Serialize, Deserialize, Debug, Clone)]
 #[serde(rename_all = "lowercase")]
 pub enum UserPromptHandlerType {
     Accept,
     Dismiss,
     Ignore,
+    #[serde(untagged)]
+    Unknown(String),
 }
 
 type Subscription = String;
@@ -1,5 +1,7 @@
 use serde::{Deserialize, Serialize};
 
+use crate::define_builder;
+use crate::model::browser::UserContext;
 use crate::model::browsing_context::BrowsingContext;
 use crate::model::common::{Extensible, JsUint};
 use crate::model::network::{BytesValue, Cookie, SameSite};
@@ -23,14 +25,15 @@ pub enum StorageResult {
 #[derive(Debug, Serialize, Deserialize)]
 pub struct PartitionKey {
     #[serde(rename = "userContext", skip_serializing_if = "Option::is_none")]
-    pub user_context: Option<String>,
+    pub user_context: Option<UserContext>,
     #[serde(rename = "sourceOrigin", skip_serializing_if = "Option::is_none")]
     pub source_origin: Option<String>,
+    #[serde(flatten)]
     pub extensible: Extensible,
 }
 
 impl PartitionKey {
-    pub fn new(user_context: Option<String>, source_origin: Option<String>) -> Self {
+    pub fn new(user_context: Option<UserContext>, source_origin: Option<String>) -> Self {
         Self {
             user_context,
             source_origin,
@@ -74,6 +77,7 @@ pub struct CookieFilter {
     pub same_site: Option<SameSite>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub expiry: Option<JsUint>,
+    #[serde(flatten)]
     pub extensible: Extensible,
 }
 
@@ -105,6 +109,26 @@ impl CookieFilter {
     }
 }
 
+define_builder!(
+    CookieFilter,
+    CookieFilterBuilder,
+    required {},
+    optional {
+        name: into String,
+        value: into BytesValue,
+        domain: into String,
+        path: into String,
+        size: value JsUint,
+        http_only: value bool,
+        secure: value bool,
+        same_site: into SameSite,
+        expiry: value JsUint,
+    },
+    fixed {
+        extensible: Extensible::new(),
+    },
+);
+
 #[derive(Debug, Serialize, Deserialize)]
 pub struct BrowsingContextPartitionDescriptor {
     #[serde(rename = "type")]
@@ -126,14 +150,15 @@ pub struct StorageKeyPartitionDescriptor {
     #[serde(rename = "type")]
     pub storage_key_partition_descriptor_type: String,
     #[serde(rename = "userContext", skip_serializing_if = "Option::is_none")]
-    pub user_context: Option<String>,
+    pub user_context: Option<UserContext>,
     #[serde(rename = "sourceOrigin", skip_serializing_if = "Option::is_none")]
     pub source_origin: Option<String>,
+    #[serde(flatten)]
     pub extensible: Extensible,
 }
 
 impl StorageKeyPartitionDescriptor {
-    pub fn new(user_context: Option<String>, source_origin: Option<String>) -> Self {
+    pub fn new(user_context: Option<UserContext>, source_origin: Option<String>) -> Self {
         Self {
             storage_key_partition_descriptor_type: "storageKey".to_string(),
             user_context,
@@ -143,6 +168,20 @@ impl StorageKeyPartitionDescriptor {
     }
 }
 
+define_builder!(
+    StorageKeyPartitionDescriptor,
+    StorageKeyPartitionDescriptorBuilder,
+    required {},
+    optional {
+        user_context: into UserContext,
+        source_origin: into String,
+    },
+    fixed {
+        storage_key_partition_descriptor_type: "storageKey".to_string(),
+        extensible: Extensible::new(),
+    },
+);
+
 #[derive(Debug, Serialize, Deserialize)]
 #[serde(untagged)]
 pub enum PartitionDescriptor {
@@ -164,6 +203,16 @@ impl GetCookiesParameters {
     }
 }
 
+define_builder!(
+    GetCookiesParameters,
+    GetCookiesParametersBuilder,
+    required {},
+    optional {
+        filter: into CookieFilter,
+        partition: into PartitionDescriptor,
+    },
+);
+
 #[derive(Serialize, Deserialize, Debug)]
 pub struct GetCookiesResult {
     pub cookies: Vec<Cookie>,
@@ -201,6 +250,7 @@ pub struct PartialCookie {
     pub same_site: Option<SameSite>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub expiry: Option<JsUint>,
+    #[serde(flatten)]
     pub extensible: Extensible,
 }
 
@@ -230,6 +280,26 @@ impl PartialCookie {
     }
 }
 
+define_builder!(
+    PartialCookie,
+    PartialCookieBuilder,
+    required {
+        name: into String,
+        value: into BytesValue,
+        domain: into String,
+    },
+    optional {
+        path: into String,
+        http_only: value bool,
+        secure: value bool,
+        same_site: into SameSite,
+        expiry: value JsUint,
+    },
+    fixed {
+        extensible: Extensible::new(),
+    },
+);
+
 #[derive(Debug, Serialize, Deserialize)]
 pub struct SetCookieParameters {
     pub cookie: PartialCookie,
@@ -243,6 +313,17 @@ impl SetCookieParameters {
     }
 }
 
+define_builder!(
+    SetCookieParameters,
+    SetCookieParametersBuilder,
+    required {
+        cookie: into PartialCookie,
+    },
+    optional {
+        partition: into PartitionDescriptor,
+    },
+);
+
 #[derive(Serialize, Deserialize, Debug)]
 pub struct SetCookieResult {
     #[serde(rename = "partitionKey")]
@@ -278,6 +359,16 @@ impl DeleteCookiesParameters {
     }
 }
 
+define_builder!(
+    DeleteCookiesParameters,
+    DeleteCookiesParametersBuilder,
+    required {},
+    optional {
+        filter: into CookieFilter,
+        partition: into PartitionDescriptor,
+    },
+);
+
 #[derive(Serialize, Deserialize, Debug)]
 pub struct DeleteCookiesResult {
     #[serde(rename = "partitionKey")]
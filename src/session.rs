@@ -2,20 +2,26 @@ use std::collections::HashMap;
 use std::future::Future;
 use std::pin::Pin;
 use std::sync::Arc;
+use std::time::Duration;
 
 use log::debug;
 use serde::Serialize;
 use serde::de::DeserializeOwned;
 use serde_json::Value;
 use tokio::net::TcpStream;
-use tokio::sync::{Mutex, oneshot};
+use tokio::sync::{Mutex, mpsc, oneshot};
 use tokio::task;
 use tokio_tungstenite::{MaybeTlsStream, WebSocketStream, connect_async};
 
 use crate::command_sender;
 use crate::commands;
-use crate::error::{CommandError, SessionError};
+use crate::error::{CommandError, HelperError, SessionError};
 use crate::events::EventType;
+use crate::helpers;
+use crate::helpers::bodies::{BodyCapture, BodyFilter};
+use crate::helpers::expose_function::{ExposedFunction, ExposedFunctionResult};
+use crate::helpers::navigation::NavigationOutcome;
+use crate::helpers::routes::{RouteAction, RouteGuard, Router};
 use crate::message_handler;
 use crate::model::browser::ClientWindowInfo;
 use crate::model::browser::*;
@@ -37,6 +43,12 @@ use crate::webdriver::session;
 pub type EventHandler =
     Box<dyn Fn(Value) -> Pin<Box<dyn Future<Output = ()> + Send>> + Send + Sync>;
 
+/// A channel receiving the events of the given types, in the order they were received.
+pub(crate) struct EventListener {
+    pub event_types: Vec<EventType>,
+    pub sender: mpsc::UnboundedSender<Value>,
+}
+
 /// Represents a WebDriver BiDi session.
 ///
 /// This struct manages the lifecycle of a WebDriver session, including
@@ -55,6 +67,9 @@ pub type EventHandler =
 /// * `websocket_stream` - The WebSocket stream for communication protected by an `Arc` wrapped `Mutex`.
 /// * `pending_commands` - A map of pending commands awaiting responses protected by an `Arc` wrapped `Mutex`.
 /// * `event_handlers` - A map of events and their handlers protected by an `Arc` wrapped `Mutex`.
+/// * `event_listeners` - The event listener channels protected by an `Arc` wrapped `Mutex`.
+/// * `validate_commands` - Whether outgoing commands are validated in debug builds.
+/// * `router` - The request routes added with `route`.
 #[derive(Clone)]
 pub struct WebDriverBiDiSession {
     pub host: String,
@@ -66,6 +81,9 @@ pub struct WebDriverBiDiSession {
     pub websocket_stream: Option<Arc<Mutex<WebSocketStream<MaybeTlsStream<TcpStream>>>>>,
     pub pending_commands: Arc<Mutex<HashMap<u64, oneshot::Sender<Value>>>>,
     event_handlers: Arc<Mutex<HashMap<EventType, EventHandler>>>,
+    event_listeners: Arc<Mutex<Vec<EventListener>>>,
+    validate_commands: bool,
+    router: Router,
 }
 
 impl WebDriverBiDiSession {
@@ -89,6 +107,9 @@ impl WebDriverBiDiSession {
             websocket_stream: None,
             pending_commands: Arc::new(Mutex::new(HashMap::new())),
             event_handlers: Arc::new(Mutex::new(HashMap::new())),
+            event_listeners: Arc::new(Mutex::new(Vec::new())),
+            validate_commands: false,
+            router: Router::default(),
         }
     }
 
@@ -113,10 +134,16 @@ impl WebDriverBiDiSession {
 
         let pending_commands = self.pending_commands.clone();
         let event_handlers = self.event_handlers.clone();
+        let event_listeners = self.event_listeners.clone();
 
         debug!("Starting the incoming messages management loop");
         // Spawn a background task to manage incoming messages
-        self.spawn_message_handler_task(websocket_stream, pending_commands, event_handlers);
+        self.spawn_message_handler_task(
+            websocket_stream,
+            pending_commands,
+            event_handlers,
+            event_listeners,
+        );
 
         Ok(())
     }
@@ -146,6 +173,7 @@ impl WebDriverBiDiSession {
                 websocket_stream.clone(),
                 self.pending_commands.clone(),
                 command,
+                self.validate_commands,
             )
             .await
         } else {
@@ -154,6 +182,20 @@ impl WebDriverBiDiSession {
         }
     }
 
+    /// Enable or disable the validation of outgoing commands.
+    ///
+    /// When enabled, debug builds check every command against the constraints of
+    /// the remote end CDDL definition before sending it, and return a
+    /// `CommandError::InvalidParameters` instead of waiting for an `invalid argument`
+    /// error from the browser. Release builds never validate commands.
+    ///
+    /// # Arguments
+    ///
+    /// * `enabled` - Whether to validate outgoing commands.
+    pub fn set_command_validation(&mut self, enabled: bool) {
+        self.validate_commands = enabled;
+    }
+
     /// Spawn a background task to manage incoming WebSocket messages.
     ///
     /// This method creates a new asynchronous task that continuously listens for
@@ -163,11 +205,13 @@ impl WebDriverBiDiSession {
         websocket_stream: Arc<Mutex<WebSocketStream<MaybeTlsStream<TcpStream>>>>,
         pending_commands: Arc<Mutex<HashMap<u64, oneshot::Sender<Value>>>>,
         event_handlers: Arc<Mutex<HashMap<EventType, EventHandler>>>,
+        event_listeners: Arc<Mutex<Vec<EventListener>>>,
     ) {
         task::spawn(message_handler::handle_messages(
             websocket_stream,
             pending_commands,
             event_handlers,
+            event_listeners,
         ));
     }
 
@@ -196,6 +240,35 @@ impl WebDriverBiDiSession {
         let mut handlers = self.event_handlers.lock().await;
         handlers.remove(&event_type);
     }
+
+    /// Add a listener for the specified event types.
+    ///
+    /// Unlike event handlers, any number of listeners can be added for the same
+    /// event type and each listener receives the events in the order they were sent
+    /// by the remote end. The listener is removed once the receiver is dropped.
+    ///
+    /// The events still have to be enabled with `session_subscribe`.
+    ///
+    /// # Arguments
+    ///
+    /// * `event_types` - The types of the events to receive.
+    ///
+    /// # Returns
+    ///
+    /// The receiving half of the listener channel.
+    pub async fn add_event_listener(
+        &mut self,
+        event_types: Vec<EventType>,
+    ) -> mpsc::UnboundedReceiver<Value> {
+        debug!("Adding event listener for events: {:?}", event_types);
+        let (sender, receiver) = mpsc::unbounded_channel();
+        let mut listeners = self.event_listeners.lock().await;
+        listeners.push(EventListener {
+            event_types,
+            sender,
+        });
+        receiver
+    }
 }
 
 // Browsing context commands
@@ -621,10 +694,46 @@ impl WebDriverBiDiSession {
     ) -> Result<ClientWindowInfo, CommandError> {
         commands::browser::set_client_window_state(self, params).await
     }
+
+    // https://w3c.github.io/webdriver-bidi/#command-browser-setDownloadBehavior
+
+    /// Allow or deny downloads, globally or for the given user contexts.
+    ///
+    /// # Arguments
+    ///
+    /// * `params` - The parameters as a `SetDownloadBehaviorParameters` instance.
+    ///
+    /// # Returns
+    ///
+    /// A result containing the `EmptyResult` or a `CommandError`.
+    pub async fn browser_set_download_behavior(
+        &mut self,
+        params: SetDownloadBehaviorParameters,
+    ) -> Result<EmptyResult, CommandError> {
+        commands::browser::set_download_behavior(self, params).await
+    }
 }
 
 // Emulation commands
 impl WebDriverBiDiSession {
+    // https://w3c.github.io/webdriver-bidi/#command-emulation-setForcedColorsModeThemeOverride
+
+    /// Emulates the forced colors mode theme of the given top-level traversables or user contexts.
+    ///
+    /// # Arguments
+    ///
+    /// * `params` - The parameters as a `SetForcedColorsModeThemeOverrideParameters` instance.
+    ///
+    /// # Returns
+    ///
+    /// A result containing the `EmptyResult` or a `CommandError`.
+    pub async fn set_forced_colors_mode_theme_override(
+        &mut self,
+        params: SetForcedColorsModeThemeOverrideParameters,
+    ) -> Result<EmptyResult, CommandError> {
+        commands::emulation::set_forced_colors_mode_theme_override(self, params).await
+    }
+
     // https://w3c.github.io/webdriver-bidi/#command-emulation-setGeolocationOverride
 
     /// Modify geolocation characteristics on the given top-level traversables or user contexts.
@@ -661,6 +770,24 @@ impl WebDriverBiDiSession {
         commands::emulation::set_locale_override(self, params).await
     }
 
+    // https://w3c.github.io/webdriver-bidi/#command-emulation-setNetworkConditions
+
+    /// Emulates network conditions on the given top-level traversables or user contexts.
+    ///
+    /// # Arguments
+    ///
+    /// * `params` - The parameters as a `SetNetworkConditionsParameters` instance.
+    ///
+    /// # Returns
+    ///
+    /// A result containing the `EmptyResult` or a `CommandError`.
+    pub async fn set_network_conditions(
+        &mut self,
+        params: SetNetworkConditionsParameters,
+    ) -> Result<EmptyResult, CommandError> {
+        commands::emulation::set_network_conditions(self, params).await
+    }
+
     // https://w3c.github.io/webdriver-bidi/#command-emulation-setScreenOrientationOverride
 
     /// Emulates screen orientation of the given top-level traversables or user contexts.
@@ -679,6 +806,42 @@ impl WebDriverBiDiSession {
         commands::emulation::set_screen_orientation_override(self, params).await
     }
 
+    // https://w3c.github.io/webdriver-bidi/#command-emulation-setScreenSettingsOverride
+
+    /// Emulates the screen area of the given top-level traversables or user contexts.
+    ///
+    /// # Arguments
+    ///
+    /// * `params` - The parameters as a `SetScreenSettingsOverrideParameters` instance.
+    ///
+    /// # Returns
+    ///
+    /// A result containing the `EmptyResult` or a `CommandError`.
+    pub async fn set_screen_settings_override(
+        &mut self,
+        params: SetScreenSettingsOverrideParameters,
+    ) -> Result<EmptyResult, CommandError> {
+        commands::emulation::set_screen_settings_override(self, params).await
+    }
+
+    // https://w3c.github.io/webdriver-bidi/#command-emulation-setScriptingEnabled
+
+    /// Disables or re-enables scripting on the given top-level traversables or user contexts.
+    ///
+    /// # Arguments
+    ///
+    /// * `params` - The parameters as a `SetScriptingEnabledParameters` instance.
+    ///
+    /// # Returns
+    ///
+    /// A result containing the `EmptyResult` or a `CommandError`.
+    pub async fn set_scripting_enabled(
+        &mut self,
+        params: SetScriptingEnabledParameters,
+    ) -> Result<EmptyResult, CommandError> {
+        commands::emulation::set_scripting_enabled(self, params).await
+    }
+
     // https://w3c.github.io/webdriver-bidi/#command-emulation-setTimezoneOverride
 
     /// Modifies timezone on the given top-level traversables or user contexts.
@@ -696,6 +859,42 @@ impl WebDriverBiDiSession {
     ) -> Result<EmptyResult, CommandError> {
         commands::emulation::set_timezone_override(self, params).await
     }
+
+    // https://w3c.github.io/webdriver-bidi/#command-emulation-setTouchOverride
+
+    /// Emulates touch input on the given top-level traversables or user contexts.
+    ///
+    /// # Arguments
+    ///
+    /// * `params` - The parameters as a `SetTouchOverrideParameters` instance.
+    ///
+    /// # Returns
+    ///
+    /// A result containing the `EmptyResult` or a `CommandError`.
+    pub async fn set_touch_override(
+        &mut self,
+        params: SetTouchOverrideParameters,
+    ) -> Result<EmptyResult, CommandError> {
+        commands::emulation::set_touch_override(self, params).await
+    }
+
+    // https://w3c.github.io/webdriver-bidi/#command-emulation-setUserAgentOverride
+
+    /// Modifies the user agent of the given top-level traversables or user contexts.
+    ///
+    /// # Arguments
+    ///
+    /// * `params` - The parameters as a `SetUserAgentOverrideParameters` instance.
+    ///
+    /// # Returns
+    ///
+    /// A result containing the `EmptyResult` or a `CommandError`.
+    pub async fn set_user_agent_override(
+        &mut self,
+        params: SetUserAgentOverrideParameters,
+    ) -> Result<EmptyResult, CommandError> {
+        commands::emulation::set_user_agent_override(self, params).await
+    }
 }
 
 // Network commands
@@ -1200,3 +1399,128 @@ impl WebDriverBiDiSession {
         commands::web_extension::uninstall(self, params).await
     }
 }
+
+// Helpers
+impl WebDriverBiDiSession {
+    /// Expose a Rust function to the pages as `window[name]`.
+    ///
+    /// The function is installed by a preload script, so it's available in the
+    /// documents created after this call. Calling it from a page sends the arguments,
+    /// encoded as JSON, to `function` and returns a promise settled with its result.
+    ///
+    /// # Arguments
+    ///
+    /// * `name` - The name of the function in the pages.
+    /// * `contexts` - The top-level browsing contexts to expose the function in, all
+    ///   of them if `None`.
+    /// * `function` - The asynchronous function answering the calls.
+    ///
+    /// # Returns
+    ///
+    /// A result containing the `ExposedFunction` handle or a `HelperError`.
+    pub async fn expose_function<F, Fut>(
+        &mut self,
+        name: &str,
+        contexts: Option<Vec<BrowsingContext>>,
+        function: F,
+    ) -> Result<ExposedFunction, HelperError>
+    where
+        F: Fn(Vec<Value>) -> Fut + Send + Sync + 'static,
+        Fut: Future<Output = ExposedFunctionResult> + Send + 'static,
+    {
+        helpers::expose_function::expose_function(self, name, contexts, function).await
+    }
+
+    /// Navigate a browsing context and wait for the navigation to end.
+    ///
+    /// The navigation events and the main document's network response are
+    /// correlated by navigation id, so that the outcome reports the HTTP status, the
+    /// final URL after redirects, the event timings and whether the navigation failed
+    /// or was aborted. A same-document navigation completes once the fragment is
+    /// navigated, whatever the awaited readiness state.
+    ///
+    /// # Arguments
+    ///
+    /// * `context` - The browsing context to navigate.
+    /// * `url` - The URL to navigate to.
+    /// * `wait` - The readiness state ending the navigation.
+    /// * `timeout` - The maximum time to wait for.
+    ///
+    /// # Returns
+    ///
+    /// A result containing the `NavigationOutcome` or a `HelperError`.
+    pub async fn navigate_and_wait(
+        &mut self,
+        context: BrowsingContext,
+        url: impl Into<String>,
+        wait: ReadinessState,
+        timeout: Duration,
+    ) -> Result<NavigationOutcome, HelperError> {
+        helpers::navigation::navigate_and_wait(self, context, url.into(), wait, timeout).await
+    }
+
+    /// Route the requests matching a URL pattern through a handler.
+    ///
+    /// The handler receives the intercepted request and decides whether it's
+    /// continued, with optional overrides, fulfilled with a provided response or
+    /// aborted. It's called in the order the requests arrive, and the futures it
+    /// returns are awaited concurrently. When several routes match a request, the
+    /// most recently added one handles it, and the previous ones when it returns
+    /// `RouteAction::Fallback`. The requests blocked by a removed route are continued unchanged.
+    ///
+    /// # Arguments
+    ///
+    /// * `pattern` - The URL pattern of the requests to route.
+    /// * `handler` - The asynchronous function deciding what to do with a request.
+    ///
+    /// # Returns
+    ///
+    /// A result containing the `RouteGuard` removing the route once dropped or a
+    /// `HelperError`.
+    pub async fn route<F, Fut>(
+        &mut self,
+        pattern: UrlPattern,
+        handler: F,
+    ) -> Result<RouteGuard, HelperError>
+    where
+        F: Fn(RequestData) -> Fut + Send + Sync + 'static,
+        Fut: Future<Output = RouteAction> + Send + 'static,
+    {
+        let router = self.router.clone();
+        router
+            .route(self, vec![pattern], move |params| handler(params.request))
+            .await
+    }
+
+    // Route the requests matching any of the patterns, or every request if there
+    // are none, through a single intercept. The handler gets the whole event.
+    pub(crate) async fn route_patterns<F, Fut>(
+        &mut self,
+        patterns: Vec<UrlPattern>,
+        handler: F,
+    ) -> Result<RouteGuard, HelperError>
+    where
+        F: Fn(BaseParameters) -> Fut + Send + Sync + 'static,
+        Fut: Future<Output = RouteAction> + Send + 'static,
+    {
+        let router = self.router.clone();
+        router.route(self, patterns, handler).await
+    }
+
+    /// Capture the bodies of the requests matching a filter.
+    ///
+    /// The bodies are read with `BodyCapture::body`, decoded from the `StringValue`
+    /// or `Base64Value` sent by the remote end. The data collector capturing them is
+    /// removed when the returned handle is dropped.
+    ///
+    /// # Arguments
+    ///
+    /// * `filter` - The requests to capture the bodies of as a `BodyFilter` instance.
+    ///
+    /// # Returns
+    ///
+    /// A result containing the `BodyCapture` handle or a `HelperError`.
+    pub async fn capture_bodies(&mut self, filter: BodyFilter) -> Result<BodyCapture, HelperError> {
+        BodyCapture::new(self, filter).await
+    }
+}
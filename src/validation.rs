@@ -0,0 +1,325 @@
+use serde_json::Value;
+
+use crate::error::CommandError;
+
+// js-int = -9007199254740991..9007199254740991
+// js-uint = 0..9007199254740991
+const MAX_SAFE_INTEGER: u64 = 9_007_199_254_740_991;
+
+const METHOD_KEY: &str = "method";
+const PARAMS_KEY: &str = "params";
+const ANY_METHOD: &str = "*";
+
+/// A constraint on a single value of a command, as found in `cddl/remote.cddl`.
+#[derive(Debug, Clone, Copy)]
+enum Constraint {
+    /// `(lower..upper)`, both bounds inclusive.
+    Range(f64, f64),
+    /// `(float .ge lower)`
+    Ge(f64),
+    /// `(float .gt lower)`
+    Gt(f64),
+    /// `[+ T]`
+    NonEmpty,
+}
+
+/// A constraint applied to the value(s) found at `path` within the `params`
+/// of a command whose method is `method`.
+///
+/// The path is a dot-separated list of keys, `*` matches every element of an array.
+struct Rule {
+    method: &'static str,
+    path: &'static str,
+    constraint: Constraint,
+}
+
+const RULES: &[Rule] = &[
+    // browsingContext.ImageFormat
+    Rule {
+        method: "browsingContext.captureScreenshot",
+        path: "format.quality",
+        constraint: Constraint::Range(0.0, 1.0),
+    },
+    // browsingContext.BoxClipRectangle, a clip with a negative size is rejected by the remote end
+    Rule {
+        method: "browsingContext.captureScreenshot",
+        path: "clip.width",
+        constraint: Constraint::Ge(0.0),
+    },
+    Rule {
+        method: "browsingContext.captureScreenshot",
+        path: "clip.height",
+        constraint: Constraint::Ge(0.0),
+    },
+    // browsingContext.LocateNodesParameters
+    Rule {
+        method: "browsingContext.locateNodes",
+        path: "maxNodeCount",
+        constraint: Constraint::Ge(1.0),
+    },
+    Rule {
+        method: "browsingContext.locateNodes",
+        path: "startNodes",
+        constraint: Constraint::NonEmpty,
+    },
+    // browsingContext.PrintParameters
+    Rule {
+        method: "browsingContext.print",
+        path: "scale",
+        constraint: Constraint::Range(0.1, 2.0),
+    },
+    Rule {
+        method: "browsingContext.print",
+        path: "margin.bottom",
+        constraint: Constraint::Ge(0.0),
+    },
+    Rule {
+        method: "browsingContext.print",
+        path: "margin.left",
+        constraint: Constraint::Ge(0.0),
+    },
+    Rule {
+        method: "browsingContext.print",
+        path: "margin.right",
+        constraint: Constraint::Ge(0.0),
+    },
+    Rule {
+        method: "browsingContext.print",
+        path: "margin.top",
+        constraint: Constraint::Ge(0.0),
+    },
+    // browsingContext.PrintPageParameters, minimum size is 1pt x 1pt
+    Rule {
+        method: "browsingContext.print",
+        path: "page.height",
+        constraint: Constraint::Ge(0.0352),
+    },
+    Rule {
+        method: "browsingContext.print",
+        path: "page.width",
+        constraint: Constraint::Ge(0.0352),
+    },
+    // browsingContext.SetViewportParameters
+    Rule {
+        method: "browsingContext.setViewport",
+        path: "devicePixelRatio",
+        constraint: Constraint::Gt(0.0),
+    },
+    // emulation.GeolocationCoordinates
+    Rule {
+        method: "emulation.setGeolocationOverride",
+        path: "coordinates.latitude",
+        constraint: Constraint::Range(-90.0, 90.0),
+    },
+    Rule {
+        method: "emulation.setGeolocationOverride",
+        path: "coordinates.longitude",
+        constraint: Constraint::Range(-180.0, 180.0),
+    },
+    Rule {
+        method: "emulation.setGeolocationOverride",
+        path: "coordinates.accuracy",
+        constraint: Constraint::Ge(0.0),
+    },
+    Rule {
+        method: "emulation.setGeolocationOverride",
+        path: "coordinates.altitudeAccuracy",
+        constraint: Constraint::Ge(0.0),
+    },
+    Rule {
+        method: "emulation.setGeolocationOverride",
+        path: "coordinates.heading",
+        constraint: Constraint::Range(0.0, 360.0),
+    },
+    Rule {
+        method: "emulation.setGeolocationOverride",
+        path: "coordinates.speed",
+        constraint: Constraint::Ge(0.0),
+    },
+    // emulation.SetTouchOverrideParameters
+    Rule {
+        method: "emulation.setTouchOverride",
+        path: "maxTouchPoints",
+        constraint: Constraint::Ge(1.0),
+    },
+    // input.PointerCommonProperties
+    Rule {
+        method: "input.performActions",
+        path: "actions.*.actions.*.twist",
+        constraint: Constraint::Range(0.0, 359.0),
+    },
+    Rule {
+        method: "input.performActions",
+        path: "actions.*.actions.*.altitudeAngle",
+        constraint: Constraint::Range(0.0, std::f64::consts::FRAC_PI_2),
+    },
+    Rule {
+        method: "input.performActions",
+        path: "actions.*.actions.*.azimuthAngle",
+        constraint: Constraint::Range(0.0, std::f64::consts::TAU),
+    },
+    // session.ManualProxyConfiguration
+    Rule {
+        method: "browser.createUserContext",
+        path: "proxy.socksVersion",
+        constraint: Constraint::Range(0.0, 255.0),
+    },
+    // [+browsingContext.BrowsingContext] and [+browser.UserContext]
+    Rule {
+        method: ANY_METHOD,
+        path: "contexts",
+        constraint: Constraint::NonEmpty,
+    },
+    Rule {
+        method: ANY_METHOD,
+        path: "userContexts",
+        constraint: Constraint::NonEmpty,
+    },
+    // session.SubscriptionRequest and session.UnsubscribeByIDRequest
+    Rule {
+        method: "session.subscribe",
+        path: "events",
+        constraint: Constraint::NonEmpty,
+    },
+    Rule {
+        method: "session.unsubscribe",
+        path: "events",
+        constraint: Constraint::NonEmpty,
+    },
+    Rule {
+        method: "session.unsubscribe",
+        path: "subscriptions",
+        constraint: Constraint::NonEmpty,
+    },
+];
+
+/// Validate a serialized command against the constraints of the remote end CDDL definition.
+///
+/// Every integer in the command must fit in a `js-int` or `js-uint`, and the value
+/// constraints of the command parameters (ranges, lower bounds, non-empty lists) must hold.
+///
+/// # Arguments
+///
+/// * `command` - The command serialized as a JSON value.
+///
+/// # Returns
+///
+/// An empty result or a `CommandError::InvalidParameters` describing the first violation.
+pub fn validate_command(command: &Value) -> Result<(), CommandError> {
+    let method = command
+        .get(METHOD_KEY)
+        .and_then(|method| method.as_str())
+        .unwrap_or_default();
+
+    check_command(method, command)
+        .map_err(|reason| CommandError::InvalidParameters(format!("{} {}", method, reason)))
+}
+
+fn check_command(method: &str, command: &Value) -> Result<(), String> {
+    check_integers("", command)?;
+
+    let Some(params) = command.get(PARAMS_KEY) else {
+        return Ok(());
+    };
+
+    for rule in RULES
+        .iter()
+        .filter(|rule| rule.method == ANY_METHOD || rule.method == method)
+    {
+        let segments = rule.path.split('.').collect::<Vec<_>>();
+        check_rule(rule, &segments, PARAMS_KEY.to_string(), params)?;
+    }
+
+    Ok(())
+}
+
+/// Recursively check that every integer of the value is within the safe JavaScript integer range.
+fn check_integers(path: &str, value: &Value) -> Result<(), String> {
+    match value {
+        Value::Number(number) => {
+            let out_of_range = match (number.as_u64(), number.as_i64()) {
+                (Some(n), _) => n > MAX_SAFE_INTEGER,
+                (None, Some(n)) => n.unsigned_abs() > MAX_SAFE_INTEGER,
+                (None, None) => false,
+            };
+            if out_of_range {
+                return Err(invalid(
+                    path,
+                    format!("{} is outside of the js-int/js-uint range", number),
+                ));
+            }
+            Ok(())
+        }
+        Value::Array(items) => items
+            .iter()
+            .enumerate()
+            .try_for_each(|(idx, item)| check_integers(&format!("{}.{}", path, idx), item)),
+        Value::Object(map) => map.iter().try_for_each(|(key, item)| {
+            let path = if path.is_empty() {
+                key.to_string()
+            } else {
+                format!("{}.{}", path, key)
+            };
+            check_integers(&path, item)
+        }),
+        _ => Ok(()),
+    }
+}
+
+/// Walk the path segments and check the constraint on every value they lead to.
+///
+/// Missing optional fields and `null` values are skipped.
+fn check_rule(rule: &Rule, segments: &[&str], path: String, value: &Value) -> Result<(), String> {
+    let Some((segment, rest)) = segments.split_first() else {
+        return check_constraint(&path, rule.constraint, value);
+    };
+
+    if *segment == "*" {
+        let Some(items) = value.as_array() else {
+            return Ok(());
+        };
+        for (idx, item) in items.iter().enumerate() {
+            check_rule(rule, rest, format!("{}.{}", path, idx), item)?;
+        }
+        return Ok(());
+    }
+
+    match value.get(segment) {
+        Some(next) => check_rule(rule, rest, format!("{}.{}", path, segment), next),
+        None => Ok(()),
+    }
+}
+
+fn check_constraint(path: &str, constraint: Constraint, value: &Value) -> Result<(), String> {
+    if value.is_null() {
+        return Ok(());
+    }
+
+    let valid = match constraint {
+        Constraint::Range(lower, upper) => value
+            .as_f64()
+            .is_none_or(|number| number >= lower && number <= upper),
+        Constraint::Ge(lower) => value.as_f64().is_none_or(|number| number >= lower),
+        Constraint::Gt(lower) => value.as_f64().is_none_or(|number| number > lower),
+        Constraint::NonEmpty => value.as_array().is_none_or(|items| !items.is_empty()),
+    };
+
+    if valid {
+        return Ok(());
+    }
+
+    let expected = match constraint {
+        Constraint::Range(lower, upper) => format!("a value in {}..{}", lower, upper),
+        Constraint::Ge(lower) => format!("a value greater than or equal to {}", lower),
+        Constraint::Gt(lower) => format!("a value greater than {}", lower),
+        Constraint::NonEmpty => "a non-empty list".to_string(),
+    };
+    Err(invalid(
+        path,
+        format!("expected {}, got {}", expected, value),
+    ))
+}
+
+fn invalid(path: &str, reason: String) -> String {
+    format!("`{}`: {}", path, reason)
+}
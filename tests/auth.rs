@@ -0,0 +1,213 @@
+use std::time::Duration;
+
+use anyhow::Result;
+use axum::Router;
+use axum::http::{HeaderMap, StatusCode, header};
+use axum::response::{Html, IntoResponse};
+use axum::routing::get;
+use webdriverbidi::helpers::auth::{AuthAnswer, AuthResponder, CredentialScope, CredentialStore};
+use webdriverbidi::model::browsing_context::ReadinessState;
+use webdriverbidi::model::network::{
+    AuthChallenge, NoCredentialsAction, UrlPattern, UrlPatternPattern, UrlPatternString,
+};
+
+mod utils;
+
+const TIMEOUT: Duration = Duration::from_secs(10);
+
+// user:secret
+const AUTHORIZATION: &str = "Basic dXNlcjpzZWNyZXQ=";
+
+async fn serve_pages() -> Result<(String, tokio::task::JoinHandle<()>)> {
+    async fn protected(headers: HeaderMap) -> impl IntoResponse {
+        match headers.get(header::AUTHORIZATION) {
+            Some(value) if value == AUTHORIZATION => Html("<p>welcome</p>").into_response(),
+            _ => (
+                StatusCode::UNAUTHORIZED,
+                [(header::WWW_AUTHENTICATE, "Basic realm=\"test\"")],
+                Html("<p>denied</p>"),
+            )
+                .into_response(),
+        }
+    }
+
+    let app = Router::new().route("/protected", get(protected));
+    utils::axum_utils::serve_router(app).await
+}
+
+fn challenge(realm: &str) -> AuthChallenge {
+    AuthChallenge {
+        scheme: "basic".to_string(),
+        realm: realm.to_string(),
+    }
+}
+
+mod credential_store {
+    use super::*;
+
+    #[test]
+    fn test_find_by_origin_and_realm() {
+        let mut store = CredentialStore::new();
+        store.add(
+            CredentialScope::Origin("http://localhost:8080".to_string()),
+            Some("admin"),
+            "admin",
+            "admin-secret",
+        );
+        store.add(
+            CredentialScope::Origin("http://localhost:8080".to_string()),
+            None,
+            "user",
+            "secret",
+        );
+
+        let admin = store.find("http://localhost:8080/admin", &[challenge("admin")]);
+        assert_eq!(admin.map(|c| c.username), Some("admin".to_string()));
+
+        let user = store.find("http://localhost:8080/", &[challenge("other")]);
+        assert_eq!(user.map(|c| c.username), Some("user".to_string()));
+
+        assert!(
+            store
+                .find("http://localhost:9090/", &[challenge("admin")])
+                .is_none()
+        );
+        assert!(
+            store
+                .find("https://localhost:8080/", &[challenge("admin")])
+                .is_none()
+        );
+    }
+
+    #[test]
+    fn test_find_by_pattern() {
+        let mut store = CredentialStore::new();
+        store.add(
+            CredentialScope::Pattern(UrlPattern::UrlPatternPattern(
+                UrlPatternPattern::builder()
+                    .hostname("example.com")
+                    .pathname("private")
+                    .build(),
+            )),
+            None,
+            "pattern",
+            "secret",
+        );
+        store.add(
+            CredentialScope::Pattern(UrlPattern::UrlPatternString(UrlPatternString::new(
+                "https://example.org/api?v=1".to_string(),
+            ))),
+            None,
+            "string",
+            "secret",
+        );
+
+        let find = |url: &str| store.find(url, &[challenge("any")]).map(|c| c.username);
+        assert_eq!(
+            find("https://example.com/private"),
+            Some("pattern".to_string())
+        );
+        assert_eq!(
+            find("http://EXAMPLE.com:81/private"),
+            Some("pattern".to_string())
+        );
+        assert_eq!(find("https://example.com/public"), None);
+        assert_eq!(
+            find("https://example.org/api?v=1"),
+            Some("string".to_string())
+        );
+        assert_eq!(find("https://example.org/api?v=2"), None);
+    }
+}
+
+mod auth_responder {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_provides_matching_credentials() -> Result<()> {
+        let mut bidi_session = utils::session::init().await?;
+        let (base_url, server) = serve_pages().await?;
+        let context = utils::browsing_context::get_nth_context(&mut bidi_session, 0).await?;
+
+        let mut store = CredentialStore::new();
+        store.add(
+            CredentialScope::Origin(base_url.clone()),
+            Some("test"),
+            "user",
+            "secret",
+        );
+        let responder =
+            AuthResponder::new(&mut bidi_session, store, NoCredentialsAction::Cancel).await?;
+        bidi_session
+            .navigate_and_wait(
+                context.clone(),
+                format!("{base_url}/protected"),
+                ReadinessState::Complete,
+                TIMEOUT,
+            )
+            .await?;
+        let text =
+            utils::script::evaluate_string(&mut bidi_session, &context, "document.body.innerText")
+                .await?;
+        let answered = responder.answered().await;
+
+        responder.close().await?;
+        utils::session::close(&mut bidi_session).await?;
+        server.abort();
+
+        assert_eq!(text, "welcome");
+        assert_eq!(answered.len(), 1);
+        assert_eq!(answered[0].challenges[0].realm, "test");
+        assert_eq!(
+            answered[0].answer,
+            AuthAnswer::Credentials {
+                username: "user".to_string()
+            }
+        );
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_rejected_credentials_fall_back() -> Result<()> {
+        let mut bidi_session = utils::session::init().await?;
+        let (base_url, server) = serve_pages().await?;
+        let context = utils::browsing_context::get_nth_context(&mut bidi_session, 0).await?;
+
+        let mut store = CredentialStore::new();
+        store.add(
+            CredentialScope::Origin(base_url.clone()),
+            None,
+            "user",
+            "wrong",
+        );
+        let responder =
+            AuthResponder::new(&mut bidi_session, store, NoCredentialsAction::Cancel).await?;
+        bidi_session
+            .navigate_and_wait(
+                context,
+                format!("{base_url}/protected"),
+                ReadinessState::Complete,
+                TIMEOUT,
+            )
+            .await?;
+        let answered = responder.answered().await;
+
+        responder.close().await?;
+        utils::session::close(&mut bidi_session).await?;
+        server.abort();
+
+        let answers: Vec<AuthAnswer> = answered.into_iter().map(|a| a.answer).collect();
+        assert_eq!(
+            answers,
+            vec![
+                AuthAnswer::Credentials {
+                    username: "user".to_string()
+                },
+                AuthAnswer::Cancel,
+            ]
+        );
+
+        Ok(())
+    }
+}
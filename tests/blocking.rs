@@ -0,0 +1,257 @@
+use anyhow::Result;
+use axum::Router;
+use axum::extract::Path;
+use axum::http::header;
+use axum::response::{Html, IntoResponse};
+use axum::routing::get;
+use webdriverbidi::helpers::blocking::{BlockList, BlockReason, ResourceBlocker};
+
+mod utils;
+
+// A transparent 1x1 GIF
+const GIF: &[u8] = &[
+    0x47, 0x49, 0x46, 0x38, 0x39, 0x61, 0x01, 0x00, 0x01, 0x00, 0x80, 0x00, 0x00, 0x00, 0x00, 0x00,
+    0xff, 0xff, 0xff, 0x21, 0xf9, 0x04, 0x01, 0x00, 0x00, 0x00, 0x00, 0x2c, 0x00, 0x00, 0x00, 0x00,
+    0x01, 0x00, 0x01, 0x00, 0x00, 0x02, 0x02, 0x44, 0x01, 0x00, 0x3b,
+];
+
+// The page is served from 127.0.0.1, the script from localhost plays a third-party
+// analytics domain
+async fn serve_pages() -> Result<(String, tokio::task::JoinHandle<()>)> {
+    async fn page(Path(port): Path<u16>) -> Html<String> {
+        Html(format!(
+            "<img id='a' src='/pixel.gif'><img id='b' src='/pixel.gif?b'>\
+             <script src='http://localhost:{port}/analytics.js'></script>"
+        ))
+    }
+
+    let app = Router::new()
+        .route("/page/{port}", get(page))
+        .route(
+            "/pixel.gif",
+            get(|| async { ([(header::CONTENT_TYPE, "image/gif")], GIF).into_response() }),
+        )
+        .route(
+            "/analytics.js",
+            get(|| async {
+                (
+                    [(header::CONTENT_TYPE, "text/javascript")],
+                    "window.tracked = true;",
+                )
+                    .into_response()
+            }),
+        )
+        .route("/api", get(|| async { "api" }));
+    utils::axum_utils::serve_router(app).await
+}
+
+fn port_of(base_url: &str) -> &str {
+    base_url.rsplit(':').next().unwrap_or_default()
+}
+
+const LOADED: &str = "[...document.images].map((img) => img.naturalWidth).join(',') \
+                      + '|' + String(window.tracked)";
+
+mod block_list {
+    use super::*;
+
+    #[test]
+    fn test_reason() {
+        let block_list = BlockList::new().images().media().host("Analytics.example");
+
+        assert_eq!(
+            block_list.reason("https://example.com/a.png", "image"),
+            Some(BlockReason::Destination("image".to_string()))
+        );
+        assert_eq!(
+            block_list.reason("https://example.com/a.webm", "video"),
+            Some(BlockReason::Destination("video".to_string()))
+        );
+        assert_eq!(
+            block_list.reason("https://cdn.analytics.example/a.js", "script"),
+            Some(BlockReason::Host("analytics.example".to_string()))
+        );
+        assert_eq!(
+            block_list.reason("https://analytics.example/a.png", "image"),
+            Some(BlockReason::Host("analytics.example".to_string()))
+        );
+        assert_eq!(
+            block_list.reason("https://notanalytics.example/a.js", "script"),
+            None
+        );
+        assert_eq!(block_list.reason("https://example.com/", "document"), None);
+    }
+}
+
+mod resource_blocker {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_blocks_and_counts() -> Result<()> {
+        let mut bidi_session = utils::session::init().await?;
+        let (base_url, server) = serve_pages().await?;
+        let context = utils::browsing_context::get_nth_context(&mut bidi_session, 0).await?;
+
+        let blocker = ResourceBlocker::new(
+            &mut bidi_session,
+            BlockList::new().images().host("localhost"),
+            None,
+        )
+        .await?;
+        let port = port_of(&base_url);
+        utils::browsing_context::navigate(
+            &mut bidi_session,
+            context.clone(),
+            format!("{base_url}/page/{port}"),
+        )
+        .await?;
+        let loaded = utils::script::evaluate_string(&mut bidi_session, &context, LOADED).await?;
+        let api = utils::script::evaluate_string(
+            &mut bidi_session,
+            &context,
+            "fetch('/api').then((r) => r.text())",
+        )
+        .await?;
+        let blocked = blocker.blocked().await;
+        let total = blocker.blocked_total().await;
+
+        blocker.close().await?;
+        utils::session::close(&mut bidi_session).await?;
+        server.abort();
+
+        assert_eq!(loaded, "0,0|undefined");
+        assert_eq!(api, "api");
+        assert_eq!(
+            blocked.get(&BlockReason::Destination("image".to_string())),
+            Some(&2)
+        );
+        assert_eq!(
+            blocked.get(&BlockReason::Host("localhost".to_string())),
+            Some(&1)
+        );
+        assert_eq!(total, 3);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_scoped_to_user_contexts() -> Result<()> {
+        let mut bidi_session = utils::session::init().await?;
+        let (base_url, server) = serve_pages().await?;
+        let default_context =
+            utils::browsing_context::get_nth_context(&mut bidi_session, 0).await?;
+        let user_context = utils::browser::create_user_context(&mut bidi_session).await?;
+        let blocked_context = utils::browsing_context::new_tab_in_user_context(
+            &mut bidi_session,
+            user_context.clone(),
+        )
+        .await?;
+
+        let blocker = ResourceBlocker::new(
+            &mut bidi_session,
+            BlockList::new().images().host("localhost"),
+            Some(vec![user_context.clone()]),
+        )
+        .await?;
+        let url = format!("{base_url}/page/{}", port_of(&base_url));
+        utils::browsing_context::navigate(&mut bidi_session, blocked_context.clone(), url.clone())
+            .await?;
+        utils::browsing_context::navigate(&mut bidi_session, default_context.clone(), url).await?;
+        let blocked =
+            utils::script::evaluate_string(&mut bidi_session, &blocked_context, LOADED).await?;
+        let loaded =
+            utils::script::evaluate_string(&mut bidi_session, &default_context, LOADED).await?;
+
+        blocker.close().await?;
+        utils::browser::remove_user_context(&mut bidi_session, user_context).await?;
+        utils::session::close(&mut bidi_session).await?;
+        server.abort();
+
+        assert_eq!(blocked, "0,0|undefined");
+        assert_eq!(loaded, "1,1|true");
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_tab_created_after_blocker() -> Result<()> {
+        let mut bidi_session = utils::session::init().await?;
+        let (base_url, server) = serve_pages().await?;
+        let user_context = utils::browser::create_user_context(&mut bidi_session).await?;
+        let blocker = ResourceBlocker::new(
+            &mut bidi_session,
+            BlockList::new().images().host("localhost"),
+            Some(vec![user_context.clone()]),
+        )
+        .await?;
+
+        // The first navigation of the tab may reach the blocker before the tree knows
+        // the tab, it must be blocked all the same
+        let blocked_context = utils::browsing_context::new_tab_in_user_context(
+            &mut bidi_session,
+            user_context.clone(),
+        )
+        .await?;
+        let url = format!("{base_url}/page/{}", port_of(&base_url));
+        utils::browsing_context::navigate(&mut bidi_session, blocked_context.clone(), url).await?;
+        let blocked =
+            utils::script::evaluate_string(&mut bidi_session, &blocked_context, LOADED).await?;
+
+        blocker.close().await?;
+        utils::browser::remove_user_context(&mut bidi_session, user_context).await?;
+        utils::session::close(&mut bidi_session).await?;
+        server.abort();
+
+        assert_eq!(blocked, "0,0|undefined");
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_blockers_of_several_user_contexts() -> Result<()> {
+        let mut bidi_session = utils::session::init().await?;
+        let (base_url, server) = serve_pages().await?;
+        let first = utils::browser::create_user_context(&mut bidi_session).await?;
+        let second = utils::browser::create_user_context(&mut bidi_session).await?;
+        let first_context =
+            utils::browsing_context::new_tab_in_user_context(&mut bidi_session, first.clone())
+                .await?;
+        let second_context =
+            utils::browsing_context::new_tab_in_user_context(&mut bidi_session, second.clone())
+                .await?;
+
+        let images = ResourceBlocker::new(
+            &mut bidi_session,
+            BlockList::new().images(),
+            Some(vec![first.clone()]),
+        )
+        .await?;
+        let analytics = ResourceBlocker::new(
+            &mut bidi_session,
+            BlockList::new().host("localhost"),
+            Some(vec![second.clone()]),
+        )
+        .await?;
+        let url = format!("{base_url}/page/{}", port_of(&base_url));
+        utils::browsing_context::navigate(&mut bidi_session, first_context.clone(), url.clone())
+            .await?;
+        utils::browsing_context::navigate(&mut bidi_session, second_context.clone(), url).await?;
+        let first_loaded =
+            utils::script::evaluate_string(&mut bidi_session, &first_context, LOADED).await?;
+        let second_loaded =
+            utils::script::evaluate_string(&mut bidi_session, &second_context, LOADED).await?;
+
+        images.close().await?;
+        analytics.close().await?;
+        utils::browser::remove_user_context(&mut bidi_session, first).await?;
+        utils::browser::remove_user_context(&mut bidi_session, second).await?;
+        utils::session::close(&mut bidi_session).await?;
+        server.abort();
+
+        // The requests neither blocker blocks are continued instead of hanging
+        assert_eq!(first_loaded, "0,0|true");
+        assert_eq!(second_loaded, "1,1|undefined");
+
+        Ok(())
+    }
+}
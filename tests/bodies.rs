@@ -0,0 +1,143 @@
+use std::time::Duration;
+
+use anyhow::Result;
+use axum::Router;
+use axum::response::Html;
+use axum::routing::get;
+use webdriverbidi::events::EventType;
+use webdriverbidi::helpers::bodies::{Body, BodyFilter, bytes_to_text};
+use webdriverbidi::model::browsing_context::BrowsingContext;
+use webdriverbidi::model::network::{BytesValue, Request, ResponseCompletedParameters};
+use webdriverbidi::model::session::SubscriptionRequest;
+use webdriverbidi::session::WebDriverBiDiSession;
+
+mod utils;
+
+const TIMEOUT: Duration = Duration::from_secs(10);
+const PAGE: &str = "<p>a page large enough to exceed a tiny collector</p>";
+
+async fn serve_pages() -> Result<(String, tokio::task::JoinHandle<()>)> {
+    let app = Router::new().route("/page", get(|| async { Html(PAGE) }));
+    utils::axum_utils::serve_router(app).await
+}
+
+// Navigate to the page and return the id of its request, once it completed
+async fn load_page(
+    session: &mut WebDriverBiDiSession,
+    context: BrowsingContext,
+    url: String,
+) -> Result<Request> {
+    let event = EventType::NetworkResponseCompleted;
+    let mut receiver = session.add_event_listener(vec![event]).await;
+    session
+        .session_subscribe(SubscriptionRequest::new(
+            vec![event.as_str().to_string()],
+            None,
+            None,
+        ))
+        .await?;
+    utils::browsing_context::navigate(session, context, url.clone()).await?;
+
+    loop {
+        let mut event = tokio::time::timeout(TIMEOUT, receiver.recv())
+            .await?
+            .ok_or_else(|| anyhow::anyhow!("the listener was closed"))?;
+        let params: ResponseCompletedParameters = serde_json::from_value(event["params"].take())?;
+        if params.response.url == url {
+            return Ok(params.base.request.request);
+        }
+    }
+}
+
+mod body {
+    use super::*;
+
+    #[test]
+    fn test_decode_string_value() -> Result<()> {
+        let body = Body::try_from(BytesValue::string("text"))?;
+
+        assert_eq!(body, Body::Text("text".to_string()));
+        assert_eq!(body.bytes(), b"text");
+        assert_eq!(body.text()?, "text");
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_decode_base64_value() -> Result<()> {
+        let body = Body::try_from(BytesValue::base64("AAH/"))?;
+
+        assert_eq!(body, Body::Bytes(vec![0, 1, 255]));
+        assert!(body.text().is_err());
+        assert_eq!(body.into_bytes(), vec![0, 1, 255]);
+
+        let text = Body::try_from(BytesValue::base64("aGk="))?;
+        assert_eq!(text.text()?, "hi");
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_invalid_base64_value() {
+        assert!(Body::try_from(BytesValue::base64("not base64!")).is_err());
+    }
+
+    #[test]
+    fn test_bytes_to_text() {
+        assert_eq!(bytes_to_text(&BytesValue::string("text")), "text");
+        assert_eq!(bytes_to_text(&BytesValue::base64("aGk=")), "hi");
+        assert_eq!(bytes_to_text(&BytesValue::base64("AP8=")), "\0\u{fffd}");
+        // The invalid values are kept rather than dropped
+        assert_eq!(
+            bytes_to_text(&BytesValue::base64("not base64!")),
+            "not base64!"
+        );
+    }
+}
+
+mod capture_bodies {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_read_body_twice() -> Result<()> {
+        let mut bidi_session = utils::session::init().await?;
+        let (base_url, server) = serve_pages().await?;
+        let context = utils::browsing_context::get_nth_context(&mut bidi_session, 0).await?;
+
+        let capture = bidi_session
+            .capture_bodies(BodyFilter::new(1024).contexts(vec![context.clone()]))
+            .await?;
+        let request = load_page(&mut bidi_session, context, format!("{base_url}/page")).await?;
+        let first = capture.body(&request).await?;
+        // The data was disowned by the first read, the second one is served by the handle
+        let second = capture.body(&request).await?;
+
+        capture.close().await?;
+        utils::session::close(&mut bidi_session).await?;
+        server.abort();
+
+        assert_eq!(first.text()?, PAGE);
+        assert_eq!(first, second);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_body_exceeding_max_size() -> Result<()> {
+        let mut bidi_session = utils::session::init().await?;
+        let (base_url, server) = serve_pages().await?;
+        let context = utils::browsing_context::get_nth_context(&mut bidi_session, 0).await?;
+
+        let capture = bidi_session.capture_bodies(BodyFilter::new(8)).await?;
+        let request = load_page(&mut bidi_session, context, format!("{base_url}/page")).await?;
+        let result = capture.body(&request).await;
+
+        capture.close().await?;
+        utils::session::close(&mut bidi_session).await?;
+        server.abort();
+
+        assert!(result.is_err());
+
+        Ok(())
+    }
+}
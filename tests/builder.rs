@@ -0,0 +1,82 @@
+use serde_json::json;
+use webdriverbidi::model::browsing_context::{NavigateParameters, ReadinessState};
+use webdriverbidi::model::network::{BytesValue, SetCookieHeader, StringValue, UrlPatternPattern};
+use webdriverbidi::model::script::{CallFunctionParameters, ContextTarget, Target};
+
+fn string_value(value: &str) -> BytesValue {
+    BytesValue::StringValue(StringValue {
+        value_type: "string".to_string(),
+        value: value.to_string(),
+    })
+}
+
+mod define_builder {
+    use super::*;
+
+    #[test]
+    fn test_required_and_optional_fields() {
+        let params = NavigateParameters::builder("context", "https://example.com")
+            .wait(ReadinessState::Complete)
+            .build();
+
+        assert_eq!(
+            serde_json::to_value(&params).unwrap(),
+            json!({"context": "context", "url": "https://example.com", "wait": "complete"})
+        );
+    }
+
+    #[test]
+    fn test_unset_optional_fields_are_skipped() {
+        let target = Target::ContextTarget(ContextTarget::builder("context").build());
+        let params = CallFunctionParameters::builder("() => 1", true, target)
+            .user_activation(true)
+            .build();
+
+        assert_eq!(
+            serde_json::to_value(&params).unwrap(),
+            json!({
+                "functionDeclaration": "() => 1",
+                "awaitPromise": true,
+                "target": {"context": "context"},
+                "userActivation": true,
+            })
+        );
+    }
+
+    #[test]
+    fn test_matches_constructor() {
+        let value = string_value("value");
+        let built = SetCookieHeader::builder("name", value)
+            .max_age(60)
+            .secure(true)
+            .build();
+
+        let value = string_value("value");
+        let constructed = SetCookieHeader::new(
+            "name".to_string(),
+            value,
+            None,
+            None,
+            None,
+            Some(60),
+            None,
+            None,
+            Some(true),
+        );
+
+        assert_eq!(
+            serde_json::to_value(&built).unwrap(),
+            serde_json::to_value(&constructed).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_fixed_fields() {
+        let pattern = UrlPatternPattern::builder().hostname("example.com").build();
+
+        assert_eq!(
+            serde_json::to_value(&pattern).unwrap(),
+            json!({"type": "pattern", "hostname": "example.com"})
+        );
+    }
+}
@@ -0,0 +1,169 @@
+use anyhow::Result;
+use serde_json::json;
+use webdriverbidi::helpers::console::{
+    ConsoleCollector, ConsoleFilter, ConsoleMessageKind, format_stack_trace, render_remote_value,
+};
+use webdriverbidi::model::log::{Entry, Level};
+use webdriverbidi::model::script::{RemoteValue, StackTrace};
+
+mod utils;
+
+fn render(value: serde_json::Value) -> Result<String> {
+    let value: RemoteValue = serde_json::from_value(value)?;
+    Ok(render_remote_value(&value))
+}
+
+mod render_remote_value {
+    use super::*;
+
+    #[test]
+    fn test_primitives() -> Result<()> {
+        assert_eq!(render(json!({"type": "string", "value": "text"}))?, "text");
+        assert_eq!(render(json!({"type": "number", "value": 42}))?, "42");
+        assert_eq!(render(json!({"type": "number", "value": 1.5}))?, "1.5");
+        assert_eq!(
+            render(json!({"type": "number", "value": "-Infinity"}))?,
+            "-Infinity"
+        );
+        assert_eq!(render(json!({"type": "boolean", "value": true}))?, "true");
+        assert_eq!(render(json!({"type": "bigint", "value": "12"}))?, "12n");
+        assert_eq!(render(json!({"type": "null"}))?, "null");
+        assert_eq!(render(json!({"type": "undefined"}))?, "undefined");
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_containers() -> Result<()> {
+        assert_eq!(
+            render(json!({"type": "array", "value": [
+                {"type": "string", "value": "a"},
+                {"type": "number", "value": 1},
+            ]}))?,
+            r#"["a", 1]"#
+        );
+        assert_eq!(
+            render(json!({"type": "object", "value": [
+                ["key", {"type": "array", "value": [{"type": "null"}]}],
+            ]}))?,
+            "{key: [null]}"
+        );
+        assert_eq!(
+            render(json!({"type": "map", "value": [
+                [{"type": "number", "value": 1}, {"type": "string", "value": "one"}],
+            ]}))?,
+            r#"Map(1) {1 => "one"}"#
+        );
+        assert_eq!(
+            render(json!({"type": "set", "value": [{"type": "boolean", "value": false}]}))?,
+            "Set(1) {false}"
+        );
+        assert_eq!(
+            render(json!({"type": "function", "handle": "h"}))?,
+            "[function]"
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_format_stack_trace() -> Result<()> {
+        let stack_trace: StackTrace = serde_json::from_value(json!({"callFrames": [
+            {"columnNumber": 5, "functionName": "load", "lineNumber": 10, "url": "http://a/app.js"},
+            {"columnNumber": 1, "functionName": "", "lineNumber": 2, "url": "http://a/"},
+        ]}))?;
+
+        assert_eq!(
+            format_stack_trace(&stack_trace),
+            "    at load (http://a/app.js:10:5)\n    at <anonymous> (http://a/:2:1)"
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_console_entry_is_not_generic() -> Result<()> {
+        let entry: Entry = serde_json::from_value(json!({
+            "type": "console",
+            "level": "info",
+            "source": {"realm": "realm"},
+            "text": "hello",
+            "timestamp": 1,
+            "method": "log",
+            "args": [{"type": "string", "value": "hello"}],
+        }))?;
+
+        assert!(matches!(entry, Entry::ConsoleLogEntry(ref e) if e.method == "log"));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_javascript_entry_is_not_generic() -> Result<()> {
+        let entry: Entry = serde_json::from_value(json!({
+            "type": "javascript",
+            "level": "error",
+            "source": {"realm": "realm"},
+            "text": "boom",
+            "timestamp": 1,
+        }))?;
+
+        assert!(matches!(entry, Entry::JavascriptLogEntry(ref e) if e.base.level == Level::Error));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_render_unknown_value() -> Result<()> {
+        assert_eq!(
+            render(json!({"type": "vendor", "handle": "h"}))?,
+            "[vendor]"
+        );
+
+        Ok(())
+    }
+}
+
+mod console_collector {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_collects_console_and_errors() -> Result<()> {
+        let mut bidi_session = utils::session::init().await?;
+        let context = utils::browsing_context::get_nth_context(&mut bidi_session, 0).await?;
+        let collector = ConsoleCollector::new(&mut bidi_session, None).await?;
+
+        utils::script::evaluate(
+            &mut bidi_session,
+            &context,
+            "console.warn('count', 3, [1, 'a']); setTimeout(() => { throw new Error('boom'); })",
+        )
+        .await?;
+        tokio::time::sleep(std::time::Duration::from_millis(500)).await;
+
+        let warnings = collector
+            .filter(
+                &ConsoleFilter::new()
+                    .context(&context)
+                    .level(Level::Warn)
+                    .console(),
+            )
+            .await;
+        let errors = collector.js_errors().await;
+        let assertion = tokio::spawn(async move { collector.assert_no_js_errors().await }).await;
+
+        utils::session::close(&mut bidi_session).await?;
+
+        assert_eq!(warnings.len(), 1);
+        assert_eq!(
+            warnings[0].kind,
+            ConsoleMessageKind::Console("warn".to_string())
+        );
+        assert_eq!(warnings[0].args, vec!["count", "3", r#"[1, "a"]"#]);
+        assert_eq!(errors.len(), 1);
+        assert!(errors[0].text.contains("boom"));
+        assert!(assertion.is_err());
+
+        Ok(())
+    }
+}
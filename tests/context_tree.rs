@@ -0,0 +1,87 @@
+use std::time::Duration;
+
+use anyhow::Result;
+use webdriverbidi::helpers::context_tree::ContextTree;
+use webdriverbidi::model::browsing_context::CloseParameters;
+
+mod utils;
+
+const DEFAULT_HTML: &str = "default.html";
+
+// Give the events time to reach the tree
+async fn settle() {
+    tokio::time::sleep(Duration::from_millis(500)).await;
+}
+
+mod context_tree {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_tracks_iframes_and_urls() -> Result<()> {
+        let mut bidi_session = utils::session::init().await?;
+        let (url, server) = utils::axum_utils::serve_static(DEFAULT_HTML).await?;
+        let context = utils::browsing_context::get_nth_context(&mut bidi_session, 0).await?;
+        let tree = ContextTree::new(&mut bidi_session).await?;
+
+        utils::browsing_context::navigate(&mut bidi_session, context.clone(), url.clone()).await?;
+        utils::script::evaluate(
+            &mut bidi_session,
+            &context,
+            "new Promise((resolve) => {
+                const iframe = document.createElement('iframe');
+                iframe.onload = resolve;
+                document.body.appendChild(iframe);
+            })",
+        )
+        .await?;
+        settle().await;
+
+        let top_level = tree.top_level().await;
+        let children = tree.children(&context).await;
+        let descendants = tree.descendants(&context).await;
+        let current_url = tree.url(&context).await;
+
+        tree.close().await?;
+        utils::session::close(&mut bidi_session).await?;
+        server.abort();
+
+        assert!(top_level.iter().any(|node| node.context == context));
+        assert_eq!(children.len(), 1);
+        assert_eq!(children[0].parent.as_ref(), Some(&context));
+        assert_eq!(descendants.len(), 1);
+        assert_eq!(current_url.as_deref(), Some(url.as_str()));
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_tracks_user_contexts() -> Result<()> {
+        let mut bidi_session = utils::session::init().await?;
+        let tree = ContextTree::new(&mut bidi_session).await?;
+
+        let user_context = utils::browser::create_user_context(&mut bidi_session).await?;
+        let tab = utils::browsing_context::new_tab_in_user_context(
+            &mut bidi_session,
+            user_context.clone(),
+        )
+        .await?;
+        settle().await;
+        let in_user_context = tree.top_level_in_user_context(&user_context).await;
+
+        bidi_session
+            .browsing_context_close(CloseParameters::new(tab.clone(), None))
+            .await?;
+        settle().await;
+        let closed = tree.get(&tab).await;
+
+        tree.close().await?;
+        utils::browser::remove_user_context(&mut bidi_session, user_context).await?;
+        utils::session::close(&mut bidi_session).await?;
+
+        assert_eq!(in_user_context.len(), 1);
+        assert_eq!(in_user_context[0].context, tab);
+        assert!(closed.is_none());
+
+        Ok(())
+    }
+}
@@ -0,0 +1,166 @@
+use anyhow::Result;
+use serde_json::{Value, json};
+use webdriverbidi::helpers::cookies::{from_netscape, parse_set_cookie, to_netscape};
+use webdriverbidi::model::network::{BytesValue, Cookie, SameSite};
+use webdriverbidi::model::storage::PartialCookie;
+
+fn cookie(value: Value, http_only: bool, same_site: &str, expiry: Option<u64>) -> Result<Cookie> {
+    let mut cookie = json!({
+        "name": "id",
+        "value": value,
+        "domain": ".example.com",
+        "path": "/",
+        "size": 4,
+        "httpOnly": http_only,
+        "secure": true,
+        "sameSite": same_site,
+    });
+    if let Some(expiry) = expiry {
+        cookie["expiry"] = json!(expiry);
+    }
+    Ok(serde_json::from_value(cookie)?)
+}
+
+mod set_cookie {
+    use super::*;
+
+    #[test]
+    fn test_parse_attributes() -> Result<()> {
+        let header = parse_set_cookie(
+            "sid=a=b; Path=/app; domain=example.com; Max-Age=60; \
+             Expires=Wed, 21 Oct 2026 07:28:00 GMT; SameSite=strict; Secure; HttpOnly; Foo=bar",
+        )
+        .ok_or_else(|| anyhow::anyhow!("the header wasn't parsed"))?;
+
+        assert_eq!(header.name, "sid");
+        assert_eq!(header.value, BytesValue::string("a=b"));
+        assert_eq!(header.path.as_deref(), Some("/app"));
+        assert_eq!(header.domain.as_deref(), Some("example.com"));
+        assert_eq!(header.max_age, Some(60));
+        assert_eq!(
+            header.expiry.as_deref(),
+            Some("Wed, 21 Oct 2026 07:28:00 GMT")
+        );
+        assert_eq!(header.same_site, Some(SameSite::Strict));
+        assert_eq!(header.secure, Some(true));
+        assert_eq!(header.http_only, Some(true));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_parse_minimal_and_invalid() {
+        let header = parse_set_cookie("a=").map(|header| serde_json::to_value(header).ok());
+        assert_eq!(
+            header,
+            Some(Some(
+                json!({"name": "a", "value": {"type": "string", "value": ""}})
+            ))
+        );
+
+        assert_eq!(
+            parse_set_cookie("a=1; SameSite=Weird").and_then(|header| header.same_site),
+            Some(SameSite::Unknown("Weird".to_string()))
+        );
+        assert!(parse_set_cookie("no pair").is_none());
+        assert!(parse_set_cookie("=value").is_none());
+    }
+}
+
+mod partial_cookie {
+    use super::*;
+
+    #[test]
+    fn test_from_cookie() -> Result<()> {
+        let lax = cookie(
+            json!({"type": "base64", "value": "aGk="}),
+            true,
+            "lax",
+            Some(1700000000),
+        )?;
+        assert_eq!(
+            serde_json::to_value(PartialCookie::from(&lax))?,
+            json!({
+                "name": "id",
+                "value": {"type": "base64", "value": "aGk="},
+                "domain": ".example.com",
+                "path": "/",
+                "httpOnly": true,
+                "secure": true,
+                "sameSite": "lax",
+                "expiry": 1700000000,
+            })
+        );
+
+        let default = cookie(
+            json!({"type": "string", "value": "1"}),
+            false,
+            "default",
+            None,
+        )?;
+        let partial = PartialCookie::from(&default);
+        assert!(partial.same_site.is_none());
+        assert!(partial.expiry.is_none());
+
+        Ok(())
+    }
+}
+
+mod netscape {
+    use super::*;
+
+    #[test]
+    fn test_export_and_import() -> Result<()> {
+        let cookies = vec![
+            cookie(
+                json!({"type": "base64", "value": "aGk="}),
+                true,
+                "lax",
+                Some(1700000000),
+            )?,
+            cookie(json!({"type": "string", "value": "1"}), false, "none", None)?,
+        ];
+
+        let text = to_netscape(&cookies);
+        assert_eq!(
+            text,
+            "# Netscape HTTP Cookie File\n\n\
+             #HttpOnly_.example.com\tTRUE\t/\tTRUE\t1700000000\tid\thi\n\
+             .example.com\tTRUE\t/\tTRUE\t0\tid\t1\n"
+        );
+
+        let imported = from_netscape(&text)?;
+        assert_eq!(imported.len(), 2);
+        assert_eq!(imported[0].value, BytesValue::string("hi"));
+        assert_eq!(imported[0].http_only, Some(true));
+        assert_eq!(imported[0].expiry, Some(1700000000));
+        assert_eq!(imported[1].http_only, Some(false));
+        assert_eq!(imported[1].expiry, None);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_import_include_subdomains() -> Result<()> {
+        let imported = from_netscape(
+            ".example.com\tFALSE\t/\tFALSE\t0\thost\t1\n\
+             example.com\tTRUE\t/\tFALSE\t0\tdomain\t2\n",
+        )?;
+
+        assert_eq!(imported[0].domain, "example.com");
+        assert_eq!(imported[1].domain, ".example.com");
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_import_malformed_line() {
+        let result = from_netscape("# comment\n\nexample.com\tFALSE\t/\n");
+
+        assert!(
+            result
+                .err()
+                .is_some_and(|e| e.to_string().contains("line 3"))
+        );
+    }
+}
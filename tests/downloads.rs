@@ -0,0 +1,176 @@
+use std::time::Duration;
+
+use anyhow::Result;
+use axum::Router;
+use axum::http::header;
+use axum::routing::get;
+use webdriverbidi::helpers::downloads::{DownloadStatus, Downloads};
+use webdriverbidi::model::browser::{DownloadBehavior, SetDownloadBehaviorParameters};
+use webdriverbidi::model::browsing_context::{
+    DownloadEndParams, NavigateParameters, ReadinessState,
+};
+
+mod utils;
+
+const FILENAME: &str = "report.txt";
+const CONTENT: &str = "Hello, downloads!";
+
+async fn serve_attachment() -> Result<(String, tokio::task::JoinHandle<()>)> {
+    let app = Router::new().route(
+        "/download",
+        get(|| async {
+            (
+                [(
+                    header::CONTENT_DISPOSITION,
+                    format!("attachment; filename=\"{FILENAME}\""),
+                )],
+                CONTENT,
+            )
+        }),
+    );
+    let (base_url, handle) = utils::axum_utils::serve_router(app).await?;
+    Ok((format!("{base_url}/download"), handle))
+}
+
+mod set_download_behavior {
+    use super::*;
+
+    #[test]
+    fn test_serialize_behaviors() -> Result<()> {
+        let allowed = SetDownloadBehaviorParameters::new(
+            Some(DownloadBehavior::allowed("/tmp/downloads".to_string())),
+            None,
+        );
+        assert_eq!(
+            serde_json::to_value(&allowed)?,
+            serde_json::json!({
+                "downloadBehavior": {"type": "allowed", "destinationFolder": "/tmp/downloads"}
+            })
+        );
+
+        let reset = SetDownloadBehaviorParameters::new(None, None);
+        assert_eq!(
+            serde_json::to_value(&reset)?,
+            serde_json::json!({"downloadBehavior": null})
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_deserialize_download_end() -> Result<()> {
+        let info = serde_json::json!({
+            "context": "ctx",
+            "navigation": "nav",
+            "timestamp": 1,
+            "url": "http://example.com/file",
+        });
+
+        let mut complete = info.clone();
+        complete["status"] = "complete".into();
+        complete["filepath"] = "/tmp/file".into();
+        let params: DownloadEndParams = serde_json::from_value(complete)?;
+        assert!(matches!(
+            params,
+            DownloadEndParams::DownloadComplete(ref p) if p.filepath.as_deref() == Some("/tmp/file")
+        ));
+
+        let mut canceled = info.clone();
+        canceled["status"] = "canceled".into();
+        let params: DownloadEndParams = serde_json::from_value(canceled.clone())?;
+        assert!(matches!(params, DownloadEndParams::DownloadCanceled(_)));
+        assert_eq!(params.status(), "canceled");
+        assert_eq!(serde_json::to_value(&params)?, canceled);
+
+        let mut interrupted = info;
+        interrupted["status"] = "interrupted".into();
+        let params: DownloadEndParams = serde_json::from_value(interrupted)?;
+        assert!(matches!(params, DownloadEndParams::Unknown(_)));
+        assert_eq!(params.status(), "interrupted");
+        assert_eq!(params.base().url, "http://example.com/file");
+
+        Ok(())
+    }
+}
+
+mod wait_for_download {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_allowed() -> Result<()> {
+        let mut bidi_session = utils::session::init().await?;
+        let (url, server) = serve_attachment().await?;
+        let destination = std::env::temp_dir().join("webdriverbidi-downloads");
+        std::fs::create_dir_all(&destination)?;
+
+        bidi_session
+            .browser_set_download_behavior(SetDownloadBehaviorParameters::new(
+                Some(DownloadBehavior::allowed(
+                    destination.to_string_lossy().into_owned(),
+                )),
+                None,
+            ))
+            .await?;
+        let downloads = Downloads::new(&mut bidi_session).await?;
+        let context = utils::browsing_context::get_nth_context(&mut bidi_session, 0).await?;
+
+        // The navigation is aborted once the response turns out to be a download
+        let _ = bidi_session
+            .browsing_context_navigate(
+                NavigateParameters::builder(context.clone(), url.clone())
+                    .wait(ReadinessState::None)
+                    .build(),
+            )
+            .await;
+        let download = downloads
+            .wait_for_download(&context, Duration::from_secs(10))
+            .await?;
+
+        downloads.close().await?;
+        utils::session::close(&mut bidi_session).await?;
+        server.abort();
+
+        assert_eq!(download.status, DownloadStatus::Complete);
+        assert_eq!(download.suggested_filename, FILENAME);
+        assert_eq!(download.url, url);
+        let path = download.path.expect("complete download has a path");
+        assert_eq!(std::fs::read_to_string(path)?, CONTENT);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_denied() -> Result<()> {
+        let mut bidi_session = utils::session::init().await?;
+        let (url, server) = serve_attachment().await?;
+
+        bidi_session
+            .browser_set_download_behavior(SetDownloadBehaviorParameters::new(
+                Some(DownloadBehavior::denied()),
+                None,
+            ))
+            .await?;
+        let downloads = Downloads::new(&mut bidi_session).await?;
+        let context = utils::browsing_context::get_nth_context(&mut bidi_session, 0).await?;
+
+        let _ = bidi_session
+            .browsing_context_navigate(
+                NavigateParameters::builder(context.clone(), url)
+                    .wait(ReadinessState::None)
+                    .build(),
+            )
+            .await;
+        let download = downloads
+            .wait_for_download(&context, Duration::from_secs(10))
+            .await?;
+
+        downloads.close().await?;
+        utils::session::close(&mut bidi_session).await?;
+        server.abort();
+
+        assert_eq!(download.status, DownloadStatus::Canceled);
+        assert!(download.path.is_none());
+
+        Ok(())
+    }
+}
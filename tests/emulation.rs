@@ -0,0 +1,244 @@
+use anyhow::Result;
+use serde_json::json;
+use webdriverbidi::model::emulation::{
+    ForcedColorsModeTheme, NetworkConditions, ScreenArea, ScreenOrientation,
+    ScreenOrientationNatural, ScreenOrientationType, SetForcedColorsModeThemeOverride,
+    SetForcedColorsModeThemeOverrideParameters, SetNetworkConditionsParameters,
+    SetScreenOrientationOverrideParameters, SetScreenSettingsOverride,
+    SetScreenSettingsOverrideParameters, SetScriptingEnabledParameters, SetTouchOverride,
+    SetTouchOverrideParameters, SetUserAgentOverrideParameters,
+};
+
+mod utils;
+
+const USER_AGENT: &str = "webdriverbidi-test-agent";
+const SCRIPT_URL: &str = "data:text/html,<script>document.title = 'executed'</script>";
+
+// https://github.com/web-platform-tests/wpt/tree/master/webdriver/tests/bidi/emulation/set_user_agent_override
+mod set_user_agent_override {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_context() -> Result<()> {
+        let mut bidi_session = utils::session::init().await?;
+        let context = utils::browsing_context::get_nth_context(&mut bidi_session, 0).await?;
+
+        let params = SetUserAgentOverrideParameters::new(
+            Some(USER_AGENT.to_string()),
+            Some(vec![context.clone()]),
+            None,
+        );
+        bidi_session.set_user_agent_override(params).await?;
+        let overridden =
+            utils::script::evaluate_string(&mut bidi_session, &context, "navigator.userAgent")
+                .await?;
+
+        let params = SetUserAgentOverrideParameters::new(None, Some(vec![context.clone()]), None);
+        bidi_session.set_user_agent_override(params).await?;
+        let restored =
+            utils::script::evaluate_string(&mut bidi_session, &context, "navigator.userAgent")
+                .await?;
+
+        utils::session::close(&mut bidi_session).await?;
+
+        assert_eq!(overridden, USER_AGENT);
+        assert_ne!(restored, USER_AGENT);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_user_context() -> Result<()> {
+        let mut bidi_session = utils::session::init().await?;
+        let user_context = utils::browser::create_user_context(&mut bidi_session).await?;
+
+        let params = SetUserAgentOverrideParameters::new(
+            Some(USER_AGENT.to_string()),
+            None,
+            Some(vec![user_context.clone()]),
+        );
+        bidi_session.set_user_agent_override(params).await?;
+
+        let context = utils::browsing_context::new_tab_in_user_context(
+            &mut bidi_session,
+            user_context.clone(),
+        )
+        .await?;
+        let user_agent =
+            utils::script::evaluate_string(&mut bidi_session, &context, "navigator.userAgent")
+                .await?;
+
+        utils::browser::remove_user_context(&mut bidi_session, user_context).await?;
+        utils::session::close(&mut bidi_session).await?;
+
+        assert_eq!(user_agent, USER_AGENT);
+
+        Ok(())
+    }
+}
+
+// https://github.com/web-platform-tests/wpt/tree/master/webdriver/tests/bidi/emulation/set_scripting_enabled
+mod set_scripting_enabled {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_disabled() -> Result<()> {
+        let mut bidi_session = utils::session::init().await?;
+        let context = utils::browsing_context::get_nth_context(&mut bidi_session, 0).await?;
+
+        let params =
+            SetScriptingEnabledParameters::new(Some(false), Some(vec![context.clone()]), None);
+        bidi_session.set_scripting_enabled(params).await?;
+        utils::browsing_context::navigate(&mut bidi_session, context.clone(), SCRIPT_URL.into())
+            .await?;
+        let title =
+            utils::script::evaluate_string(&mut bidi_session, &context, "document.title").await?;
+
+        utils::session::close(&mut bidi_session).await?;
+
+        assert_ne!(title, "executed");
+
+        Ok(())
+    }
+}
+
+mod set_network_conditions {
+    use super::*;
+
+    #[test]
+    fn test_serialize_offline() {
+        let params =
+            SetNetworkConditionsParameters::new(Some(NetworkConditions::offline()), None, None);
+
+        assert_eq!(
+            serde_json::to_value(&params).unwrap(),
+            json!({"networkConditions": {"type": "offline"}})
+        );
+    }
+
+    #[test]
+    fn test_serialize_reset() {
+        let params = SetNetworkConditionsParameters::new(None, None, Some(vec!["default".into()]));
+
+        assert_eq!(
+            serde_json::to_value(&params).unwrap(),
+            json!({"networkConditions": null, "userContexts": ["default"]})
+        );
+    }
+}
+
+mod set_forced_colors_mode_theme_override {
+    use super::*;
+
+    #[test]
+    fn test_serialize_theme() {
+        let params = SetForcedColorsModeThemeOverrideParameters::new(
+            Some(ForcedColorsModeTheme::Dark),
+            Some(vec!["context".into()]),
+            None,
+        );
+
+        assert_eq!(
+            serde_json::to_value(SetForcedColorsModeThemeOverride::new(params)).unwrap(),
+            json!({
+                "method": "emulation.setForcedColorsModeThemeOverride",
+                "params": {"theme": "dark", "contexts": ["context"]},
+            })
+        );
+    }
+
+    #[test]
+    fn test_serialize_reset() {
+        let params = SetForcedColorsModeThemeOverrideParameters::new(None, None, None);
+
+        assert_eq!(
+            serde_json::to_value(&params).unwrap(),
+            json!({"theme": null})
+        );
+    }
+}
+
+mod set_screen_settings_override {
+    use super::*;
+
+    #[test]
+    fn test_serialize_screen_area() {
+        let params = SetScreenSettingsOverrideParameters::new(
+            Some(ScreenArea::new(800, 600)),
+            None,
+            Some(vec!["default".into()]),
+        );
+
+        assert_eq!(
+            serde_json::to_value(SetScreenSettingsOverride::new(params)).unwrap(),
+            json!({
+                "method": "emulation.setScreenSettingsOverride",
+                "params": {
+                    "screenArea": {"width": 800, "height": 600},
+                    "userContexts": ["default"],
+                },
+            })
+        );
+    }
+
+    #[test]
+    fn test_serialize_reset() {
+        let params = SetScreenSettingsOverrideParameters::new(None, None, None);
+
+        assert_eq!(
+            serde_json::to_value(&params).unwrap(),
+            json!({"screenArea": null})
+        );
+    }
+}
+
+mod set_screen_orientation_override {
+    use super::*;
+
+    #[test]
+    fn test_serialize_orientation() {
+        let params = SetScreenOrientationOverrideParameters {
+            screen_orientation: Some(ScreenOrientation {
+                natural: ScreenOrientationNatural::Portrait,
+                orientation_type: ScreenOrientationType::LandscapePrimary,
+            }),
+            contexts: Some(vec!["context".into()]),
+            user_contexts: None,
+        };
+
+        assert_eq!(
+            serde_json::to_value(&params).unwrap(),
+            json!({
+                "screenOrientation": {"natural": "portrait", "type": "landscape-primary"},
+                "contexts": ["context"],
+            })
+        );
+    }
+}
+
+mod set_touch_override {
+    use super::*;
+
+    #[test]
+    fn test_serialize_max_touch_points() {
+        let params = SetTouchOverrideParameters::new(Some(5), Some(vec!["context".into()]), None);
+
+        assert_eq!(
+            serde_json::to_value(SetTouchOverride::new(params)).unwrap(),
+            json!({
+                "method": "emulation.setTouchOverride",
+                "params": {"maxTouchPoints": 5, "contexts": ["context"]},
+            })
+        );
+    }
+
+    #[test]
+    fn test_serialize_reset() {
+        let params = SetTouchOverrideParameters::new(None, None, Some(vec!["default".into()]));
+
+        assert_eq!(
+            serde_json::to_value(&params).unwrap(),
+            json!({"maxTouchPoints": null, "userContexts": ["default"]})
+        );
+    }
+}
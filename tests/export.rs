@@ -0,0 +1,128 @@
+use anyhow::Result;
+use serde_json::{Value, json};
+use webdriverbidi::helpers::bodies::Body;
+use webdriverbidi::helpers::export::{to_curl, to_reqwest};
+use webdriverbidi::model::network::RequestData;
+
+fn request_data(method: &str, headers: Value, cookies: Value) -> Result<RequestData> {
+    Ok(serde_json::from_value(json!({
+        "request": "1",
+        "url": "https://example.com/api?q=it's",
+        "method": method,
+        "headers": headers,
+        "cookies": cookies,
+        "headersSize": 0,
+        "bodySize": null,
+        "destination": "",
+        "initiatorType": null,
+        "timings": {
+            "timeOrigin": 0, "requestTime": 0, "redirectStart": 0, "redirectEnd": 0,
+            "fetchStart": 0, "dnsStart": 0, "dnsEnd": 0, "connectStart": 0,
+            "connectEnd": 0, "tlsStart": 0, "requestStart": 0, "responseStart": 0,
+            "responseEnd": 0,
+        },
+    }))?)
+}
+
+fn cookie(name: &str, value: &str) -> Value {
+    json!({
+        "name": name,
+        "value": {"type": "string", "value": value},
+        "domain": "example.com",
+        "path": "/",
+        "size": 0,
+        "httpOnly": false,
+        "secure": true,
+        "sameSite": "lax",
+    })
+}
+
+mod curl {
+    use super::*;
+
+    #[test]
+    fn test_get_with_headers_and_cookies() -> Result<()> {
+        let request = request_data(
+            "GET",
+            json!([
+                {"name": "Accept", "value": {"type": "string", "value": "*/*"}},
+                {"name": "Accept-Encoding", "value": {"type": "string", "value": "gzip"}},
+                {"name": ":authority", "value": {"type": "string", "value": "example.com"}},
+                {"name": "X-Token", "value": {"type": "base64", "value": "c2VjcmV0"}},
+            ]),
+            json!([cookie("a", "1"), cookie("b", "2")]),
+        )?;
+
+        assert_eq!(
+            to_curl(&request, None),
+            "curl 'https://example.com/api?q=it'\\''s' \\\n  \
+             -H 'Accept: */*' \\\n  \
+             -H 'X-Token: secret' \\\n  \
+             -b 'a=1; b=2' \\\n  \
+             --compressed"
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_post_with_bodies() -> Result<()> {
+        let request = request_data(
+            "POST",
+            json!([
+                {"name": "Content-Type", "value": {"type": "string", "value": "application/json"}},
+                {"name": "Content-Length", "value": {"type": "string", "value": "7"}},
+                {"name": "Cookie", "value": {"type": "string", "value": "a=1"}},
+            ]),
+            json!([cookie("a", "1")]),
+        )?;
+
+        let text = to_curl(&request, Some(&Body::Text("{\"a\":1}".to_string())));
+        assert_eq!(
+            text,
+            "curl -X 'POST' 'https://example.com/api?q=it'\\''s' \\\n  \
+             -H 'Content-Type: application/json' \\\n  \
+             -H 'Cookie: a=1' \\\n  \
+             --data-raw '{\"a\":1}'"
+        );
+
+        let binary = to_curl(&request, Some(&Body::Bytes(vec![0, 1, 255])));
+        assert!(binary.starts_with("printf %s 'AAH/' | base64 -d | curl -X 'POST'"));
+        assert!(binary.ends_with("--data-binary @-"));
+
+        Ok(())
+    }
+}
+
+mod reqwest_snippet {
+    use super::*;
+
+    #[test]
+    fn test_snippet() -> Result<()> {
+        let request = request_data(
+            "PUT",
+            json!([{"name": "X-Quote", "value": {"type": "string", "value": "say \"hi\""}}]),
+            json!([cookie("a", "1")]),
+        )?;
+
+        assert_eq!(
+            to_reqwest(&request, Some(&Body::Text("body".to_string()))),
+            "reqwest::Client::new()\n    \
+             .put(\"https://example.com/api?q=it's\")\n    \
+             .header(\"X-Quote\", \"say \\\"hi\\\"\")\n    \
+             .header(\"Cookie\", \"a=1\")\n    \
+             .body(\"body\")\n    \
+             .send()\n    \
+             .await?"
+        );
+
+        let custom = request_data("PURGE", json!([]), json!([]))?;
+        let snippet = to_reqwest(&custom, Some(&Body::Bytes(vec![0, 255])));
+        assert!(snippet.contains(
+            ".request(reqwest::Method::from_bytes(b\"PURGE\")?, \"https://example.com/api?q=it's\")"
+        ));
+        assert!(snippet.contains(".body(vec![0, 255])"));
+
+        Ok(())
+    }
+}
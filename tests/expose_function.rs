@@ -0,0 +1,107 @@
+use std::sync::Arc;
+
+use anyhow::Result;
+use serde_json::{Value, json};
+use tokio::sync::Mutex;
+
+mod utils;
+
+const DEFAULT_HTML: &str = "default.html";
+
+mod expose_function {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_returns_result_to_page() -> Result<()> {
+        let mut bidi_session = utils::session::init().await?;
+        let (url, server) = utils::axum_utils::serve_static(DEFAULT_HTML).await?;
+        let context = utils::browsing_context::get_nth_context(&mut bidi_session, 0).await?;
+
+        let exposed = bidi_session
+            .expose_function("add", None, |args: Vec<Value>| async move {
+                let sum: f64 = args.iter().filter_map(Value::as_f64).sum();
+                Ok(json!(sum))
+            })
+            .await?;
+        utils::browsing_context::navigate(&mut bidi_session, context.clone(), url).await?;
+        let result = utils::script::evaluate_string(
+            &mut bidi_session,
+            &context,
+            "window.add(1, 2, 3).then(String)",
+        )
+        .await?;
+
+        exposed.remove().await?;
+        utils::session::close(&mut bidi_session).await?;
+        server.abort();
+
+        assert_eq!(result, "6");
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_error_rejects_promise() -> Result<()> {
+        let mut bidi_session = utils::session::init().await?;
+        let (url, server) = utils::axum_utils::serve_static(DEFAULT_HTML).await?;
+        let context = utils::browsing_context::get_nth_context(&mut bidi_session, 0).await?;
+
+        let exposed = bidi_session
+            .expose_function("fail", None, |_| async {
+                Err("fixture unavailable".to_string())
+            })
+            .await?;
+        utils::browsing_context::navigate(&mut bidi_session, context.clone(), url).await?;
+        let result = utils::script::evaluate_string(
+            &mut bidi_session,
+            &context,
+            "window.fail().then(() => 'resolved', (e) => e.message)",
+        )
+        .await?;
+
+        exposed.remove().await?;
+        utils::session::close(&mut bidi_session).await?;
+        server.abort();
+
+        assert_eq!(result, "fixture unavailable");
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_reports_progress() -> Result<()> {
+        let mut bidi_session = utils::session::init().await?;
+        let (url, server) = utils::axum_utils::serve_static(DEFAULT_HTML).await?;
+        let context = utils::browsing_context::get_nth_context(&mut bidi_session, 0).await?;
+
+        let steps = Arc::new(Mutex::new(Vec::new()));
+        let steps_clone = steps.clone();
+        let exposed = bidi_session
+            .expose_function("report", Some(vec![context.clone()]), move |args| {
+                let steps = steps_clone.clone();
+                async move {
+                    steps.lock().await.extend(args);
+                    Ok(Value::Null)
+                }
+            })
+            .await?;
+        utils::browsing_context::navigate(&mut bidi_session, context.clone(), url).await?;
+        utils::script::evaluate(
+            &mut bidi_session,
+            &context,
+            "(async () => { await window.report('setup'); await window.report({ done: true }); })()",
+        )
+        .await?;
+
+        exposed.remove().await?;
+        utils::session::close(&mut bidi_session).await?;
+        server.abort();
+
+        assert_eq!(
+            *steps.lock().await,
+            vec![json!("setup"), json!({"done": true})]
+        );
+
+        Ok(())
+    }
+}
@@ -0,0 +1,141 @@
+use std::time::{Duration, Instant};
+
+use anyhow::Result;
+use axum::Router;
+use axum::response::Html;
+use axum::routing::get;
+use webdriverbidi::helpers::faults::{Fault, FaultInjector, FaultRule};
+use webdriverbidi::model::network::{UrlPattern, UrlPatternPattern};
+
+mod utils;
+
+async fn serve_pages() -> Result<(String, tokio::task::JoinHandle<()>)> {
+    let app = Router::new()
+        .route("/page", get(|| async { Html("<p>page</p>") }))
+        .route("/api", get(|| async { "api" }));
+    utils::axum_utils::serve_router(app).await
+}
+
+fn api_pattern() -> UrlPattern {
+    UrlPattern::UrlPatternPattern(UrlPatternPattern::builder().pathname("/api").build())
+}
+
+mod fault_rule {
+    use super::*;
+
+    #[test]
+    fn test_matches_conditions() {
+        let rule = FaultRule::new(Fault::Fail)
+            .pattern(api_pattern())
+            .method("POST")
+            .method("PUT")
+            .destination("");
+
+        assert!(rule.matches("http://localhost/api", "post", ""));
+        assert!(rule.matches("http://localhost/api", "PUT", ""));
+        assert!(!rule.matches("http://localhost/api", "GET", ""));
+        assert!(!rule.matches("http://localhost/api", "POST", "script"));
+        assert!(!rule.matches("http://localhost/other", "POST", ""));
+    }
+
+    #[test]
+    fn test_matches_any_request_by_default() {
+        let rule = FaultRule::new(Fault::Delay(Duration::from_millis(10)));
+
+        assert!(rule.matches("https://example.com/", "GET", "document"));
+        assert_eq!(rule.probability, 1.0);
+        assert_eq!(rule.clone().probability(1.5).probability, 1.0);
+        assert_eq!(rule.probability(-1.0).probability, 0.0);
+    }
+}
+
+mod fault_injector {
+    use super::*;
+
+    const FETCH: &str = "fetch('/api').then((r) => String(r.status), () => 'failed')";
+
+    #[tokio::test]
+    async fn test_injects_status_and_delay() -> Result<()> {
+        let mut bidi_session = utils::session::init().await?;
+        let (base_url, server) = serve_pages().await?;
+        let context = utils::browsing_context::get_nth_context(&mut bidi_session, 0).await?;
+        utils::browsing_context::navigate(
+            &mut bidi_session,
+            context.clone(),
+            format!("{base_url}/page"),
+        )
+        .await?;
+
+        let injector = FaultInjector::new(
+            &mut bidi_session,
+            vec![
+                FaultRule::new(Fault::Delay(Duration::from_millis(500))).pattern(api_pattern()),
+                FaultRule::new(Fault::Status(503)).pattern(api_pattern()),
+            ],
+            0,
+        )
+        .await?;
+        let start = Instant::now();
+        let status = utils::script::evaluate_string(&mut bidi_session, &context, FETCH).await?;
+        let elapsed = start.elapsed();
+        let injected = injector.injected().await;
+
+        injector.close().await?;
+        utils::session::close(&mut bidi_session).await?;
+        server.abort();
+
+        assert_eq!(status, "503");
+        assert!(elapsed >= Duration::from_millis(500));
+        let faults: Vec<Fault> = injected.into_iter().map(|f| f.fault).collect();
+        assert_eq!(
+            faults,
+            vec![Fault::Delay(Duration::from_millis(500)), Fault::Status(503)]
+        );
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_seed_replays_failures() -> Result<()> {
+        let mut bidi_session = utils::session::init().await?;
+        let (base_url, server) = serve_pages().await?;
+        let context = utils::browsing_context::get_nth_context(&mut bidi_session, 0).await?;
+        utils::browsing_context::navigate(
+            &mut bidi_session,
+            context.clone(),
+            format!("{base_url}/page"),
+        )
+        .await?;
+
+        let mut runs = Vec::new();
+        for _ in 0..2 {
+            let injector = FaultInjector::new(
+                &mut bidi_session,
+                vec![
+                    FaultRule::new(Fault::Fail)
+                        .pattern(api_pattern())
+                        .probability(0.5),
+                ],
+                42,
+            )
+            .await?;
+            let mut outcomes = Vec::new();
+            for _ in 0..10 {
+                outcomes.push(
+                    utils::script::evaluate_string(&mut bidi_session, &context, FETCH).await?,
+                );
+            }
+            injector.close().await?;
+            runs.push(outcomes);
+        }
+
+        utils::session::close(&mut bidi_session).await?;
+        server.abort();
+
+        assert_eq!(runs[0], runs[1]);
+        assert!(runs[0].iter().any(|outcome| outcome == "failed"));
+        assert!(runs[0].iter().any(|outcome| outcome == "200"));
+
+        Ok(())
+    }
+}
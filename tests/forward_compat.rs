@@ -0,0 +1,110 @@
+use serde_json::json;
+use webdriverbidi::model::browsing_context::ReadinessState;
+use webdriverbidi::model::emulation::{
+    ScreenOrientation, ScreenOrientationNatural, ScreenOrientationType,
+};
+use webdriverbidi::model::error::ErrorCode;
+use webdriverbidi::model::log::{Entry, Level};
+use webdriverbidi::model::script::{EvaluateResult, RemoteValue};
+use webdriverbidi::model::storage::GetCookiesResult;
+
+mod unknown_variants {
+    use super::*;
+
+    #[test]
+    fn test_known_value() {
+        let code: ErrorCode = serde_json::from_value(json!("no such frame")).unwrap();
+        assert_eq!(code, ErrorCode::NoSuchFrame);
+    }
+
+    #[test]
+    fn test_unknown_value_roundtrip() {
+        let code: ErrorCode = serde_json::from_value(json!("no such thing")).unwrap();
+        assert_eq!(code, ErrorCode::Unknown("no such thing".to_string()));
+        assert_eq!(serde_json::to_value(&code).unwrap(), json!("no such thing"));
+
+        let state: ReadinessState = serde_json::from_value(json!("prerendered")).unwrap();
+        assert!(matches!(state, ReadinessState::Unknown(ref value) if value == "prerendered"));
+    }
+
+    #[test]
+    fn test_unknown_value_in_event() {
+        let entry: Entry = serde_json::from_value(json!({
+            "type": "vendor",
+            "level": "trace",
+            "source": {"realm": "realm"},
+            "text": "text",
+            "timestamp": 1,
+        }))
+        .unwrap();
+
+        let Entry::GenericLogEntry(entry) = entry else {
+            panic!("expected a generic log entry, got {:?}", entry);
+        };
+        assert!(matches!(entry.base.level, Level::Unknown(ref value) if value == "trace"));
+    }
+
+    #[test]
+    fn test_unknown_remote_value_type() {
+        let result: EvaluateResult = serde_json::from_value(json!({
+            "type": "success",
+            "result": {
+                "type": "array",
+                "value": [
+                    {"type": "string", "value": "known"},
+                    {"type": "vendor", "value": {"x": 1}, "handle": "h"},
+                ],
+            },
+            "realm": "realm",
+        }))
+        .unwrap();
+
+        let EvaluateResult::EvaluateResultSuccess(success) = result else {
+            panic!("expected a successful result, got {:?}", result);
+        };
+        let RemoteValue::ArrayRemoteValue(array) = success.result else {
+            panic!("expected an array, got {:?}", success.result);
+        };
+        let Some(RemoteValue::Unknown(value)) = array.value.as_ref().and_then(|v| v.get(1)) else {
+            panic!("expected an unknown value, got {:?}", array.value);
+        };
+        assert_eq!(value.value_type, "vendor");
+        assert_eq!(value.value, Some(json!({"x": 1})));
+        assert_eq!(
+            serde_json::to_value(value).unwrap(),
+            json!({"type": "vendor", "value": {"x": 1}, "handle": "h"})
+        );
+    }
+
+    #[test]
+    fn test_unit_variants_serialize_as_strings() {
+        let orientation = ScreenOrientation {
+            natural: ScreenOrientationNatural::Landscape,
+            orientation_type: ScreenOrientationType::PortraitSecondary,
+        };
+
+        assert_eq!(
+            serde_json::to_value(&orientation).unwrap(),
+            json!({"natural": "landscape", "type": "portrait-secondary"})
+        );
+    }
+}
+
+mod additional_fields {
+    use super::*;
+
+    #[test]
+    fn test_partition_key_extensions() {
+        let result: GetCookiesResult = serde_json::from_value(json!({
+            "cookies": [],
+            "partitionKey": {"userContext": "default", "vendor:extra": true},
+        }))
+        .unwrap();
+
+        assert_eq!(result.partition_key.user_context.unwrap(), "default");
+        assert_eq!(
+            result.partition_key.extensible.get("vendor:extra"),
+            Some(&json!(true))
+        );
+    }
+}
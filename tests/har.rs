@@ -0,0 +1,257 @@
+use std::time::Duration;
+
+use anyhow::Result;
+use axum::Router;
+use axum::response::Html;
+use axum::routing::{get, post};
+use serde_json::json;
+use webdriverbidi::helpers::har::{
+    HarCache, HarContent, HarEntry, HarNameValue, HarRecorder, HarRequest, HarResponse, HarScope,
+    HarTimings, format_timestamp, query_string,
+};
+use webdriverbidi::model::browsing_context::ReadinessState;
+use webdriverbidi::model::network::FetchTimingInfo;
+
+mod utils;
+
+const TIMEOUT: Duration = Duration::from_secs(10);
+
+async fn serve_pages() -> Result<(String, tokio::task::JoinHandle<()>)> {
+    let app = Router::new()
+        .route(
+            "/page",
+            get(|| async {
+                Html(
+                    "<script>fetch('/api?a=1&b=2'); fetch('/submit', \
+                     { method: 'POST', headers: { 'Content-Type': 'text/plain' }, body: 'posted' })\
+                     </script>",
+                )
+            }),
+        )
+        .route("/api", get(|| async { "api" }))
+        .route("/submit", post(|| async { "submitted" }));
+    utils::axum_utils::serve_router(app).await
+}
+
+mod har_format {
+    use super::*;
+
+    #[test]
+    fn test_format_timestamp() {
+        assert_eq!(format_timestamp(0), "1970-01-01T00:00:00.000Z");
+        assert_eq!(
+            format_timestamp(951_782_400_000),
+            "2000-02-29T00:00:00.000Z"
+        );
+        assert_eq!(
+            format_timestamp(1_700_000_000_123),
+            "2023-11-14T22:13:20.123Z"
+        );
+    }
+
+    #[test]
+    fn test_query_string() {
+        assert_eq!(
+            query_string("http://localhost/api?a=1&b=&c#hash"),
+            vec![
+                HarNameValue {
+                    name: "a".to_string(),
+                    value: "1".to_string(),
+                },
+                HarNameValue {
+                    name: "b".to_string(),
+                    value: String::new(),
+                },
+                HarNameValue {
+                    name: "c".to_string(),
+                    value: String::new(),
+                },
+            ]
+        );
+        assert!(query_string("http://localhost/api").is_empty());
+    }
+
+    #[test]
+    fn test_timings() -> Result<()> {
+        let timings: FetchTimingInfo = serde_json::from_value(json!({
+            "timeOrigin": 0, "requestTime": 0, "redirectStart": 0, "redirectEnd": 0,
+            "fetchStart": 1, "dnsStart": 2, "dnsEnd": 4, "connectStart": 4,
+            "connectEnd": 10, "tlsStart": 6, "requestStart": 10, "responseStart": 25,
+            "responseEnd": 30,
+        }))?;
+        let timings = HarTimings::from(&timings);
+
+        assert_eq!(timings.dns, 2.0);
+        assert_eq!(timings.connect, 6.0);
+        assert_eq!(timings.ssl, 4.0);
+        assert_eq!(timings.wait, 15.0);
+        assert_eq!(timings.receive, 5.0);
+        assert_eq!(timings.blocked, -1.0);
+        assert_eq!(timings.total(), 28.0);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_serialize_entry() -> Result<()> {
+        let entry = HarEntry {
+            started_date_time: format_timestamp(0),
+            time: 0.0,
+            request: HarRequest {
+                method: "GET".to_string(),
+                url: "http://localhost/".to_string(),
+                http_version: String::new(),
+                cookies: Vec::new(),
+                headers: Vec::new(),
+                query_string: Vec::new(),
+                post_data: None,
+                headers_size: -1,
+                body_size: 0,
+            },
+            response: HarResponse {
+                status: 0,
+                status_text: String::new(),
+                http_version: String::new(),
+                cookies: Vec::new(),
+                headers: Vec::new(),
+                content: HarContent {
+                    size: 0,
+                    mime_type: String::new(),
+                    text: None,
+                    encoding: None,
+                },
+                redirect_url: String::new(),
+                headers_size: -1,
+                body_size: -1,
+                error: Some("net::ERR_FAILED".to_string()),
+            },
+            cache: HarCache::default(),
+            timings: HarTimings {
+                blocked: -1.0,
+                dns: -1.0,
+                connect: -1.0,
+                send: 0.0,
+                wait: 0.0,
+                receive: 0.0,
+                ssl: -1.0,
+            },
+            comment: None,
+        };
+        let value = serde_json::to_value(&entry)?;
+
+        assert_eq!(value["startedDateTime"], "1970-01-01T00:00:00.000Z");
+        assert_eq!(value["request"]["httpVersion"], "");
+        assert_eq!(value["request"]["queryString"], json!([]));
+        assert_eq!(value["response"]["redirectURL"], "");
+        assert_eq!(value["response"]["_error"], "net::ERR_FAILED");
+        assert_eq!(value["cache"], json!({}));
+        assert!(value.get("comment").is_none());
+
+        let entry: HarEntry = serde_json::from_value(value)?;
+        assert_eq!(entry.response.error.as_deref(), Some("net::ERR_FAILED"));
+
+        Ok(())
+    }
+}
+
+mod har_recorder {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_record_context_with_bodies() -> Result<()> {
+        let mut bidi_session = utils::session::init().await?;
+        let (base_url, server) = serve_pages().await?;
+        let context = utils::browsing_context::get_nth_context(&mut bidi_session, 0).await?;
+
+        let recorder = HarRecorder::new(
+            &mut bidi_session,
+            HarScope::Contexts(vec![context.clone()]),
+            Some(1024 * 1024),
+        )
+        .await?;
+        bidi_session
+            .navigate_and_wait(
+                context.clone(),
+                format!("{base_url}/page"),
+                ReadinessState::Complete,
+                TIMEOUT,
+            )
+            .await?;
+        // Give the fetches started by the page the time to complete
+        let api_url = format!("{base_url}/api?a=1&b=2");
+        let submit_url = format!("{base_url}/submit");
+        let mut har = recorder.har().await;
+        for _ in 0..50 {
+            let recorded = |url: &str| har.log.entries.iter().any(|entry| entry.request.url == url);
+            if recorded(&api_url) && recorded(&submit_url) {
+                break;
+            }
+            tokio::time::sleep(Duration::from_millis(100)).await;
+            har = recorder.har().await;
+        }
+
+        recorder.close().await?;
+        utils::session::close(&mut bidi_session).await?;
+        server.abort();
+
+        assert_eq!(har.log.version, "1.2");
+        let page = har
+            .log
+            .entries
+            .iter()
+            .find(|entry| entry.request.url == format!("{base_url}/page"))
+            .expect("the page entry");
+        assert_eq!(page.response.status, 200);
+
+        let api = har
+            .log
+            .entries
+            .iter()
+            .find(|entry| entry.request.url == api_url)
+            .expect("the fetch entry");
+        assert_eq!(api.response.status, 200);
+        assert_eq!(api.response.content.text.as_deref(), Some("api"));
+        assert_eq!(api.request.query_string.len(), 2);
+        assert!(api.request.post_data.is_none());
+
+        let submit = har
+            .log
+            .entries
+            .iter()
+            .find(|entry| entry.request.url == submit_url)
+            .expect("the post entry");
+        let post_data = submit.request.post_data.as_ref().expect("the post data");
+        assert_eq!(post_data.mime_type, "text/plain");
+        assert_eq!(post_data.text, "posted");
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_other_contexts_are_not_recorded() -> Result<()> {
+        let mut bidi_session = utils::session::init().await?;
+        let (base_url, server) = serve_pages().await?;
+        let context = utils::browsing_context::get_nth_context(&mut bidi_session, 0).await?;
+        let other = utils::browsing_context::new_tab(&mut bidi_session).await?;
+
+        let recorder =
+            HarRecorder::new(&mut bidi_session, HarScope::Contexts(vec![context]), None).await?;
+        bidi_session
+            .navigate_and_wait(
+                other,
+                format!("{base_url}/page"),
+                ReadinessState::Complete,
+                TIMEOUT,
+            )
+            .await?;
+        let har = recorder.har().await;
+
+        recorder.close().await?;
+        utils::session::close(&mut bidi_session).await?;
+        server.abort();
+
+        assert!(har.log.entries.is_empty());
+
+        Ok(())
+    }
+}
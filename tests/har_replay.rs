@@ -0,0 +1,187 @@
+use anyhow::Result;
+use axum::Router;
+use axum::response::Html;
+use axum::routing::get;
+use serde_json::{Value, json};
+use webdriverbidi::helpers::har::{Har, HarEntry};
+use webdriverbidi::helpers::har_replay::{HarReplay, HarReplayOptions, NotFound, UrlMatch};
+
+mod utils;
+
+async fn serve_pages() -> Result<(String, tokio::task::JoinHandle<()>)> {
+    let app = Router::new()
+        .route("/page", get(|| async { Html("<p>page</p>") }))
+        .route("/api", get(|| async { "live" }))
+        .route("/other", get(|| async { "other" }));
+    utils::axum_utils::serve_router(app).await
+}
+
+fn entry(method: &str, url: &str, status: u64, text: &str) -> Value {
+    json!({
+        "startedDateTime": "2024-01-01T00:00:00.000Z",
+        "time": 1.0,
+        "request": {
+            "method": method,
+            "url": url,
+            "httpVersion": "http/1.1",
+            "cookies": [],
+            "headers": [],
+            "queryString": [],
+            "headersSize": -1,
+            "bodySize": 0,
+        },
+        "response": {
+            "status": status,
+            "statusText": "OK",
+            "httpVersion": "http/1.1",
+            "cookies": [],
+            "headers": [
+                {"name": "Content-Type", "value": "text/plain"},
+                {"name": "Content-Encoding", "value": "gzip"},
+            ],
+            "content": {"size": text.len(), "mimeType": "text/plain", "text": text},
+            "redirectURL": "",
+            "headersSize": -1,
+            "bodySize": -1,
+        },
+        "cache": {},
+        "timings": {"send": 0, "wait": 1, "receive": 0},
+    })
+}
+
+fn har(entries: Vec<Value>) -> Result<Har> {
+    Ok(serde_json::from_value(json!({
+        "log": {
+            "version": "1.2",
+            "creator": {"name": "test", "version": "1"},
+            "entries": entries,
+        }
+    }))?)
+}
+
+mod matching {
+    use super::*;
+
+    #[test]
+    fn test_exact_url_and_method() -> Result<()> {
+        let entry: HarEntry = serde_json::from_value(entry("GET", "http://a/api?x=1", 200, ""))?;
+        let options = HarReplayOptions::new();
+
+        assert!(options.matches(&entry, "GET", "http://a/api?x=1"));
+        assert!(options.matches(&entry, "get", "http://a/api?x=1#top"));
+        assert!(!options.matches(&entry, "POST", "http://a/api?x=1"));
+        assert!(!options.matches(&entry, "GET", "http://a/api?x=2"));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_ignore_query_and_method() -> Result<()> {
+        let entry: HarEntry = serde_json::from_value(entry("GET", "http://a/api?x=1", 200, ""))?;
+        let options = HarReplayOptions::new()
+            .url_match(UrlMatch::IgnoreQuery)
+            .match_method(false);
+
+        assert!(options.matches(&entry, "POST", "http://a/api?x=2"));
+        assert!(options.matches(&entry, "GET", "http://a/api"));
+        assert!(!options.matches(&entry, "GET", "http://a/other?x=1"));
+
+        Ok(())
+    }
+}
+
+mod har_replay {
+    use super::*;
+
+    const FETCH: &str = "(url) => fetch(url).then((r) => r.text(), () => 'failed')";
+
+    async fn fetch(
+        session: &mut webdriverbidi::session::WebDriverBiDiSession,
+        context: &webdriverbidi::model::browsing_context::BrowsingContext,
+        path: &str,
+    ) -> Result<String> {
+        let expression = format!("({FETCH})('{path}')");
+        utils::script::evaluate_string(session, context, &expression).await
+    }
+
+    #[tokio::test]
+    async fn test_serves_recorded_responses_in_order() -> Result<()> {
+        let mut bidi_session = utils::session::init().await?;
+        let (base_url, server) = serve_pages().await?;
+        let context = utils::browsing_context::get_nth_context(&mut bidi_session, 0).await?;
+        utils::browsing_context::navigate(
+            &mut bidi_session,
+            context.clone(),
+            format!("{base_url}/page"),
+        )
+        .await?;
+
+        let api = format!("{base_url}/api");
+        let replay = HarReplay::new(
+            &mut bidi_session,
+            har(vec![
+                entry("GET", &api, 200, "first"),
+                entry("GET", &api, 200, "second"),
+                entry("GET", &format!("{base_url}/unused"), 200, "unused"),
+            ])?,
+            HarReplayOptions::new(),
+        )
+        .await?;
+        let first = fetch(&mut bidi_session, &context, "/api").await?;
+        let second = fetch(&mut bidi_session, &context, "/api").await?;
+        let third = fetch(&mut bidi_session, &context, "/api").await?;
+        let unmatched = fetch(&mut bidi_session, &context, "/other").await?;
+        let unused = replay.unused().await;
+
+        replay.close().await?;
+        utils::session::close(&mut bidi_session).await?;
+        server.abort();
+
+        assert_eq!(first, "first");
+        assert_eq!(second, "second");
+        assert_eq!(third, "second");
+        assert_eq!(unmatched, "other");
+        assert_eq!(unused.len(), 1);
+        assert_eq!(unused[0].request.url, format!("{base_url}/unused"));
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_aborts_unmatched_requests() -> Result<()> {
+        let mut bidi_session = utils::session::init().await?;
+        let (base_url, server) = serve_pages().await?;
+        let context = utils::browsing_context::get_nth_context(&mut bidi_session, 0).await?;
+        utils::browsing_context::navigate(
+            &mut bidi_session,
+            context.clone(),
+            format!("{base_url}/page"),
+        )
+        .await?;
+
+        let replay = HarReplay::new(
+            &mut bidi_session,
+            har(vec![entry(
+                "GET",
+                &format!("{base_url}/api"),
+                200,
+                "recorded",
+            )])?,
+            HarReplayOptions::new().not_found(NotFound::Abort),
+        )
+        .await?;
+        let recorded = fetch(&mut bidi_session, &context, "/api").await?;
+        let unmatched = fetch(&mut bidi_session, &context, "/other").await?;
+
+        replay.close().await?;
+        let live = fetch(&mut bidi_session, &context, "/api").await?;
+        utils::session::close(&mut bidi_session).await?;
+        server.abort();
+
+        assert_eq!(recorded, "recorded");
+        assert_eq!(unmatched, "failed");
+        assert_eq!(live, "live");
+
+        Ok(())
+    }
+}
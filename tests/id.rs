@@ -0,0 +1,44 @@
+use serde_json::json;
+use webdriverbidi::model::browsing_context::{BrowsingContext, NavigateParameters};
+use webdriverbidi::model::script::{Realm, RealmInfo};
+
+mod define_id {
+    use super::*;
+
+    #[test]
+    fn test_serializes_transparently() {
+        let params = NavigateParameters::new("context".into(), "https://example.com".into(), None);
+
+        assert_eq!(
+            serde_json::to_value(&params).unwrap(),
+            json!({"context": "context", "url": "https://example.com"})
+        );
+    }
+
+    #[test]
+    fn test_deserializes_transparently() {
+        let realm_info: RealmInfo = serde_json::from_value(json!({
+            "type": "window",
+            "realm": "realm",
+            "origin": "null",
+            "context": "context",
+        }))
+        .unwrap();
+
+        let RealmInfo::WindowRealmInfo(info) = realm_info else {
+            panic!("expected a window realm, got {:?}", realm_info);
+        };
+        assert_eq!(info.base.realm, Realm::from("realm"));
+        assert_eq!(info.context, "context");
+    }
+
+    #[test]
+    fn test_conversions() {
+        let context = BrowsingContext::from("context");
+
+        assert_eq!(context.as_str(), "context");
+        assert_eq!(context.to_string(), "context");
+        assert_eq!(String::from(context.clone()), "context");
+        assert_eq!(BrowsingContext::new(String::from("context")), context);
+    }
+}
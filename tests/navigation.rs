@@ -0,0 +1,230 @@
+use std::time::Duration;
+
+use anyhow::Result;
+use axum::Router;
+use axum::http::StatusCode;
+use axum::response::{Html, Redirect};
+use axum::routing::get;
+use serde_json::json;
+use webdriverbidi::helpers::navigation::NavigationState;
+use webdriverbidi::model::browsing_context::ReadinessState;
+use webdriverbidi::model::network::{BytesValue, ResponseCompletedParameters};
+
+mod utils;
+
+const TIMEOUT: Duration = Duration::from_secs(10);
+
+async fn serve_pages() -> Result<(String, tokio::task::JoinHandle<()>)> {
+    let app = Router::new()
+        .route("/ok", get(|| async { Html("<p>ok</p>") }))
+        .route(
+            "/anchor",
+            get(|| async {
+                Html("<p>top</p><div style='height:3000px'></div><p id='anchor'></p>")
+            }),
+        )
+        .route(
+            "/missing",
+            get(|| async { (StatusCode::NOT_FOUND, Html("<p>missing</p>")) }),
+        )
+        .route("/redirect", get(|| async { Redirect::temporary("/ok") }));
+    utils::axum_utils::serve_router(app).await
+}
+
+mod network_events {
+    use super::*;
+
+    #[test]
+    fn test_deserialize_response_completed() -> Result<()> {
+        let params: ResponseCompletedParameters = serde_json::from_value(json!({
+            "context": "context",
+            "isBlocked": false,
+            "navigation": "navigation",
+            "redirectCount": 1,
+            "request": {
+                "request": "request",
+                "url": "http://example.com/",
+                "method": "GET",
+                "headers": [{"name": "Cookie", "value": {"type": "string", "value": "a=b"}}],
+                "cookies": [{
+                    "name": "a",
+                    "value": {"type": "base64", "value": "Yg=="},
+                    "domain": "example.com",
+                    "path": "/",
+                    "size": 2,
+                    "httpOnly": false,
+                    "secure": false,
+                    "sameSite": "lax",
+                }],
+                "headersSize": 0,
+                "bodySize": 0,
+                "destination": "document",
+                "initiatorType": null,
+                "timings": {
+                    "timeOrigin": 0, "requestTime": 0, "redirectStart": 0, "redirectEnd": 0,
+                    "fetchStart": 0, "dnsStart": 0, "dnsEnd": 0, "connectStart": 0,
+                    "connectEnd": 0, "tlsStart": 0, "requestStart": 0, "responseStart": 0,
+                    "responseEnd": 0,
+                },
+            },
+            "timestamp": 1,
+            "response": {
+                "url": "http://example.com/",
+                "protocol": "http/1.1",
+                "status": 200,
+                "statusText": "OK",
+                "fromCache": false,
+                "headers": [],
+                "mimeType": "text/html",
+                "bytesReceived": 0,
+                "headersSize": null,
+                "bodySize": null,
+                "content": {"size": 0},
+            },
+        }))?;
+
+        assert_eq!(params.base.redirect_count, 1);
+        assert_eq!(
+            params.base.request.headers[0].value,
+            BytesValue::string("a=b")
+        );
+        assert_eq!(
+            params.base.request.cookies[0].value,
+            BytesValue::base64("Yg==")
+        );
+
+        Ok(())
+    }
+}
+
+mod navigate_and_wait {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_reports_status_and_timings() -> Result<()> {
+        let mut bidi_session = utils::session::init().await?;
+        let (base_url, server) = serve_pages().await?;
+        let context = utils::browsing_context::get_nth_context(&mut bidi_session, 0).await?;
+
+        let outcome = bidi_session
+            .navigate_and_wait(
+                context,
+                format!("{base_url}/ok"),
+                ReadinessState::Complete,
+                TIMEOUT,
+            )
+            .await?;
+
+        utils::session::close(&mut bidi_session).await?;
+        server.abort();
+
+        assert_eq!(outcome.state, NavigationState::Completed);
+        assert_eq!(outcome.status, Some(200));
+        assert!(outcome.timings.committed.is_some());
+        assert!(outcome.timings.dom_content_loaded.is_some());
+        assert!(outcome.timings.load.is_some());
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_reports_http_error_status() -> Result<()> {
+        let mut bidi_session = utils::session::init().await?;
+        let (base_url, server) = serve_pages().await?;
+        let context = utils::browsing_context::get_nth_context(&mut bidi_session, 0).await?;
+
+        let outcome = bidi_session
+            .navigate_and_wait(
+                context,
+                format!("{base_url}/missing"),
+                ReadinessState::Interactive,
+                TIMEOUT,
+            )
+            .await?;
+
+        utils::session::close(&mut bidi_session).await?;
+        server.abort();
+
+        assert_eq!(outcome.state, NavigationState::Completed);
+        assert_eq!(outcome.status, Some(404));
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_follows_redirects() -> Result<()> {
+        let mut bidi_session = utils::session::init().await?;
+        let (base_url, server) = serve_pages().await?;
+        let context = utils::browsing_context::get_nth_context(&mut bidi_session, 0).await?;
+
+        let outcome = bidi_session
+            .navigate_and_wait(
+                context,
+                format!("{base_url}/redirect"),
+                ReadinessState::Complete,
+                TIMEOUT,
+            )
+            .await?;
+
+        utils::session::close(&mut bidi_session).await?;
+        server.abort();
+
+        assert_eq!(outcome.url, format!("{base_url}/ok"));
+        assert_eq!(outcome.redirect_count, 1);
+        assert_eq!(outcome.status, Some(200));
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_fragment_navigation() -> Result<()> {
+        let mut bidi_session = utils::session::init().await?;
+        let (base_url, server) = serve_pages().await?;
+        let context = utils::browsing_context::get_nth_context(&mut bidi_session, 0).await?;
+        let url = format!("{base_url}/anchor");
+        utils::browsing_context::navigate(&mut bidi_session, context.clone(), url.clone()).await?;
+
+        // Only fragmentNavigated is emitted, whatever the awaited readiness state
+        let outcome = bidi_session
+            .navigate_and_wait(
+                context,
+                format!("{url}#anchor"),
+                ReadinessState::Complete,
+                TIMEOUT,
+            )
+            .await?;
+
+        utils::session::close(&mut bidi_session).await?;
+        server.abort();
+
+        assert_eq!(outcome.state, NavigationState::Completed);
+        assert_eq!(outcome.url, format!("{url}#anchor"));
+        assert!(outcome.timings.committed.is_some());
+        assert!(outcome.timings.load.is_none());
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_reports_failure() -> Result<()> {
+        let mut bidi_session = utils::session::init().await?;
+        let context = utils::browsing_context::get_nth_context(&mut bidi_session, 0).await?;
+
+        // Nothing listens on the discard port
+        let outcome = bidi_session
+            .navigate_and_wait(
+                context,
+                "http://127.0.0.1:9/",
+                ReadinessState::Complete,
+                TIMEOUT,
+            )
+            .await?;
+
+        utils::session::close(&mut bidi_session).await?;
+
+        assert_eq!(outcome.state, NavigationState::Failed);
+        assert!(outcome.status.is_none());
+
+        Ok(())
+    }
+}
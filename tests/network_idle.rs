@@ -0,0 +1,158 @@
+use std::time::Duration;
+
+use anyhow::Result;
+use axum::Router;
+use axum::response::Html;
+use axum::routing::get;
+use webdriverbidi::helpers::network_idle::NetworkIdle;
+use webdriverbidi::model::network::{UrlPattern, UrlPatternPattern};
+
+mod utils;
+
+const TIMEOUT: Duration = Duration::from_secs(10);
+const QUIET_PERIOD: Duration = Duration::from_millis(300);
+
+async fn serve_pages() -> Result<(String, tokio::task::JoinHandle<()>)> {
+    let app = Router::new()
+        .route("/page", get(|| async { Html("<p>page</p>") }))
+        .route(
+            "/slow",
+            get(|| async {
+                tokio::time::sleep(Duration::from_secs(1)).await;
+                "slow"
+            }),
+        )
+        .route(
+            "/poll",
+            get(|| async {
+                tokio::time::sleep(Duration::from_secs(60)).await;
+                "poll"
+            }),
+        );
+    utils::axum_utils::serve_router(app).await
+}
+
+mod wait_for_network_idle {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_waits_for_pending_requests() -> Result<()> {
+        let mut bidi_session = utils::session::init().await?;
+        let (base_url, server) = serve_pages().await?;
+        let context = utils::browsing_context::get_nth_context(&mut bidi_session, 0).await?;
+        utils::browsing_context::navigate(
+            &mut bidi_session,
+            context.clone(),
+            format!("{base_url}/page"),
+        )
+        .await?;
+
+        let network_idle = NetworkIdle::new(&mut bidi_session, vec![]).await?;
+        utils::script::evaluate(
+            &mut bidi_session,
+            &context,
+            "fetch('/slow').then(() => { window.done = true; }); null",
+        )
+        .await?;
+        network_idle
+            .wait_for_network_idle(&context, QUIET_PERIOD, 0, TIMEOUT)
+            .await?;
+        let done =
+            utils::script::evaluate_string(&mut bidi_session, &context, "String(window.done)")
+                .await?;
+        let inflight = network_idle.inflight(&context).await;
+
+        network_idle.close().await?;
+        utils::session::close(&mut bidi_session).await?;
+        server.abort();
+
+        assert_eq!(done, "true");
+        assert!(inflight.is_empty());
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_short_requests_reset_quiet_period() -> Result<()> {
+        let mut bidi_session = utils::session::init().await?;
+        let (base_url, server) = serve_pages().await?;
+        let context = utils::browsing_context::get_nth_context(&mut bidi_session, 0).await?;
+        utils::browsing_context::navigate(
+            &mut bidi_session,
+            context.clone(),
+            format!("{base_url}/page"),
+        )
+        .await?;
+
+        // Each request is over long before the next one starts
+        let network_idle = NetworkIdle::new(&mut bidi_session, vec![]).await?;
+        utils::script::evaluate(
+            &mut bidi_session,
+            &context,
+            "window.sent = 0; \
+             const timer = setInterval(() => { \
+                 fetch('/page'); \
+                 if (++window.sent == 10) clearInterval(timer); \
+             }, 100); null",
+        )
+        .await?;
+        network_idle
+            .wait_for_network_idle(&context, QUIET_PERIOD, 0, TIMEOUT)
+            .await?;
+        let sent =
+            utils::script::evaluate_string(&mut bidi_session, &context, "String(window.sent)")
+                .await?;
+
+        network_idle.close().await?;
+        utils::session::close(&mut bidi_session).await?;
+        server.abort();
+
+        assert_eq!(sent, "10");
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_ignored_long_polling() -> Result<()> {
+        let mut bidi_session = utils::session::init().await?;
+        let (base_url, server) = serve_pages().await?;
+        let context = utils::browsing_context::get_nth_context(&mut bidi_session, 0).await?;
+        utils::browsing_context::navigate(
+            &mut bidi_session,
+            context.clone(),
+            format!("{base_url}/page"),
+        )
+        .await?;
+
+        let counted = NetworkIdle::new(&mut bidi_session, vec![]).await?;
+        let ignoring = NetworkIdle::new(
+            &mut bidi_session,
+            vec![UrlPattern::UrlPatternPattern(
+                UrlPatternPattern::builder().pathname("/poll").build(),
+            )],
+        )
+        .await?;
+        utils::script::evaluate(&mut bidi_session, &context, "fetch('/poll'); null").await?;
+
+        let ignored = ignoring
+            .wait_for_network_idle(&context, QUIET_PERIOD, 0, TIMEOUT)
+            .await;
+        let timed_out = counted
+            .wait_for_network_idle(&context, QUIET_PERIOD, 0, Duration::from_secs(1))
+            .await;
+        let tolerated = counted
+            .wait_for_network_idle(&context, QUIET_PERIOD, 1, TIMEOUT)
+            .await;
+
+        counted.close().await?;
+        ignoring.close().await?;
+        utils::session::close(&mut bidi_session).await?;
+        server.abort();
+
+        assert!(ignored.is_ok());
+        assert!(timed_out.is_err());
+        assert!(tolerated.is_ok());
+
+        Ok(())
+    }
+}
@@ -0,0 +1,116 @@
+use std::time::Duration;
+
+use anyhow::Result;
+use webdriverbidi::helpers::preload_scripts::PreloadScripts;
+use webdriverbidi::model::browser::CreateUserContextParameters;
+use webdriverbidi::model::script::AddPreloadScriptParameters;
+
+mod utils;
+
+const DEFAULT_HTML: &str = "default.html";
+const MARKER_SCRIPT: &str = "() => { window.marker = 'preloaded'; }";
+const MARKER_EXPRESSION: &str = "String(window.marker)";
+
+mod preload_scripts {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_guard_drop_removes_script() -> Result<()> {
+        let mut bidi_session = utils::session::init().await?;
+        let (url, server) = utils::axum_utils::serve_static(DEFAULT_HTML).await?;
+        let context = utils::browsing_context::get_nth_context(&mut bidi_session, 0).await?;
+        let manager = PreloadScripts::new(&bidi_session);
+
+        let guard = manager
+            .add(AddPreloadScriptParameters::builder(MARKER_SCRIPT).build())
+            .await?;
+        let listed = manager.list().await;
+        utils::browsing_context::navigate(&mut bidi_session, context.clone(), url.clone()).await?;
+        let with_script =
+            utils::script::evaluate_string(&mut bidi_session, &context, MARKER_EXPRESSION).await?;
+
+        drop(guard);
+        // The removal is spawned when the guard is dropped
+        tokio::time::sleep(Duration::from_millis(500)).await;
+        utils::browsing_context::navigate(&mut bidi_session, context.clone(), url).await?;
+        let without_script =
+            utils::script::evaluate_string(&mut bidi_session, &context, MARKER_EXPRESSION).await?;
+        let listed_after = manager.list().await;
+
+        utils::session::close(&mut bidi_session).await?;
+        server.abort();
+
+        assert_eq!(listed.len(), 1);
+        assert_eq!(listed[0].function_declaration, MARKER_SCRIPT);
+        assert_eq!(with_script, "preloaded");
+        assert_eq!(without_script, "undefined");
+        assert!(listed_after.is_empty());
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_apply_to_user_context() -> Result<()> {
+        let mut bidi_session = utils::session::init().await?;
+        let (url, server) = utils::axum_utils::serve_static(DEFAULT_HTML).await?;
+        let manager = PreloadScripts::new(&bidi_session);
+
+        let first = bidi_session
+            .browser_create_user_context(CreateUserContextParameters::new(None, None, None))
+            .await?
+            .user_context;
+        let guard = manager
+            .add(
+                AddPreloadScriptParameters::builder(MARKER_SCRIPT)
+                    .user_contexts(vec![first.clone()])
+                    .build(),
+            )
+            .await?;
+        // Registered by another component, it isn't copied
+        let other = manager
+            .add(
+                AddPreloadScriptParameters::builder("() => { window.other = true; }")
+                    .user_contexts(vec![first.clone()])
+                    .build(),
+            )
+            .await?;
+
+        let second = bidi_session
+            .browser_create_user_context(CreateUserContextParameters::new(None, None, None))
+            .await?
+            .user_context;
+        let applied = manager
+            .apply_to_user_context(std::slice::from_ref(guard.id()), &second)
+            .await?;
+        let listed = manager.list().await;
+        let listed = listed
+            .iter()
+            .find(|script| &script.id == guard.id())
+            .expect("the script is listed");
+
+        let context =
+            utils::browsing_context::new_tab_in_user_context(&mut bidi_session, second.clone())
+                .await?;
+        utils::browsing_context::navigate(&mut bidi_session, context.clone(), url).await?;
+        let marker =
+            utils::script::evaluate_string(&mut bidi_session, &context, MARKER_EXPRESSION).await?;
+        let other_marker =
+            utils::script::evaluate_string(&mut bidi_session, &context, "String(window.other)")
+                .await?;
+
+        guard.remove().await?;
+        other.remove().await?;
+        utils::browser::remove_user_context(&mut bidi_session, first).await?;
+        utils::browser::remove_user_context(&mut bidi_session, second.clone()).await?;
+        utils::session::close(&mut bidi_session).await?;
+        server.abort();
+
+        assert_eq!(applied, 1);
+        assert_eq!(listed.reapplied.len(), 1);
+        assert!(listed.user_contexts.as_ref().unwrap().contains(&second));
+        assert_eq!(marker, "preloaded");
+        assert_eq!(other_marker, "undefined");
+
+        Ok(())
+    }
+}
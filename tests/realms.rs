@@ -0,0 +1,139 @@
+use std::time::Duration;
+
+use anyhow::Result;
+use serde_json::json;
+use webdriverbidi::helpers::realms::Realms;
+use webdriverbidi::model::script::{RealmInfo, RealmType, Target};
+
+mod utils;
+
+const DEFAULT_HTML: &str = "default.html";
+
+mod realm_info {
+    use super::*;
+
+    #[test]
+    fn test_deserializes_by_type() -> Result<()> {
+        let info: RealmInfo = serde_json::from_value(json!({
+            "type": "service-worker",
+            "realm": "realm",
+            "origin": "http://example.com",
+        }))?;
+
+        assert!(matches!(
+            &info,
+            RealmInfo::ServiceWorkerRealmInfo(worker) if worker.realm_type == "service-worker"
+        ));
+        assert_eq!(info.realm_type(), RealmType::ServiceWorker);
+        assert_eq!(info.realm(), "realm");
+        assert!(matches!(info.target(), Target::RealmTarget(t) if t.realm == "realm"));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_serializes_type() -> Result<()> {
+        let value = json!({
+            "type": "dedicated-worker",
+            "realm": "worker",
+            "origin": "http://example.com",
+            "owners": ["window"],
+        });
+        let info: RealmInfo = serde_json::from_value(value.clone())?;
+
+        assert_eq!(serde_json::to_value(&info)?, value);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_unknown_type() -> Result<()> {
+        let value = json!({
+            "type": "vendor-worklet",
+            "realm": "realm",
+            "origin": "http://example.com",
+            "vendor:owner": "window",
+        });
+        let info: RealmInfo = serde_json::from_value(value.clone())?;
+
+        assert_eq!(
+            info.realm_type(),
+            RealmType::Unknown("vendor-worklet".to_string())
+        );
+        assert_eq!(info.realm(), "realm");
+        assert_eq!(serde_json::to_value(&info)?, value);
+
+        Ok(())
+    }
+}
+
+mod realms {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_window_and_dedicated_workers() -> Result<()> {
+        let mut bidi_session = utils::session::init().await?;
+        let (url, server) = utils::axum_utils::serve_static(DEFAULT_HTML).await?;
+        let context = utils::browsing_context::get_nth_context(&mut bidi_session, 0).await?;
+        utils::browsing_context::navigate(&mut bidi_session, context.clone(), url).await?;
+
+        let realms = Realms::new(&mut bidi_session).await?;
+        let window = realms.window(&context, None).await;
+        utils::script::evaluate(
+            &mut bidi_session,
+            &context,
+            "window.worker = new Worker(URL.createObjectURL(new Blob(['setInterval(() => {}, 1000)'])))",
+        )
+        .await?;
+        let worker = realms
+            .wait_for(
+                |info| matches!(info, RealmInfo::DedicatedWorkerRealmInfo(_)),
+                Duration::from_secs(5),
+            )
+            .await?;
+        let workers = realms.dedicated_workers(&context).await;
+
+        realms.close().await?;
+        utils::session::close(&mut bidi_session).await?;
+        server.abort();
+
+        assert!(window.is_some());
+        assert_eq!(workers.len(), 1);
+        assert_eq!(workers[0].realm(), worker.realm());
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_sandbox_realm_is_tracked() -> Result<()> {
+        let mut bidi_session = utils::session::init().await?;
+        let context = utils::browsing_context::get_nth_context(&mut bidi_session, 0).await?;
+        let realms = Realms::new(&mut bidi_session).await?;
+
+        let params = webdriverbidi::model::script::EvaluateParameters::builder(
+            "1",
+            Target::ContextTarget(
+                webdriverbidi::model::script::ContextTarget::builder(context.clone())
+                    .sandbox("isolated")
+                    .build(),
+            ),
+            false,
+        )
+        .build();
+        bidi_session.script_evaluate(params).await?;
+        let sandbox = realms
+            .wait_for(
+                |info| matches!(info, RealmInfo::WindowRealmInfo(w) if w.sandbox.as_deref() == Some("isolated")),
+                Duration::from_secs(5),
+            )
+            .await?;
+        let target = realms.window_target(&context, Some("isolated")).await;
+
+        realms.close().await?;
+        utils::session::close(&mut bidi_session).await?;
+
+        assert!(matches!(target, Some(Target::RealmTarget(t)) if &t.realm == sandbox.realm()));
+
+        Ok(())
+    }
+}
@@ -0,0 +1,227 @@
+use anyhow::Result;
+use axum::Router;
+use axum::response::Html;
+use axum::routing::get;
+use serde_json::json;
+use webdriverbidi::helpers::routes::{ContinueOverrides, Fulfillment, RouteAction};
+use webdriverbidi::model::network::{
+    AddInterceptParameters, BytesValue, InterceptPhase, UrlPattern, UrlPatternString,
+};
+
+mod utils;
+
+const FETCH_API: &str = "fetch('/api').then((r) => r.text(), () => 'failed')";
+
+async fn serve_pages() -> Result<(String, tokio::task::JoinHandle<()>)> {
+    let app = Router::new()
+        .route("/page", get(|| async { Html("<p>page</p>") }))
+        .route("/api", get(|| async { "api" }))
+        .route("/other", get(|| async { "other" }));
+    utils::axum_utils::serve_router(app).await
+}
+
+fn api_pattern(base_url: &str) -> UrlPattern {
+    UrlPattern::UrlPatternString(UrlPatternString::new(format!("{base_url}/api")))
+}
+
+mod route_actions {
+    use super::*;
+
+    #[test]
+    fn test_serialize_intercept_url_patterns() -> Result<()> {
+        let params = AddInterceptParameters::builder(vec![InterceptPhase::BeforeRequestSent])
+            .url_patterns(vec![api_pattern("http://localhost")])
+            .build();
+
+        assert_eq!(
+            serde_json::to_value(params)?,
+            json!({
+                "phases": ["beforeRequestSent"],
+                "urlPatterns": [{"type": "string", "pattern": "http://localhost/api"}],
+            })
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_default_action_continues_unchanged() {
+        match RouteAction::default() {
+            RouteAction::Continue(overrides) => {
+                assert!(overrides.url.is_none());
+                assert!(overrides.method.is_none());
+                assert!(overrides.headers.is_none());
+                assert!(overrides.body.is_none());
+            }
+            action => panic!("Expected a continue action, got {action:?}"),
+        }
+    }
+
+    #[test]
+    fn test_fulfillment_headers() {
+        let fulfillment = Fulfillment::new(201)
+            .header("Content-Type", "text/plain")
+            .body(BytesValue::string("created"));
+
+        assert_eq!(fulfillment.status, 201);
+        assert_eq!(fulfillment.headers[0].name, "Content-Type");
+        assert_eq!(
+            fulfillment.headers[0].value,
+            BytesValue::string("text/plain")
+        );
+        assert_eq!(fulfillment.body, Some(BytesValue::string("created")));
+    }
+}
+
+mod route {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_fulfill() -> Result<()> {
+        let mut bidi_session = utils::session::init().await?;
+        let (base_url, server) = serve_pages().await?;
+        let context = utils::browsing_context::get_nth_context(&mut bidi_session, 0).await?;
+        utils::browsing_context::navigate(
+            &mut bidi_session,
+            context.clone(),
+            format!("{base_url}/page"),
+        )
+        .await?;
+
+        let guard = bidi_session
+            .route(api_pattern(&base_url), |_| async {
+                RouteAction::Fulfill(
+                    Fulfillment::new(200)
+                        .header("Content-Type", "text/plain")
+                        .body(BytesValue::string("mocked")),
+                )
+            })
+            .await?;
+        let body = utils::script::evaluate_string(&mut bidi_session, &context, FETCH_API).await?;
+
+        guard.remove().await?;
+        utils::session::close(&mut bidi_session).await?;
+        server.abort();
+
+        assert_eq!(body, "mocked");
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_continue_with_overrides() -> Result<()> {
+        let mut bidi_session = utils::session::init().await?;
+        let (base_url, server) = serve_pages().await?;
+        let context = utils::browsing_context::get_nth_context(&mut bidi_session, 0).await?;
+        utils::browsing_context::navigate(
+            &mut bidi_session,
+            context.clone(),
+            format!("{base_url}/page"),
+        )
+        .await?;
+
+        let other = format!("{base_url}/other");
+        let guard = bidi_session
+            .route(api_pattern(&base_url), move |_| {
+                let other = other.clone();
+                async move { RouteAction::Continue(ContinueOverrides::new().url(other)) }
+            })
+            .await?;
+        let body = utils::script::evaluate_string(&mut bidi_session, &context, FETCH_API).await?;
+
+        guard.remove().await?;
+        utils::session::close(&mut bidi_session).await?;
+        server.abort();
+
+        assert_eq!(body, "other");
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_abort() -> Result<()> {
+        let mut bidi_session = utils::session::init().await?;
+        let (base_url, server) = serve_pages().await?;
+        let context = utils::browsing_context::get_nth_context(&mut bidi_session, 0).await?;
+        utils::browsing_context::navigate(
+            &mut bidi_session,
+            context.clone(),
+            format!("{base_url}/page"),
+        )
+        .await?;
+
+        let guard = bidi_session
+            .route(api_pattern(&base_url), |_| async { RouteAction::Abort })
+            .await?;
+        let body = utils::script::evaluate_string(&mut bidi_session, &context, FETCH_API).await?;
+
+        guard.remove().await?;
+        utils::session::close(&mut bidi_session).await?;
+        server.abort();
+
+        assert_eq!(body, "failed");
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_removed_route_lets_requests_through() -> Result<()> {
+        let mut bidi_session = utils::session::init().await?;
+        let (base_url, server) = serve_pages().await?;
+        let context = utils::browsing_context::get_nth_context(&mut bidi_session, 0).await?;
+        utils::browsing_context::navigate(
+            &mut bidi_session,
+            context.clone(),
+            format!("{base_url}/page"),
+        )
+        .await?;
+
+        let guard = bidi_session
+            .route(api_pattern(&base_url), |_| async { RouteAction::Abort })
+            .await?;
+        guard.remove().await?;
+        let body = utils::script::evaluate_string(&mut bidi_session, &context, FETCH_API).await?;
+
+        utils::session::close(&mut bidi_session).await?;
+        server.abort();
+
+        assert_eq!(body, "api");
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_fallback_to_previous_route() -> Result<()> {
+        let mut bidi_session = utils::session::init().await?;
+        let (base_url, server) = serve_pages().await?;
+        let context = utils::browsing_context::get_nth_context(&mut bidi_session, 0).await?;
+        utils::browsing_context::navigate(
+            &mut bidi_session,
+            context.clone(),
+            format!("{base_url}/page"),
+        )
+        .await?;
+
+        let previous = bidi_session
+            .route(api_pattern(&base_url), |_| async {
+                RouteAction::Fulfill(Fulfillment::new(200).body(BytesValue::string("previous")))
+            })
+            .await?;
+        let latest = bidi_session
+            .route(api_pattern(&base_url), |_| async { RouteAction::Fallback })
+            .await?;
+        let body = utils::script::evaluate_string(&mut bidi_session, &context, FETCH_API).await?;
+        previous.remove().await?;
+        let continued =
+            utils::script::evaluate_string(&mut bidi_session, &context, FETCH_API).await?;
+
+        latest.remove().await?;
+        utils::session::close(&mut bidi_session).await?;
+        server.abort();
+
+        assert_eq!(body, "previous");
+        assert_eq!(continued, "api");
+
+        Ok(())
+    }
+}
@@ -0,0 +1,163 @@
+use anyhow::Result;
+use axum::Router;
+use axum::response::Html;
+use axum::routing::get;
+use base64::Engine;
+use base64::engine::general_purpose::STANDARD;
+use webdriverbidi::helpers::screenshots::{self, Screenshot, ScreenshotFormat};
+use webdriverbidi::model::browsing_context::{
+    CaptureScreenshotResult, CssLocator, ImageFormat, Locator,
+};
+
+mod utils;
+
+const PNG_BYTES: &[u8] = b"\x89PNG\r\n\x1a\n\0\0\0\rIHDR";
+const JPEG_BYTES: &[u8] = b"\xff\xd8\xff\xe0\0\x10JFIF";
+
+fn result(bytes: &[u8]) -> CaptureScreenshotResult {
+    CaptureScreenshotResult {
+        data: STANDARD.encode(bytes),
+    }
+}
+
+async fn serve_pages() -> Result<(String, tokio::task::JoinHandle<()>)> {
+    let app = Router::new().route(
+        "/long",
+        get(|| async {
+            Html(
+                "<body style='margin:0'>\
+                 <div id='box' style='width:120px;height:80px;background:red'></div>\
+                 <div style='height:3000px'></div></body>",
+            )
+        }),
+    );
+    utils::axum_utils::serve_router(app).await
+}
+
+// The size of a PNG in pixels, read from its IHDR chunk
+fn png_size(bytes: &[u8]) -> (u32, u32) {
+    let width = u32::from_be_bytes([bytes[16], bytes[17], bytes[18], bytes[19]]);
+    let height = u32::from_be_bytes([bytes[20], bytes[21], bytes[22], bytes[23]]);
+    (width, height)
+}
+
+mod decode {
+    use super::*;
+
+    #[test]
+    fn test_format_from_data() -> Result<()> {
+        let png = Screenshot::decode(&result(PNG_BYTES), None)?;
+        assert_eq!(png.format, ScreenshotFormat::Png);
+        assert_eq!(png.bytes, PNG_BYTES);
+
+        // The browser fell back to PNG although JPEG was requested
+        let jpeg = ImageFormat::builder("image/jpeg").quality(0.5).build();
+        let fallback = Screenshot::decode(&result(PNG_BYTES), Some(&jpeg))?;
+        assert_eq!(fallback.format, ScreenshotFormat::Png);
+
+        let sniffed = Screenshot::decode(&result(JPEG_BYTES), None)?;
+        assert_eq!(sniffed.format, ScreenshotFormat::Jpeg);
+
+        let requested = Screenshot::decode(&result(b"unknown"), Some(&jpeg))?;
+        assert_eq!(requested.format, ScreenshotFormat::Jpeg);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_invalid_base64() {
+        let invalid = CaptureScreenshotResult {
+            data: "not base64!".to_string(),
+        };
+
+        assert!(Screenshot::decode(&invalid, None).is_err());
+    }
+}
+
+mod save {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_extension_follows_format() -> Result<()> {
+        let dir = std::env::temp_dir().join(format!("screenshots-{}", std::process::id()));
+        tokio::fs::create_dir_all(&dir).await?;
+        let jpeg = Screenshot::decode(&result(JPEG_BYTES), None)?;
+
+        let added = jpeg.save(dir.join("page")).await?;
+        let kept = jpeg.save(dir.join("page.JPEG")).await?;
+        let rejected = jpeg.save(dir.join("page.png")).await;
+        let written = tokio::fs::read(&added).await?;
+        tokio::fs::remove_dir_all(&dir).await?;
+
+        assert_eq!(added, dir.join("page.jpg"));
+        assert_eq!(kept, dir.join("page.JPEG"));
+        assert!(rejected.is_err());
+        assert_eq!(written, JPEG_BYTES);
+
+        Ok(())
+    }
+}
+
+mod capture {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_element_screenshot() -> Result<()> {
+        let mut bidi_session = utils::session::init().await?;
+        let (base_url, server) = serve_pages().await?;
+        let context = utils::browsing_context::get_nth_context(&mut bidi_session, 0).await?;
+        let url = format!("{base_url}/long");
+        utils::browsing_context::navigate(&mut bidi_session, context.clone(), url).await?;
+        let ratio: f64 =
+            utils::script::evaluate_string(&mut bidi_session, &context, "String(devicePixelRatio)")
+                .await?
+                .parse()?;
+
+        let locator = Locator::CssLocator(CssLocator::new("#box".to_string()));
+        let screenshot =
+            screenshots::capture_locator(&mut bidi_session, &context, locator, None).await?;
+        let missing = screenshots::capture_locator(
+            &mut bidi_session,
+            &context,
+            Locator::CssLocator(CssLocator::new("#missing".to_string())),
+            None,
+        )
+        .await;
+
+        utils::session::close(&mut bidi_session).await?;
+        server.abort();
+
+        assert_eq!(screenshot.format, ScreenshotFormat::Png);
+        assert_eq!(
+            png_size(&screenshot.bytes),
+            ((120.0 * ratio) as u32, (80.0 * ratio) as u32)
+        );
+        assert!(missing.is_err());
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_full_page_and_stitched() -> Result<()> {
+        let mut bidi_session = utils::session::init().await?;
+        let (base_url, server) = serve_pages().await?;
+        let context = utils::browsing_context::get_nth_context(&mut bidi_session, 0).await?;
+        let url = format!("{base_url}/long");
+        utils::browsing_context::navigate(&mut bidi_session, context.clone(), url).await?;
+
+        let full_page = screenshots::capture_full_page(&mut bidi_session, &context, None).await?;
+        let stitched =
+            screenshots::capture_full_page_stitched(&mut bidi_session, &context, None).await?;
+        let scroll =
+            utils::script::evaluate_string(&mut bidi_session, &context, "String(scrollY)").await?;
+
+        utils::session::close(&mut bidi_session).await?;
+        server.abort();
+
+        assert_eq!(png_size(&stitched.bytes), png_size(&full_page.bytes));
+        assert!(png_size(&stitched.bytes).1 > png_size(&stitched.bytes).0);
+        assert_eq!(scroll, "0");
+
+        Ok(())
+    }
+}
@@ -0,0 +1,182 @@
+use anyhow::Result;
+use axum::Router;
+use axum::response::Html;
+use axum::routing::get;
+use serde_json::json;
+use webdriverbidi::helpers::preload_scripts::PreloadScripts;
+use webdriverbidi::helpers::storage_state::{StateCookie, StorageState};
+use webdriverbidi::model::browsing_context::CloseParameters;
+use webdriverbidi::model::network::{Cookie, SameSite};
+use webdriverbidi::model::storage::PartialCookie;
+
+mod utils;
+
+async fn serve_pages() -> Result<(String, tokio::task::JoinHandle<()>)> {
+    let app = Router::new().route("/page", get(|| async { Html("<p>page</p>") }));
+    utils::axum_utils::serve_router(app).await
+}
+
+mod format {
+    use super::*;
+
+    #[test]
+    fn test_playwright_state_round_trip() -> Result<()> {
+        let json = json!({
+            "cookies": [{
+                "name": "session",
+                "value": "abc",
+                "domain": "example.com",
+                "path": "/",
+                "expires": -1.0,
+                "httpOnly": true,
+                "secure": false,
+                "sameSite": "Lax",
+            }],
+            "origins": [{
+                "origin": "https://example.com",
+                "localStorage": [{"name": "token", "value": "t"}],
+            }],
+        });
+
+        let state = StorageState::from_json(&json.to_string())?;
+        assert_eq!(state.cookies[0].expires, -1.0);
+        assert!(state.origins[0].session_storage.is_empty());
+
+        let reparsed: serde_json::Value = serde_json::from_str(&state.to_json()?)?;
+        assert_eq!(reparsed, json);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_cookie_conversions() -> Result<()> {
+        let cookie: Cookie = serde_json::from_value(json!({
+            "name": "id",
+            "value": {"type": "base64", "value": "aGk="},
+            "domain": ".example.com",
+            "path": "/app",
+            "size": 4,
+            "httpOnly": false,
+            "secure": true,
+            "sameSite": "default",
+            "expiry": 1700000000,
+        }))?;
+
+        let state_cookie = StateCookie::from(&cookie);
+        assert_eq!(state_cookie.value, "hi");
+        assert_eq!(state_cookie.same_site, None);
+        assert_eq!(state_cookie.expires, 1700000000.0);
+
+        let partial = PartialCookie::from(&state_cookie);
+        assert_eq!(
+            serde_json::to_value(&partial)?,
+            json!({
+                "name": "id",
+                "value": {"type": "string", "value": "hi"},
+                "domain": ".example.com",
+                "path": "/app",
+                "httpOnly": false,
+                "secure": true,
+                "expiry": 1700000000,
+            })
+        );
+
+        // The restored cookie gets the SameSite of the saved one
+        let mut strict = cookie;
+        strict.same_site = SameSite::Strict;
+        let state_cookie = StateCookie::from(&strict);
+        assert_eq!(state_cookie.same_site.as_deref(), Some("Strict"));
+        assert_eq!(
+            PartialCookie::from(&state_cookie).same_site,
+            PartialCookie::from(&strict).same_site
+        );
+
+        Ok(())
+    }
+}
+
+mod save_and_restore {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_restore_into_new_user_context() -> Result<()> {
+        let mut bidi_session = utils::session::init().await?;
+        let (base_url, server) = serve_pages().await?;
+        let context = utils::browsing_context::get_nth_context(&mut bidi_session, 0).await?;
+        let url = format!("{base_url}/page");
+        utils::browsing_context::navigate(&mut bidi_session, context.clone(), url.clone()).await?;
+        utils::script::evaluate(
+            &mut bidi_session,
+            &context,
+            "document.cookie = 'session=abc'; localStorage.setItem('token', 't'); \
+             sessionStorage.setItem('tab', '1')",
+        )
+        .await?;
+
+        let state =
+            StorageState::save(&mut bidi_session, None, std::slice::from_ref(&context)).await?;
+        let state = StorageState::from_json(&state.to_json()?)?;
+
+        let user_context = utils::browser::create_user_context(&mut bidi_session).await?;
+        let preload_scripts = PreloadScripts::new(&bidi_session);
+        let guard = state
+            .restore(&mut bidi_session, &preload_scripts, &user_context)
+            .await?;
+        let restored_context = utils::browsing_context::new_tab_in_user_context(
+            &mut bidi_session,
+            user_context.clone(),
+        )
+        .await?;
+        utils::browsing_context::navigate(&mut bidi_session, restored_context.clone(), url).await?;
+        let restored = utils::script::evaluate_string(
+            &mut bidi_session,
+            &restored_context,
+            "[document.cookie, localStorage.getItem('token'), sessionStorage.getItem('tab')].join('|')",
+        )
+        .await?;
+
+        guard.remove().await?;
+        utils::browser::remove_user_context(&mut bidi_session, user_context).await?;
+        utils::session::close(&mut bidi_session).await?;
+        server.abort();
+
+        assert_eq!(restored, "session=abc|t|1");
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_save_skips_unreadable_contexts() -> Result<()> {
+        let mut bidi_session = utils::session::init().await?;
+        let (base_url, server) = serve_pages().await?;
+        let context = utils::browsing_context::get_nth_context(&mut bidi_session, 0).await?;
+        utils::browsing_context::navigate(
+            &mut bidi_session,
+            context.clone(),
+            format!("{base_url}/page"),
+        )
+        .await?;
+        utils::script::evaluate(
+            &mut bidi_session,
+            &context,
+            "document.cookie = 'session=abc'; localStorage.setItem('token', 't')",
+        )
+        .await?;
+        let blank = utils::browsing_context::new_tab(&mut bidi_session).await?;
+        let closed = utils::browsing_context::new_tab(&mut bidi_session).await?;
+        bidi_session
+            .browsing_context_close(CloseParameters::new(closed.clone(), None))
+            .await?;
+
+        let state = StorageState::save(&mut bidi_session, None, &[closed, blank, context]).await?;
+
+        utils::session::close(&mut bidi_session).await?;
+        server.abort();
+
+        assert!(state.cookies.iter().any(|cookie| cookie.name == "session"));
+        assert_eq!(state.origins.len(), 1);
+        assert_eq!(state.origins[0].local_storage[0].value, "t");
+
+        Ok(())
+    }
+}
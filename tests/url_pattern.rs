@@ -0,0 +1,158 @@
+use reqwest::Url;
+use webdriverbidi::helpers::url_pattern;
+use webdriverbidi::model::network::{UrlPattern, UrlPatternPattern, UrlPatternString};
+
+fn url(url: &str) -> Url {
+    Url::parse(url).unwrap()
+}
+
+fn pattern(pattern: UrlPatternPattern) -> UrlPattern {
+    UrlPattern::UrlPatternPattern(pattern)
+}
+
+fn string(pattern: &str) -> UrlPattern {
+    UrlPattern::UrlPatternString(UrlPatternString::new(pattern.to_string()))
+}
+
+mod port {
+    use super::*;
+
+    #[test]
+    fn test_missing_port_matches_any_port() {
+        let any = pattern(UrlPatternPattern::builder().hostname("example.com").build());
+
+        assert!(url_pattern::matches(&any, &url("http://example.com/")));
+        assert!(url_pattern::matches(&any, &url("http://example.com:8080/")));
+    }
+
+    #[test]
+    fn test_port_matches_default_port() {
+        let https = pattern(UrlPatternPattern::builder().port("443").build());
+        let custom = pattern(UrlPatternPattern::builder().port("8080").build());
+
+        assert!(url_pattern::matches(&https, &url("https://example.com/")));
+        assert!(!url_pattern::matches(&https, &url("http://example.com/")));
+        assert!(url_pattern::matches(
+            &custom,
+            &url("http://example.com:8080/")
+        ));
+        assert!(!url_pattern::matches(&custom, &url("http://example.com/")));
+    }
+
+    #[test]
+    fn test_string_pattern_has_default_port() {
+        let default = string("http://example.com/");
+
+        assert!(url_pattern::matches(
+            &default,
+            &url("http://example.com:80/")
+        ));
+        assert!(!url_pattern::matches(
+            &default,
+            &url("http://example.com:8080/")
+        ));
+    }
+}
+
+mod pathname {
+    use super::*;
+
+    #[test]
+    fn test_pathname_is_compared_literally() {
+        let exact = pattern(UrlPatternPattern::builder().pathname("/a").build());
+
+        assert!(url_pattern::matches(&exact, &url("http://example.com/a")));
+        assert!(url_pattern::matches(
+            &exact,
+            &url("http://example.com/a?q=1")
+        ));
+        assert!(!url_pattern::matches(
+            &exact,
+            &url("http://example.com/a/b")
+        ));
+        assert!(!url_pattern::matches(&exact, &url("http://example.com/")));
+    }
+
+    #[test]
+    fn test_pathname_without_leading_slash() {
+        let relative = pattern(UrlPatternPattern::builder().pathname("a").build());
+
+        assert!(url_pattern::matches(
+            &relative,
+            &url("http://example.com/a")
+        ));
+    }
+}
+
+mod search {
+    use super::*;
+
+    #[test]
+    fn test_search_with_or_without_question_mark() {
+        let with_mark = pattern(UrlPatternPattern::builder().search("?q=1").build());
+        let without_mark = pattern(UrlPatternPattern::builder().search("q=1").build());
+
+        for search in [with_mark, without_mark] {
+            assert!(url_pattern::matches(
+                &search,
+                &url("http://example.com/?q=1")
+            ));
+            assert!(!url_pattern::matches(
+                &search,
+                &url("http://example.com/?q=2")
+            ));
+            assert!(!url_pattern::matches(&search, &url("http://example.com/")));
+        }
+    }
+
+    #[test]
+    fn test_empty_search_matches_no_query() {
+        let empty = pattern(UrlPatternPattern::builder().search("").build());
+        let missing = pattern(UrlPatternPattern::builder().pathname("/a").build());
+
+        assert!(url_pattern::matches(&empty, &url("http://example.com/")));
+        assert!(!url_pattern::matches(
+            &empty,
+            &url("http://example.com/?q=1")
+        ));
+        assert!(url_pattern::matches(
+            &missing,
+            &url("http://example.com/a?q=1")
+        ));
+    }
+
+    #[test]
+    fn test_string_pattern_without_query() {
+        let without_query = string("https://a/b");
+        let with_query = string("https://a/b?x=1");
+
+        assert!(url_pattern::matches(&without_query, &url("https://a/b")));
+        assert!(!url_pattern::matches(
+            &without_query,
+            &url("https://a/b?x=1")
+        ));
+        assert!(url_pattern::matches(&with_query, &url("https://a/b?x=1")));
+        assert!(!url_pattern::matches(&with_query, &url("https://a/b")));
+    }
+}
+
+mod components {
+    use super::*;
+
+    #[test]
+    fn test_protocol_and_hostname_ignore_case() {
+        let pattern = pattern(
+            UrlPatternPattern::builder()
+                .protocol("HTTPS:")
+                .hostname("Example.com")
+                .build(),
+        );
+
+        assert!(url_pattern::matches(&pattern, &url("https://example.com/")));
+        assert!(!url_pattern::matches(&pattern, &url("http://example.com/")));
+        assert!(!url_pattern::matches(
+            &pattern,
+            &url("https://www.example.com/")
+        ));
+    }
+}
@@ -0,0 +1,84 @@
+use std::time::Duration;
+
+use anyhow::Result;
+use webdriverbidi::helpers::user_contexts::{UserContextGuard, UserContextPool};
+use webdriverbidi::model::browser::CreateUserContextParameters;
+
+mod utils;
+
+const DEFAULT_HTML: &str = "default.html";
+
+mod guard {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_drop_removes_user_context() -> Result<()> {
+        let mut bidi_session = utils::session::init().await?;
+        let guard = UserContextGuard::new(
+            &bidi_session,
+            CreateUserContextParameters::new(Some(true), None, None),
+        )
+        .await?;
+        let user_context = guard.user_context().clone();
+        guard.new_tab().await?;
+        let created = utils::browser::get_user_context_ids(&mut bidi_session).await?;
+
+        drop(guard);
+        // The removal is spawned when the guard is dropped, poll until it's done
+        let deadline = tokio::time::Instant::now() + Duration::from_secs(10);
+        let mut remaining = utils::browser::get_user_context_ids(&mut bidi_session).await?;
+        while remaining.contains(&user_context) && tokio::time::Instant::now() < deadline {
+            tokio::time::sleep(Duration::from_millis(100)).await;
+            remaining = utils::browser::get_user_context_ids(&mut bidi_session).await?;
+        }
+
+        utils::session::close(&mut bidi_session).await?;
+
+        assert!(created.contains(&user_context));
+        assert!(!remaining.contains(&user_context));
+
+        Ok(())
+    }
+}
+
+mod pool {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_reuse_clears_cookies() -> Result<()> {
+        let mut bidi_session = utils::session::init().await?;
+        let (url, server) = utils::axum_utils::serve_static(DEFAULT_HTML).await?;
+        let pool = UserContextPool::new(
+            &bidi_session,
+            CreateUserContextParameters::new(None, None, None),
+        );
+
+        let guard = pool.acquire().await?;
+        let first = guard.user_context().clone();
+        let context = guard.new_tab().await?;
+        utils::browsing_context::navigate(&mut bidi_session, context.clone(), url.clone()).await?;
+        utils::script::evaluate(&mut bidi_session, &context, "document.cookie = 'id=1'").await?;
+        guard.remove().await?;
+        let idle = pool.idle().await;
+
+        let guard = pool.acquire().await?;
+        let second = guard.user_context().clone();
+        let context = guard.new_tab().await?;
+        utils::browsing_context::navigate(&mut bidi_session, context.clone(), url).await?;
+        let cookies =
+            utils::script::evaluate_string(&mut bidi_session, &context, "document.cookie").await?;
+
+        guard.remove().await?;
+        pool.close().await?;
+        let remaining = utils::browser::get_user_context_ids(&mut bidi_session).await?;
+        utils::session::close(&mut bidi_session).await?;
+        server.abort();
+
+        assert_eq!(idle, 1);
+        assert_eq!(first, second);
+        assert_eq!(cookies, "");
+        assert!(!remaining.contains(&first));
+
+        Ok(())
+    }
+}
@@ -17,9 +17,10 @@ use tokio::task::JoinHandle;
 use tower_http::services::ServeDir;
 // --------------------------------------------------
 
-use webdriverbidi::model::browser::ClientWindowInfo;
+use webdriverbidi::model::browser::{ClientWindowInfo, UserContext};
 use webdriverbidi::model::browser::{CreateUserContextParameters, RemoveUserContextParameters};
 use webdriverbidi::model::browsing_context::{
+    BrowsingContext,
     CreateParameters,
     CreateType,
     GetTreeParameters,
@@ -29,7 +30,8 @@ use webdriverbidi::model::browsing_context::{
 };
 use webdriverbidi::model::common::EmptyParams;
 use webdriverbidi::model::script::{
-    CallFunctionParameters, ContextTarget, LocalValue, PrimitiveProtocolValue, StringValue, Target,
+    CallFunctionParameters, ContextTarget, EvaluateParameters, LocalValue, PrimitiveProtocolValue,
+    StringValue, Target,
 };
 use webdriverbidi::model::script::{
     EvaluateResult,
@@ -75,7 +77,7 @@ pub mod browser {
     /// Return the Ids of the current user contexts.
     pub async fn get_user_context_ids(
         bidi_session: &mut WebDriverBiDiSession,
-    ) -> Result<Vec<String>> {
+    ) -> Result<Vec<UserContext>> {
         let user_contexts = bidi_session
             .browser_get_user_contexts(EmptyParams::new())
             .await?
@@ -90,7 +92,9 @@ pub mod browser {
     }
 
     /// Create a user context.
-    pub async fn create_user_context(bidi_session: &mut WebDriverBiDiSession) -> Result<String> {
+    pub async fn create_user_context(
+        bidi_session: &mut WebDriverBiDiSession,
+    ) -> Result<UserContext> {
         let user_context = bidi_session
             .browser_create_user_context(CreateUserContextParameters::new(None, None, None))
             .await?
@@ -114,10 +118,10 @@ pub mod browser {
     /// Remove a user context.
     pub async fn remove_user_context(
         bidi_session: &mut WebDriverBiDiSession,
-        user_context: String,
+        user_context: UserContext,
     ) -> Result<()> {
         bidi_session
-            .browser_remove_user_context(RemoveUserContextParameters::new(user_context.clone()))
+            .browser_remove_user_context(RemoveUserContextParameters::new(user_context))
             .await?;
 
         Ok(())
@@ -130,8 +134,8 @@ pub mod browsing_context {
     /// Open a new tab in the specified user context.
     pub async fn new_tab_in_user_context(
         session: &mut WebDriverBiDiSession,
-        user_context: String,
-    ) -> Result<String> {
+        user_context: UserContext,
+    ) -> Result<BrowsingContext> {
         let create_params = CreateParameters::new(CreateType::Tab, None, None, Some(user_context));
         let context = session
             .browsing_context_create(create_params)
@@ -144,7 +148,7 @@ pub mod browsing_context {
     /// Navigate to the specified URL and wait for the document to completely load.
     pub async fn navigate(
         session: &mut WebDriverBiDiSession,
-        context: String,
+        context: BrowsingContext,
         url: String,
     ) -> Result<()> {
         let navigate_params = NavigateParameters::new(context, url, Some(ReadinessState::Complete));
@@ -154,7 +158,7 @@ pub mod browsing_context {
     }
 
     /// Open a new window.
-    pub async fn new_window(session: &mut WebDriverBiDiSession) -> Result<String> {
+    pub async fn new_window(session: &mut WebDriverBiDiSession) -> Result<BrowsingContext> {
         let create_params = CreateParameters::new(CreateType::Window, None, None, None);
         let context = session
             .browsing_context_create(create_params)
@@ -167,14 +171,14 @@ pub mod browsing_context {
     pub async fn get_nth_context(
         session: &mut WebDriverBiDiSession,
         index: usize,
-    ) -> Result<String> {
+    ) -> Result<BrowsingContext> {
         let get_tree_params = GetTreeParameters::new(None, None);
         let get_tree_rslt = session.browsing_context_get_tree(get_tree_params).await?;
         Ok(get_tree_rslt.contexts[index].context.clone())
     }
 
     /// Open a new tab.
-    pub async fn new_tab(session: &mut WebDriverBiDiSession) -> Result<String> {
+    pub async fn new_tab(session: &mut WebDriverBiDiSession) -> Result<BrowsingContext> {
         let create_params = CreateParameters::new(CreateType::Tab, None, None, None);
         let context = session
             .browsing_context_create(create_params)
@@ -184,8 +188,48 @@ pub mod browsing_context {
     }
 }
 
+pub mod script {
+    use super::*;
+
+    /// Evaluate the expression in the context and return the resulting remote value.
+    pub async fn evaluate(
+        session: &mut WebDriverBiDiSession,
+        context: &BrowsingContext,
+        expression: &str,
+    ) -> Result<RemoteValue> {
+        let params =
+            EvaluateParameters::builder(expression, target_context(context.as_str()), true).build();
+        match session.script_evaluate(params).await? {
+            EvaluateResult::EvaluateResultSuccess(eval_rslt_success) => {
+                Ok(eval_rslt_success.result)
+            }
+            eval_rslt => Err(anyhow::anyhow!(
+                "Received unexpected EvaluateResult: {:?}",
+                eval_rslt
+            )),
+        }
+    }
+
+    /// Evaluate the expression in the context and return the resulting string.
+    pub async fn evaluate_string(
+        session: &mut WebDriverBiDiSession,
+        context: &BrowsingContext,
+        expression: &str,
+    ) -> Result<String> {
+        match evaluate(session, context, expression).await? {
+            RemoteValue::PrimitiveProtocolValue(PrimitiveProtocolValue::StringValue(
+                string_value,
+            )) => Ok(string_value.value),
+            remote_val => Err(anyhow::anyhow!(
+                "Expected a string value, actual remote value: {:?}",
+                remote_val
+            )),
+        }
+    }
+}
+
 fn target_context(context: &str) -> Target {
-    Target::ContextTarget(ContextTarget::new(context.to_string(), None))
+    Target::ContextTarget(ContextTarget::new(context.into(), None))
 }
 
 fn local_value(str: &str) -> LocalValue {
@@ -279,6 +323,22 @@ pub mod axum_utils {
 
         Ok((url, server_handle))
     }
+
+    /// Serve the router on a random port and return the base URL of the server.
+    pub async fn serve_router(app: Router) -> Result<(String, JoinHandle<()>)> {
+        let listener = tokio::net::TcpListener::bind(DEFAULT_ADDR).await?;
+        let addr: SocketAddr = listener.local_addr()?;
+
+        let server_handle = tokio::spawn(async move {
+            if let Err(e) = axum::serve(listener, app.into_make_service()).await {
+                eprintln!("Server error: {}", e);
+            }
+        });
+
+        debug!("Axum server running on {}", addr);
+
+        Ok((format!("http://{}", addr), server_handle))
+    }
 }
 
 // // /// Sleep for a given number of seconds.
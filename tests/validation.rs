@@ -0,0 +1,91 @@
+use serde_json::json;
+use webdriverbidi::error::CommandError;
+use webdriverbidi::validation::validate_command;
+
+mod validate_command {
+    use super::*;
+
+    #[test]
+    fn test_valid_command() {
+        let command = json!({
+            "id": 1,
+            "method": "browsingContext.captureScreenshot",
+            "params": {
+                "context": "context",
+                "clip": {"type": "box", "x": 0.0, "y": 0.0, "width": 10.0, "height": 10.0},
+            },
+        });
+
+        assert!(validate_command(&command).is_ok());
+    }
+
+    #[test]
+    fn test_negative_clip_rectangle() {
+        let command = json!({
+            "id": 1,
+            "method": "browsingContext.captureScreenshot",
+            "params": {
+                "context": "context",
+                "clip": {"type": "box", "x": 0.0, "y": 0.0, "width": -10.0, "height": 10.0},
+            },
+        });
+
+        let err = validate_command(&command).unwrap_err();
+        assert!(
+            matches!(err, CommandError::InvalidParameters(ref msg) if msg.contains("params.clip.width"))
+        );
+    }
+
+    #[test]
+    fn test_print_page_below_minimum() {
+        let command = json!({
+            "id": 1,
+            "method": "browsingContext.print",
+            "params": {"context": "context", "page": {"width": 0.01}},
+        });
+
+        let err = validate_command(&command).unwrap_err();
+        assert!(
+            matches!(err, CommandError::InvalidParameters(ref msg) if msg.contains("params.page.width"))
+        );
+    }
+
+    #[test]
+    fn test_js_uint_out_of_range() {
+        let command = json!({
+            "id": 1,
+            "method": "browsingContext.getTree",
+            "params": {"maxDepth": 9_007_199_254_740_992_u64},
+        });
+
+        let err = validate_command(&command).unwrap_err();
+        assert!(
+            matches!(err, CommandError::InvalidParameters(ref msg) if msg.contains("params.maxDepth"))
+        );
+    }
+
+    #[test]
+    fn test_empty_contexts() {
+        let command = json!({
+            "id": 1,
+            "method": "session.subscribe",
+            "params": {"events": ["log.entryAdded"], "contexts": []},
+        });
+
+        let err = validate_command(&command).unwrap_err();
+        assert!(
+            matches!(err, CommandError::InvalidParameters(ref msg) if msg.contains("params.contexts"))
+        );
+    }
+
+    #[test]
+    fn test_null_is_skipped() {
+        let command = json!({
+            "id": 1,
+            "method": "browsingContext.setViewport",
+            "params": {"context": "context", "viewport": null, "devicePixelRatio": null},
+        });
+
+        assert!(validate_command(&command).is_ok());
+    }
+}